@@ -4,7 +4,7 @@
 #
 # Usage: bash ~/.claude/hooks/test-rtk-rewrite.sh
 
-HOOK="$HOME/.claude/hooks/rtk-rewrite.sh"
+HOOK="${HOOK:-$HOME/.claude/hooks/rtk-rewrite.sh}"
 PASS=0
 FAIL=0
 TOTAL=0
@@ -115,7 +115,7 @@ test_rewrite "cargo test" \
 
 test_rewrite "npx prisma migrate" \
   "npx prisma migrate" \
-  "rtk prisma migrate"
+  "rtk npx prisma migrate"
 
 echo ""
 
@@ -197,9 +197,9 @@ test_rewrite "find (NOT rewritten — different arg format)" \
   "find . -name '*.ts'" \
   ""
 
-test_rewrite "tree (NOT rewritten — different arg format)" \
+test_rewrite "tree src/" \
   "tree src/" \
-  ""
+  "rtk tree src/"
 
 test_rewrite "wget (NOT rewritten — different arg format)" \
   "wget https://example.com/file" \
@@ -247,7 +247,39 @@ test_rewrite "pnpm vitest run --coverage" \
 
 echo ""
 
-# ---- SECTION 5: Should NOT rewrite ----
+# ---- SECTION 5: Compound commands and pipes ----
+echo "--- Compound commands and pipes ---"
+test_rewrite "cd && cargo test && git status" \
+  "cd x && cargo test && git status" \
+  "cd x && rtk cargo test && rtk git status"
+
+test_rewrite "subshell" \
+  "(cd web && pnpm list) || git diff" \
+  "(cd web && rtk pnpm list) || rtk git diff"
+
+test_rewrite "cat | head folded" \
+  "cat README.md | head -20" \
+  "rtk read README.md --max-lines 20"
+
+test_rewrite "grep | head folded" \
+  "grep TODO src | head -5" \
+  "rtk grep --max 5 TODO src"
+
+test_rewrite "grep -rn | head folded" \
+  "grep -rn foo src | head -3" \
+  "rtk grep --max 3 -rn foo src"
+
+test_rewrite "grep BRE alternation (NOT rewritten)" \
+  "grep -rn 'foo\\|bar' src | head -3" \
+  ""
+
+test_rewrite "pipe into wc (NOT rewritten)" \
+  "git status | wc -l" \
+  ""
+
+echo ""
+
+# ---- SECTION 6: Should NOT rewrite ----
 echo "--- Should NOT rewrite ---"
 test_rewrite "already rtk" \
   "rtk git status" \
@@ -281,6 +313,28 @@ test_rewrite "node (no pattern)" \
 
 echo ""
 
+# ---- SECTION 7: Incompatible rtk on PATH ----
+echo "--- Incompatible rtk on PATH ---"
+TOTAL=$((TOTAL + 1))
+STUB_DIR=$(mktemp -d)
+printf '#!/bin/sh\necho "error: unrecognized subcommand" >&2\nexit 2\n' > "$STUB_DIR/rtk"
+chmod +x "$STUB_DIR/rtk"
+stub_output=$(echo '{"tool_name":"Bash","tool_input":{"command":"git status"}}' \
+  | PATH="$STUB_DIR:$PATH" bash "$HOOK" 2>&1)
+stub_code=$?
+rm -rf "$STUB_DIR"
+if [ $stub_code -eq 0 ] && [ -z "$stub_output" ]; then
+  printf "  ${GREEN}PASS${RESET} rtk without rewrite (exit 2) ${DIM}→ exit 0, no rewrite${RESET}\n"
+  PASS=$((PASS + 1))
+else
+  printf "  ${RED}FAIL${RESET} rtk without rewrite (exit 2)\n"
+  printf "       expected: exit 0, no output\n"
+  printf "       actual:   exit %s, output: %s\n" "$stub_code" "$stub_output"
+  FAIL=$((FAIL + 1))
+fi
+
+echo ""
+
 # ---- SUMMARY ----
 echo "============================================"
 if [ $FAIL -eq 0 ]; then
@@ -0,0 +1,283 @@
+//! Output token budget (`--max-tokens N` / `display.max_tokens`).
+//!
+//! Filters that know the shape of their output build [`Sections`], so that when
+//! space runs out errors survive before failing tests, summaries and context.
+//! Everything else printed to stdout goes through [`print`], which drops lines
+//! past the budget. Either way the output ends with a footer saying what was cut.
+
+use crate::context;
+use std::sync::Mutex;
+
+/// Tokens kept free for the footer
+const FOOTER_RESERVE: usize = 30;
+
+/// Section kinds, most important first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Error,
+    Failure,
+    Summary,
+    Context,
+}
+
+impl Priority {
+    fn label(self) -> &'static str {
+        match self {
+            Priority::Error => "errors",
+            Priority::Failure => "failures",
+            Priority::Summary => "summary",
+            Priority::Context => "context",
+        }
+    }
+}
+
+/// Filter output split by priority
+#[derive(Default)]
+pub struct Sections {
+    parts: Vec<(Priority, String)>,
+}
+
+impl Sections {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a section; empty text is ignored
+    pub fn push(&mut self, priority: Priority, text: impl Into<String>) {
+        let text = text.into();
+        if !text.trim().is_empty() {
+            self.parts.push((priority, text));
+        }
+    }
+
+    /// Add the sections of `other` after these
+    pub fn append(&mut self, mut other: Sections) {
+        self.parts.append(&mut other.parts);
+    }
+
+    /// All sections in the order they were pushed, without budgeting
+    pub fn text(&self) -> String {
+        self.parts.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    /// Render in priority order, fitted to what is left of the global budget.
+    /// What was dropped is reported in the footer printed by [`finish`].
+    pub fn render(&self) -> String {
+        if limit().is_none() {
+            return self.render_within(None);
+        }
+        let Ok(mut gate) = GATE.lock() else {
+            return self.render_within(None);
+        };
+        let available = limit()
+            .unwrap_or(0)
+            .saturating_sub(FOOTER_RESERVE)
+            .saturating_sub(gate.used);
+        let (out, dropped) = self.fit(available);
+        gate.dropped.extend(dropped);
+        out
+    }
+
+    /// Render in priority order within `max_tokens` (None = everything),
+    /// ending with a footer when something was dropped
+    pub fn render_within(&self, max_tokens: Option<usize>) -> String {
+        let Some(max_tokens) = max_tokens else {
+            return self.sorted().map(|(_, text)| text.as_str()).collect();
+        };
+        let (mut out, dropped) = self.fit(max_tokens.saturating_sub(FOOTER_RESERVE));
+        if !dropped.is_empty() {
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(&footer(max_tokens, &dropped));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn sorted(&self) -> impl Iterator<Item = &(Priority, String)> {
+        let mut parts: Vec<&(Priority, String)> = self.parts.iter().collect();
+        parts.sort_by_key(|(priority, _)| *priority);
+        parts.into_iter()
+    }
+
+    /// Keep whole lines in priority order while they fit in `available` tokens.
+    /// Returns the kept text and a "section (N lines)" entry per cut section.
+    fn fit(&self, available: usize) -> (String, Vec<String>) {
+        let mut used = 0;
+        let mut out = String::new();
+        let mut dropped: Vec<(Priority, usize)> = Vec::new();
+        let mut full = false;
+
+        for (priority, text) in self.sorted() {
+            let mut cut = 0;
+            for line in text.split_inclusive('\n') {
+                let tokens = count(line);
+                if full || used + tokens > available {
+                    full = true;
+                    cut += 1;
+                } else {
+                    used += tokens;
+                    out.push_str(line);
+                }
+            }
+            if cut > 0 {
+                match dropped.iter_mut().find(|(p, _)| p == priority) {
+                    Some((_, n)) => *n += cut,
+                    None => dropped.push((*priority, cut)),
+                }
+            }
+        }
+
+        let dropped = dropped
+            .iter()
+            .map(|(p, n)| format!("{} ({})", p.label(), lines(*n)))
+            .collect();
+        (out, dropped)
+    }
+}
+
+/// Budget state for everything printed to stdout by this process
+struct Gate {
+    used: usize,
+    /// Lines dropped by [`print`]
+    cut_lines: usize,
+    /// Sections cut by [`Sections::render`]
+    dropped: Vec<String>,
+}
+
+static GATE: Mutex<Gate> = Mutex::new(Gate {
+    used: 0,
+    cut_lines: 0,
+    dropped: Vec::new(),
+});
+
+/// The active budget, if any
+pub fn limit() -> Option<usize> {
+    context::get().max_tokens
+}
+
+fn count(text: &str) -> usize {
+    context::get().tokenizer.count(text)
+}
+
+fn lines(n: usize) -> String {
+    if n == 1 {
+        "1 line".to_string()
+    } else {
+        format!("{} lines", n)
+    }
+}
+
+fn footer(max_tokens: usize, dropped: &[String]) -> String {
+    format!(
+        "[rtk: cut to --max-tokens {}; dropped {}]",
+        max_tokens,
+        dropped.join(", ")
+    )
+}
+
+/// Print to stdout, counting against the budget. Once a line does not fit,
+/// it and everything after it is dropped and reported by [`finish`].
+pub fn print(text: &str) {
+    let Some(limit) = limit() else {
+        std::print!("{}", text);
+        return;
+    };
+    let Ok(mut gate) = GATE.lock() else {
+        std::print!("{}", text);
+        return;
+    };
+
+    let available = limit.saturating_sub(FOOTER_RESERVE);
+    let mut kept = String::new();
+    for line in text.split_inclusive('\n') {
+        let tokens = count(line);
+        if gate.cut_lines > 0 || gate.used + tokens > available {
+            gate.cut_lines += 1;
+        } else {
+            gate.used += tokens;
+            kept.push_str(line);
+        }
+    }
+    std::print!("{}", kept);
+}
+
+/// Print a footer line (e.g. the `rtk show` hint) that is never cut
+pub fn print_footer(text: &str) {
+    let text = format!("{}\n", context::render(text));
+    if limit().is_some() {
+        if let Ok(mut gate) = GATE.lock() {
+            gate.used += count(&text);
+        }
+    }
+    std::print!("{}", text);
+}
+
+/// Print what redaction held back and the footer listing everything the budget
+/// cut. Called before rtk exits.
+pub fn finish() {
+    let held = context::render_held();
+    if !held.is_empty() {
+        print(&held);
+    }
+    let Some(limit) = limit() else {
+        return;
+    };
+    let Ok(gate) = GATE.lock() else {
+        return;
+    };
+    let mut dropped = gate.dropped.clone();
+    if gate.cut_lines > 0 {
+        let label = if dropped.is_empty() { "" } else { "another " };
+        dropped.push(format!("{}{}", label, lines(gate.cut_lines)));
+    }
+    if !dropped.is_empty() {
+        std::println!("{}", footer(limit, &dropped));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections() -> Sections {
+        let mut sections = Sections::new();
+        sections.push(Priority::Context, "ctx 1\nctx 2\nctx 3\n");
+        sections.push(Priority::Summary, "2 passed, 1 failed\n");
+        sections.push(Priority::Error, "error: boom\n");
+        sections.push(Priority::Failure, "");
+        sections
+    }
+
+    #[test]
+    fn test_sections_priority_order() {
+        assert_eq!(
+            sections().render_within(None),
+            "error: boom\n2 passed, 1 failed\nctx 1\nctx 2\nctx 3\n"
+        );
+    }
+
+    #[test]
+    fn test_sections_fit_budget() {
+        let body = "error: boom\n2 passed, 1 failed\n";
+        let budget = count("error: boom\n") + count("2 passed, 1 failed\n") + FOOTER_RESERVE;
+        let out = sections().render_within(Some(budget));
+        assert_eq!(
+            out,
+            format!(
+                "{}[rtk: cut to --max-tokens {}; dropped context (3 lines)]\n",
+                body, budget
+            )
+        );
+    }
+
+    #[test]
+    fn test_sections_tiny_budget_keeps_nothing_but_footer() {
+        let out = sections().render_within(Some(5));
+        assert_eq!(
+            out,
+            "[rtk: cut to --max-tokens 5; dropped errors (1 line), summary (1 line), context (3 lines)]\n"
+        );
+    }
+}
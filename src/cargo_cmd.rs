@@ -1,7 +1,16 @@
+use crate::budget::{Priority, Sections};
+use crate::delta;
+use crate::parser::{
+    self, truncate_output, BuildOutput, LintIssue, LintResult, LintSeverity, ParseResult,
+    TestFailure, TestResult,
+};
+use crate::stream::{self, StderrMode, StreamFilter};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::OsString;
 use std::process::Command;
 use std::sync::OnceLock;
@@ -25,10 +34,10 @@ pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-/// Generic cargo command runner with filtering
-fn run_cargo_filtered<F>(subcommand: &str, args: &[String], verbose: u8, filter_fn: F) -> Result<()>
+/// Generic cargo command runner with streaming filtering
+fn run_cargo_filtered<F>(subcommand: &str, args: &[String], verbose: u8, mut filter: F) -> Result<()>
 where
-    F: Fn(&str) -> String,
+    F: StreamFilter,
 {
     let timer = tracking::TimedExecution::start();
 
@@ -39,51 +48,97 @@ where
     }
 
     if verbose > 0 {
-        eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
+        render_eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
     }
 
-    let output = cmd
-        .output()
-        .with_context(|| format!("Failed to run cargo {}", subcommand))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
+    let label = format!("cargo {} {}", subcommand, args.join(" "));
 
-    let filtered = filter_fn(&raw);
-    println!("{}", filtered);
+    if parser::json_requested() && subcommand != "install" {
+        let output = cmd.output_timed().context("Failed to run cargo")?;
+        let raw = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let exit_code = output.status.code().unwrap_or(1);
+        let tool = format!("cargo {}", subcommand);
+        let json = match subcommand {
+            "test" => parser::print_json(&tool, parse_cargo_test(&raw), exit_code),
+            "clippy" => parser::print_json(&tool, parse_cargo_clippy(&raw), exit_code),
+            _ => parser::print_json(&tool, parse_cargo_build(&raw, exit_code), exit_code),
+        };
+        timer.track(
+            &label,
+            &format!("rtk cargo {} {}", subcommand, args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
 
-    timer.track(
-        &format!("cargo {} {}", subcommand, args.join(" ")),
+    if delta::requested() && matches!(subcommand, "test" | "clippy") {
+        // Needs the complete result, so no streaming
+        let output = cmd.output_timed().context("Failed to run cargo")?;
+        let raw = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report = match subcommand {
+            "test" => delta::report(&label, || parse_cargo_test(&raw)),
+            _ => delta::report(&label, || parse_cargo_clippy(&raw)),
+        };
+        let filtered = report.unwrap_or_else(|| stream::filter_all(filter, &raw));
+        render_println!("{}", filtered);
+        tee::print_hint(&label, &raw, &filtered);
+        timer.track(
+            &label,
+            &format!("rtk cargo {} {}", subcommand, args.join(" ")),
+            &raw,
+            &filtered,
+        );
+        crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
+        return Ok(());
+    }
+
+    let mut outcome = stream::run(&mut cmd, &label, &mut filter, StderrMode::Filter)?;
+    outcome.print_tee_hint();
+
+    timer.track_streamed(
+        &label,
         &format!("rtk cargo {} {}", subcommand, args.join(" ")),
-        &raw,
-        &filtered,
+        outcome.raw_bytes,
+        &outcome.filtered,
     );
 
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    outcome.exit_on_failure();
 
     Ok(())
 }
 
 fn run_build(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("build", args, verbose, filter_cargo_build)
+    run_cargo_filtered("build", args, verbose, CargoBuildFilter::default())
 }
 
 fn run_test(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("test", args, verbose, filter_cargo_test)
+    run_cargo_filtered("test", args, verbose, CargoTestFilter::default())
 }
 
 fn run_clippy(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy)
+    run_cargo_filtered("clippy", args, verbose, CargoClippyFilter::default())
 }
 
 fn run_check(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("check", args, verbose, filter_cargo_build)
+    run_cargo_filtered("check", args, verbose, CargoBuildFilter::default())
 }
 
 fn run_install(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("install", args, verbose, filter_cargo_install)
+    run_cargo_filtered(
+        "install",
+        args,
+        verbose,
+        stream::Buffered::new(filter_cargo_install),
+    )
 }
 
 /// Format crate name + version into a display string
@@ -269,95 +324,367 @@ fn filter_cargo_install(output: &str) -> String {
     result
 }
 
+/// Max error/warning blocks printed for build/check
+const MAX_BUILD_BLOCKS: usize = 15;
+
 /// Filter cargo build/check output - strip "Compiling"/"Checking" lines, keep errors + summary
+#[cfg(test)]
 fn filter_cargo_build(output: &str) -> String {
-    let mut errors: Vec<String> = Vec::new();
-    let mut warnings = 0;
-    let mut error_count = 0;
-    let mut compiled = 0;
-    let mut in_error = false;
-    let mut current_error = Vec::new();
+    stream::filter_all(CargoBuildFilter::default(), output)
+}
 
-    for line in output.lines() {
-        if line.trim_start().starts_with("Compiling") || line.trim_start().starts_with("Checking") {
-            compiled += 1;
-            continue;
+/// Streaming cargo build/check filter: error/warning blocks are printed as soon
+/// as they end, the counts come last.
+struct CargoBuildFilter {
+    compiled: usize,
+    warnings: usize,
+    error_count: usize,
+    in_error: bool,
+    current_error: Vec<String>,
+    /// The block being collected is an error, not a warning
+    current_is_error: bool,
+    /// Section of the last block returned by `take_block`
+    taken: Priority,
+    shown: usize,
+    hidden: usize,
+}
+
+impl Default for CargoBuildFilter {
+    fn default() -> Self {
+        Self {
+            compiled: 0,
+            warnings: 0,
+            error_count: 0,
+            in_error: false,
+            current_error: Vec::new(),
+            current_is_error: false,
+            taken: Priority::Context,
+            shown: 0,
+            hidden: 0,
         }
-        if line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-        {
-            continue;
+    }
+}
+
+impl CargoBuildFilter {
+    /// Close the current block; returns it if still under the display limit
+    fn take_block(&mut self) -> Option<String> {
+        if self.current_error.is_empty() {
+            return None;
         }
-        if line.trim_start().starts_with("Finished") {
-            continue;
+        let block = self.current_error.join("\n");
+        self.current_error.clear();
+        self.taken = if self.current_is_error {
+            Priority::Error
+        } else {
+            Priority::Context
+        };
+
+        if self.shown >= MAX_BUILD_BLOCKS {
+            self.hidden += 1;
+            return None;
+        }
+        self.shown += 1;
+        if self.shown == 1 {
+            Some(block)
+        } else {
+            Some(format!("\n{}", block))
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if self.current_error.len() < stream::MAX_BLOCK_LINES {
+            self.current_error.push(line.to_string());
+        }
+    }
+}
+
+impl StreamFilter for CargoBuildFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("Compiling") || trimmed.starts_with("Checking") {
+            self.compiled += 1;
+            return None;
+        }
+        if trimmed.starts_with("Downloading")
+            || trimmed.starts_with("Downloaded")
+            || trimmed.starts_with("Finished")
+        {
+            return None;
         }
 
         // Detect error/warning blocks
         if line.starts_with("error[") || line.starts_with("error:") {
             // Skip "error: aborting due to" summary lines
             if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
-            }
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
+                return None;
             }
-            error_count += 1;
-            in_error = true;
-            current_error.push(line.to_string());
+            let done = self.take_block();
+            self.error_count += 1;
+            self.in_error = true;
+            self.current_is_error = true;
+            self.push_line(line);
+            done
         } else if line.starts_with("warning:")
             && line.contains("generated")
             && line.contains("warning")
         {
             // "warning: `crate` generated N warnings" summary line
-            continue;
+            None
         } else if line.starts_with("warning:") || line.starts_with("warning[") {
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
-            }
-            warnings += 1;
-            in_error = true;
-            current_error.push(line.to_string());
-        } else if in_error {
-            if line.trim().is_empty() && current_error.len() > 3 {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
-                in_error = false;
+            let done = self.take_block();
+            self.warnings += 1;
+            self.in_error = true;
+            self.current_is_error = false;
+            self.push_line(line);
+            done
+        } else if self.in_error {
+            if line.trim().is_empty() && self.current_error.len() > 3 {
+                self.in_error = false;
+                self.take_block()
             } else {
-                current_error.push(line.to_string());
+                self.push_line(line);
+                None
             }
+        } else {
+            None
         }
     }
 
-    if !current_error.is_empty() {
-        errors.push(current_error.join("\n"));
+    fn progress(&self) -> Option<String> {
+        Some(format!(
+            "{} crates, {} errors, {} warnings",
+            self.compiled, self.error_count, self.warnings
+        ))
     }
 
-    if error_count == 0 && warnings == 0 {
-        return format!("✓ cargo build ({} crates compiled)", compiled);
+    fn finish(&mut self) -> String {
+        self.finish_sections().text().trim_end().to_string()
     }
 
-    let mut result = String::new();
-    result.push_str(&format!(
-        "cargo build: {} errors, {} warnings ({} crates)\n",
-        error_count, warnings, compiled
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+    /// Errors first, then the counts; warnings and "+N more" are detail
+    fn section(&self) -> Priority {
+        self.taken
+    }
 
-    for (i, err) in errors.iter().enumerate().take(15) {
-        result.push_str(err);
-        result.push('\n');
-        if i < errors.len() - 1 {
-            result.push('\n');
+    fn finish_sections(&mut self) -> Sections {
+        let last = self.take_block();
+        let mut sections = Sections::new();
+
+        if self.error_count == 0 && self.warnings == 0 {
+            sections.push(
+                Priority::Summary,
+                format!("✓ cargo build ({} crates compiled)\n", self.compiled),
+            );
+            return sections;
+        }
+
+        if let Some(block) = last {
+            sections.push(self.taken, format!("{}\n", block));
+        }
+        if self.hidden > 0 {
+            sections.push(
+                Priority::Context,
+                format!("\n... +{} more issues\n", self.hidden),
+            );
+        }
+        sections.push(
+            Priority::Summary,
+            format!(
+                "\n═══════════════════════════════════════\ncargo build: {} errors, {} warnings ({} crates)\n",
+                self.error_count, self.warnings, self.compiled
+            ),
+        );
+        sections
+    }
+}
+
+/// Diagnostic header ("error[E0425]: ...", "warning: ..."), not a summary line
+fn is_diagnostic_header(line: &str) -> bool {
+    (line.starts_with("error[")
+        || line.starts_with("error:")
+        || line.starts_with("warning:")
+        || line.starts_with("warning["))
+        && !line.contains("aborting due to")
+        && !line.contains("could not compile")
+        && !(line.starts_with("warning:") && line.contains("generated") && line.contains("warning"))
+}
+
+/// "Finished `dev` profile [...] target(s) in 1.23s" -> 1230
+fn parse_finished_ms(output: &str) -> Option<u64> {
+    let line = output
+        .lines()
+        .find(|l| l.trim_start().starts_with("Finished"))?;
+    let secs = line.rsplit(" in ").next()?.trim().strip_suffix('s')?;
+    secs.parse::<f64>().ok().map(|s| (s * 1000.0) as u64)
+}
+
+/// Canonical result of cargo build/check for `--format json`
+fn parse_cargo_build(output: &str, exit_code: i32) -> ParseResult<BuildOutput> {
+    let headers: Vec<&str> = output.lines().filter(|l| is_diagnostic_header(l)).collect();
+    let result = BuildOutput {
+        success: exit_code == 0,
+        duration_ms: parse_finished_ms(output),
+        warnings: headers.iter().filter(|l| l.starts_with("warning")).count(),
+        errors: headers.iter().filter(|l| l.starts_with("error")).count(),
+        bundles: Vec::new(),
+        routes: Vec::new(),
+    };
+    if exit_code != 0 && result.errors == 0 {
+        let warning = "cargo failed without a compiler error".to_string();
+        return ParseResult::Degraded(result, vec![warning]);
+    }
+    ParseResult::Full(result)
+}
+
+/// Canonical result of cargo test for `--format json`: suite summaries are
+/// summed, each "---- name stdout ----" block becomes a failure
+fn parse_cargo_test(output: &str) -> ParseResult<TestResult> {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        regex::Regex::new(
+            r"test result: \w+\.\s+(\d+) passed;\s+(\d+) failed;\s+(\d+) ignored;.*?(?:finished in ([\d.]+)s)?$",
+        )
+        .unwrap()
+    });
+
+    let mut result = TestResult {
+        total: 0,
+        passed: 0,
+        failed: 0,
+        skipped: 0,
+        duration_ms: None,
+        failures: Vec::new(),
+    };
+    let mut suites = 0;
+    let mut duration_secs = 0.0;
+    let mut current: Option<TestFailure> = None;
+    let mut in_backtrace = false;
+
+    for line in output.lines() {
+        if let Some(caps) = re.captures(line) {
+            suites += 1;
+            result.passed += caps[1].parse::<usize>().unwrap_or(0);
+            result.failed += caps[2].parse::<usize>().unwrap_or(0);
+            result.skipped += caps[3].parse::<usize>().unwrap_or(0);
+            if let Some(secs) = caps.get(4).and_then(|m| m.as_str().parse::<f64>().ok()) {
+                duration_secs += secs;
+            }
+            continue;
         }
+
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            result.failures.extend(current.take());
+            in_backtrace = false;
+            current = Some(TestFailure {
+                test_name: name.to_string(),
+                file_path: String::new(),
+                error_message: String::new(),
+                stack_trace: None,
+            });
+        } else if let Some(failure) = current.as_mut() {
+            let started = !failure.file_path.is_empty() || !failure.error_message.is_empty();
+            if (line.is_empty() && started) || line == "failures:" {
+                result.failures.extend(current.take());
+            } else if line.is_empty() {
+                continue;
+            } else if let Some(rest) = line.split_once("panicked at ").map(|(_, r)| r) {
+                // "thread 'x' panicked at src/lib.rs:10:5:" (message on the next line)
+                let location = rest.trim_end_matches(':');
+                failure.file_path = location.split(':').next().unwrap_or("").to_string();
+                failure.stack_trace = Some(location.to_string());
+            } else if line == "stack backtrace:" || in_backtrace {
+                // RUST_BACKTRACE=1: frames go with the panic location
+                in_backtrace = true;
+                let trace = failure.stack_trace.get_or_insert_with(String::new);
+                if !trace.is_empty() {
+                    trace.push('\n');
+                }
+                trace.push_str(line);
+            } else if !line.starts_with("note: run with `RUST_BACKTRACE") {
+                if !failure.error_message.is_empty() {
+                    failure.error_message.push('\n');
+                }
+                failure.error_message.push_str(line);
+            }
+        }
+    }
+    result.failures.extend(current);
+
+    if suites == 0 {
+        // Nothing ran: usually a compile error
+        return ParseResult::Passthrough(truncate_output(output.trim(), 2000));
     }
+    result.total = result.passed + result.failed + result.skipped;
+    if duration_secs > 0.0 {
+        result.duration_ms = Some((duration_secs * 1000.0) as u64);
+    }
+    ParseResult::Full(result)
+}
+
+/// Canonical result of cargo clippy for `--format json`. The rule id comes from
+/// the error code, the `#[warn(...)]` note or the clippy docs link.
+fn parse_cargo_clippy(output: &str) -> ParseResult<LintResult> {
+    let mut issues: Vec<LintIssue> = Vec::new();
+
+    for line in output.lines() {
+        if is_diagnostic_header(line) {
+            let is_error = line.starts_with("error");
+            let (head, message) = line.split_once(": ").unwrap_or((line, ""));
+            let code = head
+                .split_once('[')
+                .map(|(_, c)| c.trim_end_matches(']').to_string())
+                .unwrap_or_default();
+            issues.push(LintIssue {
+                file_path: String::new(),
+                line: 0,
+                column: 0,
+                severity: if is_error {
+                    LintSeverity::Error
+                } else {
+                    LintSeverity::Warning
+                },
+                rule_id: code,
+                message: message.to_string(),
+            });
+            continue;
+        }
 
-    if errors.len() > 15 {
-        result.push_str(&format!("\n... +{} more issues\n", errors.len() - 15));
+        let Some(issue) = issues.last_mut() else {
+            continue;
+        };
+        let trimmed = line.trim_start();
+        if let Some(location) = trimmed.strip_prefix("--> ") {
+            if issue.file_path.is_empty() {
+                let mut parts = location.rsplitn(3, ':');
+                let column = parts.next().and_then(|c| c.parse().ok());
+                let line_no = parts.next().and_then(|l| l.parse().ok());
+                match (parts.next(), line_no, column) {
+                    (Some(file), Some(line_no), Some(column)) => {
+                        issue.file_path = file.to_string();
+                        issue.line = line_no;
+                        issue.column = column;
+                    }
+                    _ => issue.file_path = location.to_string(),
+                }
+            }
+        } else if issue.rule_id.is_empty() {
+            if let Some(rule) = trimmed
+                .split_once("#[warn(")
+                .or_else(|| trimmed.split_once("#[deny("))
+                .and_then(|(_, rest)| rest.split_once(")]"))
+                .map(|(rule, _)| rule)
+            {
+                issue.rule_id = rule.to_string();
+            } else if let Some((_, rule)) = trimmed.split_once("rust-clippy/master/index.html#") {
+                issue.rule_id = format!("clippy::{}", rule.trim());
+            }
+        }
     }
 
-    result.trim().to_string()
+    ParseResult::Full(LintResult::from_issues(issues))
 }
 
 /// Aggregated test results for compact display
@@ -458,135 +785,227 @@ impl AggregatedTestResult {
     }
 }
 
+/// Max failure blocks printed for cargo test
+const MAX_TEST_FAILURES: usize = 10;
+
 /// Filter cargo test output - show failures + summary only
+#[cfg(test)]
 fn filter_cargo_test(output: &str) -> String {
-    let mut failures: Vec<String> = Vec::new();
-    let mut summary_lines: Vec<String> = Vec::new();
-    let mut in_failure_section = false;
-    let mut current_failure = Vec::new();
+    stream::filter_all(CargoTestFilter::default(), output)
+}
+
+/// Streaming cargo test filter: failures are printed as each block ends,
+/// suite summaries are aggregated at the end.
+#[derive(Default)]
+struct CargoTestFilter {
+    summary_lines: Vec<String>,
+    in_failure_section: bool,
+    current_failure: Vec<String>,
+    failures: usize,
+    /// Last meaningful lines, shown when nothing else matched
+    recent: VecDeque<String>,
+}
+
+impl CargoTestFilter {
+    fn take_failure(&mut self) -> Option<String> {
+        if self.current_failure.is_empty() {
+            return None;
+        }
+        let failure = self.current_failure.join("\n");
+        self.current_failure.clear();
+        self.failures += 1;
+
+        if self.failures > MAX_TEST_FAILURES {
+            return None;
+        }
+        let entry = format!("{}. {}", self.failures, truncate(&failure, 200));
+        if self.failures == 1 {
+            Some(format!(
+                "FAILURES:\n═══════════════════════════════════════\n{}",
+                entry
+            ))
+        } else {
+            Some(entry)
+        }
+    }
+
+    fn push_failure_line(&mut self, line: &str) {
+        if self.current_failure.len() < stream::MAX_BLOCK_LINES {
+            self.current_failure.push(line.to_string());
+        }
+    }
+
+    fn remember(&mut self, line: &str) {
+        if line.trim().is_empty() || line.trim_start().starts_with("Compiling") {
+            return;
+        }
+        if self.recent.len() == 5 {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(line.to_string());
+    }
+}
+
+impl StreamFilter for CargoTestFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
+        self.remember(line);
 
-    for line in output.lines() {
         // Skip compilation lines
         if line.trim_start().starts_with("Compiling")
             || line.trim_start().starts_with("Downloading")
             || line.trim_start().starts_with("Downloaded")
             || line.trim_start().starts_with("Finished")
         {
-            continue;
+            return None;
         }
 
         // Skip "running N tests" and individual "test ... ok" lines
         if line.starts_with("running ") || (line.starts_with("test ") && line.ends_with("... ok")) {
-            continue;
+            return None;
         }
 
         // Detect failures section
         if line == "failures:" {
-            in_failure_section = true;
-            continue;
+            self.in_failure_section = true;
+            return None;
         }
 
-        if in_failure_section {
+        if self.in_failure_section {
             if line.starts_with("test result:") {
-                in_failure_section = false;
-                summary_lines.push(line.to_string());
+                self.in_failure_section = false;
+                self.summary_lines.push(line.to_string());
+                return None;
             } else if line.starts_with("    ") || line.starts_with("---- ") {
-                current_failure.push(line.to_string());
-            } else if line.trim().is_empty() && !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
-                current_failure.clear();
+                self.push_failure_line(line);
+            } else if line.trim().is_empty() && !self.current_failure.is_empty() {
+                return self.take_failure();
             } else if !line.trim().is_empty() {
-                current_failure.push(line.to_string());
+                self.push_failure_line(line);
             }
+            return None;
         }
 
         // Capture test result summary
-        if !in_failure_section && line.starts_with("test result:") {
-            summary_lines.push(line.to_string());
+        if line.starts_with("test result:") {
+            self.summary_lines.push(line.to_string());
         }
+        None
     }
 
-    if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
+    fn progress(&self) -> Option<String> {
+        Some(format!(
+            "{} suites done, {} failures",
+            self.summary_lines.len(),
+            self.failures
+        ))
     }
 
-    let mut result = String::new();
+    fn finish(&mut self) -> String {
+        self.finish_sections().text().trim_end().to_string()
+    }
 
-    if failures.is_empty() && !summary_lines.is_empty() {
-        // All passed - try to aggregate
-        let mut aggregated: Option<AggregatedTestResult> = None;
-        let mut all_parsed = true;
+    fn section(&self) -> Priority {
+        Priority::Failure
+    }
 
-        for line in &summary_lines {
-            if let Some(parsed) = AggregatedTestResult::parse_line(line) {
-                if let Some(ref mut agg) = aggregated {
-                    agg.merge(&parsed);
+    fn finish_sections(&mut self) -> Sections {
+        let last = self.take_failure();
+        let mut sections = Sections::new();
+
+        if self.failures == 0 && !self.summary_lines.is_empty() {
+            // All passed - try to aggregate
+            let mut aggregated: Option<AggregatedTestResult> = None;
+            let mut all_parsed = true;
+
+            for line in &self.summary_lines {
+                if let Some(parsed) = AggregatedTestResult::parse_line(line) {
+                    if let Some(ref mut agg) = aggregated {
+                        agg.merge(&parsed);
+                    } else {
+                        aggregated = Some(parsed);
+                    }
                 } else {
-                    aggregated = Some(parsed);
+                    all_parsed = false;
+                    break;
                 }
-            } else {
-                all_parsed = false;
-                break;
             }
-        }
 
-        // If all lines parsed successfully and we have at least one suite, return compact format
-        if all_parsed {
-            if let Some(agg) = aggregated {
-                if agg.suites > 0 {
-                    return agg.format_compact();
+            // If all lines parsed successfully and we have at least one suite, return compact format
+            if all_parsed {
+                if let Some(agg) = aggregated {
+                    if agg.suites > 0 {
+                        sections.push(Priority::Summary, format!("{}\n", agg.format_compact()));
+                        return sections;
+                    }
                 }
             }
-        }
 
-        // Fallback: use original behavior if regex failed
-        for line in &summary_lines {
-            result.push_str(&format!("✓ {}\n", line));
+            // Fallback: use original behavior if regex failed
+            let mut result = String::new();
+            for line in &self.summary_lines {
+                result.push_str(&format!("✓ {}\n", line));
+            }
+            sections.push(Priority::Summary, result);
+            return sections;
         }
-        return result.trim().to_string();
-    }
 
-    if !failures.is_empty() {
-        result.push_str(&format!("FAILURES ({}):\n", failures.len()));
-        result.push_str("═══════════════════════════════════════\n");
-        for (i, failure) in failures.iter().enumerate().take(10) {
-            result.push_str(&format!("{}. {}\n", i + 1, truncate(failure, 200)));
-        }
-        if failures.len() > 10 {
-            result.push_str(&format!("\n... +{} more failures\n", failures.len() - 10));
+        let mut summary = String::new();
+        if self.failures > 0 {
+            if let Some(entry) = last {
+                sections.push(Priority::Failure, format!("{}\n", entry));
+            }
+            if self.failures > MAX_TEST_FAILURES {
+                sections.push(
+                    Priority::Context,
+                    format!(
+                        "\n... +{} more failures\n",
+                        self.failures - MAX_TEST_FAILURES
+                    ),
+                );
+            }
+            summary.push('\n');
         }
-        result.push('\n');
-    }
 
-    for line in &summary_lines {
-        result.push_str(&format!("{}\n", line));
-    }
+        for line in &self.summary_lines {
+            summary.push_str(&format!("{}\n", line));
+        }
+        sections.push(Priority::Summary, summary);
 
-    if result.trim().is_empty() {
-        // Fallback: show last meaningful lines
-        let meaningful: Vec<&str> = output
-            .lines()
-            .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with("Compiling"))
-            .collect();
-        for line in meaningful.iter().rev().take(5).rev() {
-            result.push_str(&format!("{}\n", line));
+        if sections.text().trim().is_empty() {
+            // Fallback: show last meaningful lines
+            let mut recent = String::new();
+            for line in &self.recent {
+                recent.push_str(&format!("{}\n", line));
+            }
+            sections.push(Priority::Context, recent);
         }
-    }
 
-    result.trim().to_string()
+        sections
+    }
 }
 
 /// Filter cargo clippy output - group warnings by lint rule
+#[cfg(test)]
 fn filter_cargo_clippy(output: &str) -> String {
-    let mut by_rule: HashMap<String, Vec<String>> = HashMap::new();
-    let mut error_count = 0;
-    let mut warning_count = 0;
+    stream::filter_all(CargoClippyFilter::default(), output)
+}
 
-    // Parse clippy output lines
-    // Format: "warning: description\n  --> file:line:col\n  |\n  | code\n"
-    let mut current_rule = String::new();
+/// Locations kept per clippy rule (the rest are only counted)
+const MAX_RULE_LOCATIONS: usize = 3;
+
+/// Streaming cargo clippy filter: grouping needs the full run, so nothing is
+/// printed early, but only counts and a few locations per rule are kept.
+#[derive(Default)]
+struct CargoClippyFilter {
+    /// rule -> (occurrences, first locations)
+    by_rule: HashMap<String, (usize, Vec<String>)>,
+    error_count: usize,
+    warning_count: usize,
+    current_rule: String,
+}
 
-    for line in output.lines() {
+impl StreamFilter for CargoClippyFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
         // Skip compilation lines
         if line.trim_start().starts_with("Compiling")
             || line.trim_start().starts_with("Checking")
@@ -594,31 +1013,32 @@ fn filter_cargo_clippy(output: &str) -> String {
             || line.trim_start().starts_with("Downloaded")
             || line.trim_start().starts_with("Finished")
         {
-            continue;
+            return None;
         }
 
+        // Format: "warning: description\n  --> file:line:col\n  |\n  | code\n"
         // "warning: unused variable [unused_variables]" or "warning: description [clippy::rule_name]"
         if (line.starts_with("warning:") || line.starts_with("warning["))
             || (line.starts_with("error:") || line.starts_with("error["))
         {
             // Skip summary lines: "warning: `rtk` (bin) generated 5 warnings"
             if line.contains("generated") && line.contains("warning") {
-                continue;
+                return None;
             }
             // Skip "error: aborting" / "error: could not compile"
             if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
+                return None;
             }
 
             let is_error = line.starts_with("error");
             if is_error {
-                error_count += 1;
+                self.error_count += 1;
             } else {
-                warning_count += 1;
+                self.warning_count += 1;
             }
 
             // Extract rule name from brackets
-            current_rule = if let Some(bracket_start) = line.rfind('[') {
+            self.current_rule = if let Some(bracket_start) = line.rfind('[') {
                 if let Some(bracket_end) = line.rfind(']') {
                     line[bracket_start + 1..bracket_end].to_string()
                 } else {
@@ -631,45 +1051,72 @@ fn filter_cargo_clippy(output: &str) -> String {
             };
         } else if line.trim_start().starts_with("--> ") {
             let location = line.trim_start().trim_start_matches("--> ").to_string();
-            if !current_rule.is_empty() {
-                by_rule
-                    .entry(current_rule.clone())
-                    .or_default()
-                    .push(location);
+            if !self.current_rule.is_empty() {
+                let entry = self
+                    .by_rule
+                    .entry(self.current_rule.clone())
+                    .or_default();
+                entry.0 += 1;
+                if entry.1.len() < MAX_RULE_LOCATIONS {
+                    entry.1.push(location);
+                }
             }
         }
+        None
     }
 
-    if error_count == 0 && warning_count == 0 {
-        return "✓ cargo clippy: No issues found".to_string();
+    fn progress(&self) -> Option<String> {
+        Some(format!(
+            "{} errors, {} warnings",
+            self.error_count, self.warning_count
+        ))
     }
 
-    let mut result = String::new();
-    result.push_str(&format!(
-        "cargo clippy: {} errors, {} warnings\n",
-        error_count, warning_count
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+    fn finish(&mut self) -> String {
+        self.finish_sections().text().trim().to_string()
+    }
 
-    // Sort rules by frequency
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    /// Counts are the summary, the per-rule locations are detail
+    fn finish_sections(&mut self) -> Sections {
+        let mut sections = Sections::new();
+        if self.error_count == 0 && self.warning_count == 0 {
+            sections.push(Priority::Summary, "✓ cargo clippy: No issues found\n");
+            return sections;
+        }
+
+        sections.push(
+            Priority::Summary,
+            format!(
+                "cargo clippy: {} errors, {} warnings\n═══════════════════════════════════════\n",
+                self.error_count, self.warning_count
+            ),
+        );
 
-    for (rule, locations) in rule_counts.iter().take(15) {
-        result.push_str(&format!("  {} ({}x)\n", rule, locations.len()));
-        for loc in locations.iter().take(3) {
-            result.push_str(&format!("    {}\n", loc));
+        // Sort rules by frequency
+        let mut rule_counts: Vec<_> = self.by_rule.iter().collect();
+        rule_counts.sort_by_key(|(_, (count, _))| std::cmp::Reverse(*count));
+
+        let mut result = String::new();
+        for (rule, (count, locations)) in rule_counts.iter().take(15) {
+            result.push_str(&format!("  {} ({}x)\n", rule, count));
+            for loc in locations {
+                result.push_str(&format!("    {}\n", loc));
+            }
+            if *count > MAX_RULE_LOCATIONS {
+                result.push_str(&format!(
+                    "    ... +{} more\n",
+                    count - MAX_RULE_LOCATIONS
+                ));
+            }
         }
-        if locations.len() > 3 {
-            result.push_str(&format!("    ... +{} more\n", locations.len() - 3));
+
+        if self.by_rule.len() > 15 {
+            result.push_str(&format!("\n... +{} more rules\n", self.by_rule.len() - 15));
         }
-    }
+        sections.push(Priority::Context, result);
 
-    if by_rule.len() > 15 {
-        result.push_str(&format!("\n... +{} more rules\n", by_rule.len() - 15));
+        sections
     }
-
-    result.trim().to_string()
 }
 
 /// Runs an unsupported cargo subcommand by passing it through directly
@@ -677,11 +1124,11 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("cargo passthrough: {:?}", args);
+        render_eprintln!("cargo passthrough: {:?}", args);
     }
     let status = Command::new("cargo")
         .args(args)
-        .status()
+        .status_timed()
         .context("Failed to run cargo")?;
 
     let args_str = tracking::args_display(args);
@@ -691,7 +1138,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
     Ok(())
 }
@@ -729,6 +1176,60 @@ error: aborting due to 1 previous error
         assert!(result.contains("mismatched types"));
     }
 
+    #[test]
+    fn test_cargo_build_filter_streams_blocks() {
+        let mut filter = CargoBuildFilter::default();
+        assert_eq!(filter.feed_line("   Compiling rtk v0.5.0"), None);
+        assert_eq!(filter.feed_line("warning: unused variable: `x`"), None);
+        assert_eq!(filter.feed_line(" --> src/main.rs:3:9"), None);
+        assert_eq!(filter.feed_line("  |"), None);
+        assert_eq!(filter.feed_line("3 |     let x = 1;"), None);
+
+        // Blank line ends the block: printed before the build finishes
+        let block = filter.feed_line("").unwrap();
+        assert!(block.starts_with("warning: unused variable"));
+        assert!(block.contains("src/main.rs:3:9"));
+
+        let summary = filter.finish();
+        assert!(summary.contains("0 errors, 1 warnings (1 crates)"));
+        assert!(!summary.contains("unused variable"));
+    }
+
+    #[test]
+    fn test_filter_cargo_build_caps_blocks() {
+        let mut output = String::new();
+        for i in 0..20 {
+            output.push_str(&format!("error[E0308]: mismatch {}\n --> src/a.rs:{}:1\n", i, i));
+        }
+        let result = filter_cargo_build(&output);
+        assert!(result.contains("mismatch 14"));
+        assert!(!result.contains("mismatch 15"));
+        assert!(result.contains("+5 more issues"));
+        assert!(result.contains("20 errors"));
+    }
+
+    #[test]
+    fn test_cargo_build_budget_keeps_errors_over_warnings() {
+        let output = "   Compiling rtk v0.5.0\n\
+warning: unused variable: `x`\n --> src/main.rs:3:9\n\n\
+error[E0308]: mismatched types\n --> src/main.rs:10:5\n";
+        let sections = crate::stream::sections_all(CargoBuildFilter::default(), output);
+
+        let full = sections.render_within(None);
+        let error = full.find("E0308").unwrap();
+        let summary = full.find("cargo build: 1 errors, 1 warnings").unwrap();
+        let warning = full.find("unused variable").unwrap();
+        assert!(error < summary && summary < warning, "got: {}", full);
+
+        // Room for errors and summary (plus the footer), not the warning
+        let budget = crate::context::get().tokenizer.count(&full[..warning]) + 32;
+        let cut = sections.render_within(Some(budget));
+        assert!(cut.contains("E0308"), "got: {}", cut);
+        assert!(cut.contains("1 errors, 1 warnings"), "got: {}", cut);
+        assert!(!cut.contains("unused variable"), "got: {}", cut);
+        assert!(cut.contains("dropped context"), "got: {}", cut);
+    }
+
     #[test]
     fn test_filter_cargo_test_all_pass() {
         let output = r#"   Compiling rtk v0.5.0
@@ -955,6 +1456,97 @@ warning: `rtk` (bin) generated 2 warnings
         assert!(result.contains("clippy::too_many_arguments"));
     }
 
+    #[test]
+    fn test_parse_cargo_build_canonical() {
+        let output = r#"   Compiling rtk v0.5.0
+error[E0425]: cannot find value `x` in this scope
+ --> src/main.rs:10:5
+warning: unused variable: `y`
+ --> src/main.rs:12:9
+warning: `rtk` (bin "rtk") generated 1 warning
+error: could not compile `rtk` due to 1 previous error"#;
+        let result = parse_cargo_build(output, 101);
+        assert_eq!(result.tier(), 1);
+        let build = result.unwrap();
+        assert!(!build.success);
+        assert_eq!(build.errors, 1);
+        assert_eq!(build.warnings, 1);
+
+        let ok = "   Compiling rtk v0.5.0\n    Finished `dev` profile [unoptimized + debuginfo] target(s) in 1.50s";
+        let build = parse_cargo_build(ok, 0).unwrap();
+        assert!(build.success);
+        assert_eq!(build.duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_parse_cargo_test_canonical() {
+        let output = r#"running 3 tests
+test foo::ok ... ok
+test foo::bad ... FAILED
+test foo::skip ... ignored
+
+failures:
+
+---- foo::bad stdout ----
+
+thread 'foo::bad' (1234) panicked at src/foo.rs:42:9:
+assertion `left == right` failed
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+failures:
+    foo::bad
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+running 2 tests
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.25s
+"#;
+        let result = parse_cargo_test(output);
+        assert_eq!(result.tier(), 1);
+        let tests = result.unwrap();
+        assert_eq!(tests.total, 5);
+        assert_eq!(tests.passed, 3);
+        assert_eq!(tests.failed, 1);
+        assert_eq!(tests.skipped, 1);
+        assert_eq!(tests.duration_ms, Some(500));
+        assert_eq!(tests.failures.len(), 1);
+        assert_eq!(tests.failures[0].test_name, "foo::bad");
+        assert_eq!(tests.failures[0].file_path, "src/foo.rs");
+        assert_eq!(
+            tests.failures[0].error_message,
+            "assertion `left == right` failed"
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_test_compile_error_is_passthrough() {
+        let output = "error[E0425]: cannot find value `x`\nerror: could not compile `rtk`";
+        assert_eq!(parse_cargo_test(output).tier(), 3);
+    }
+
+    #[test]
+    fn test_parse_cargo_clippy_canonical() {
+        let output = r#"warning: this loop could be written as a `for` loop
+  --> src/main.rs:5:5
+   |
+   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#while_let_loop
+   = note: `#[warn(clippy::while_let_loop)]` on by default
+
+error[E0308]: mismatched types
+  --> src/lib.rs:3:14
+
+warning: `rtk` (bin "rtk") generated 1 warning"#;
+        let lint = parse_cargo_clippy(output).unwrap();
+        assert_eq!(lint.total_issues, 2);
+        assert_eq!(lint.warnings, 1);
+        assert_eq!(lint.errors, 1);
+        assert_eq!(lint.issues[0].rule_id, "clippy::while_let_loop");
+        assert_eq!(lint.issues[0].file_path, "src/main.rs");
+        assert_eq!(lint.issues[0].line, 5);
+        assert_eq!(lint.issues[1].rule_id, "E0308");
+        assert_eq!(lint.issues[1].message, "mismatched types");
+    }
+
     #[test]
     fn test_filter_cargo_install_success() {
         let output = r#"  Installing rtk v0.11.0
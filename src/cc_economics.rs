@@ -7,6 +7,7 @@ use anyhow::{Context, Result};
 use chrono::NaiveDate;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::ccusage::{self, CcusagePeriod, Granularity};
 use crate::tracking::{DayStats, MonthStats, Tracker, WeekStats};
@@ -14,8 +15,6 @@ use crate::utils::{format_cpt, format_tokens, format_usd};
 
 // ── Constants ──
 
-const BILLION: f64 = 1e9;
-
 // API pricing ratios (verified Feb 2026, consistent across Claude models <=200K context)
 // Source: https://docs.anthropic.com/en/docs/about-claude/models
 const WEIGHT_OUTPUT: f64 = 5.0; // Output = 5x input
@@ -187,9 +186,10 @@ pub fn run(
     monthly: bool,
     all: bool,
     format: &str,
+    db: Option<&Path>,
     verbose: u8,
 ) -> Result<()> {
-    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let tracker = Tracker::open_or_default(db).context("Failed to initialize tracking database")?;
 
     match format {
         "json" => export_json(&tracker, daily, weekly, monthly, all),
@@ -249,7 +249,7 @@ fn merge_weekly(cc: Option<Vec<CcusagePeriod>>, rtk: Vec<WeekStats>) -> Vec<Peri
         let monday_key = match convert_saturday_to_monday(&entry.week_start) {
             Some(m) => m,
             None => {
-                eprintln!("⚠️  Invalid week_start format: {}", entry.week_start);
+                render_eprintln!("⚠️  Invalid week_start format: {}", entry.week_start);
                 continue;
             }
         };
@@ -435,48 +435,48 @@ fn display_summary(tracker: &Tracker, verbose: u8) -> Result<()> {
     let periods = merge_monthly(cc_monthly, rtk_monthly);
 
     if periods.is_empty() {
-        println!("No data available. Run some rtk commands to start tracking.");
+        render_println!("No data available. Run some rtk commands to start tracking.");
         return Ok(());
     }
 
     let totals = compute_totals(&periods);
 
-    println!("💰 Claude Code Economics");
-    println!("════════════════════════════════════════════════════");
-    println!();
+    render_println!("💰 Claude Code Economics");
+    render_println!("════════════════════════════════════════════════════");
+    render_println!();
 
-    println!(
+    render_println!(
         "  Spent (ccusage):              {}",
         format_usd(totals.cc_cost)
     );
-    println!("  Token breakdown:");
-    println!(
+    render_println!("  Token breakdown:");
+    render_println!(
         "    Input:                      {}",
         format_tokens(totals.cc_input_tokens as usize)
     );
-    println!(
+    render_println!(
         "    Output:                     {}",
         format_tokens(totals.cc_output_tokens as usize)
     );
-    println!(
+    render_println!(
         "    Cache writes:               {}",
         format_tokens(totals.cc_cache_create_tokens as usize)
     );
-    println!(
+    render_println!(
         "    Cache reads:                {}",
         format_tokens(totals.cc_cache_read_tokens as usize)
     );
-    println!();
+    render_println!();
 
-    println!("  RTK commands:                 {}", totals.rtk_commands);
-    println!(
+    render_println!("  RTK commands:                 {}", totals.rtk_commands);
+    render_println!(
         "  Tokens saved:                 {}",
         format_tokens(totals.rtk_saved_tokens)
     );
-    println!();
+    render_println!();
 
-    println!("  Estimated Savings:");
-    println!("  ┌─────────────────────────────────────────────────┐");
+    render_println!("  Estimated Savings:");
+    render_println!("  ┌─────────────────────────────────────────────────┐");
 
     if let Some(weighted_savings) = totals.savings_weighted {
         let weighted_pct = if totals.cc_cost > 0.0 {
@@ -484,39 +484,41 @@ fn display_summary(tracker: &Tracker, verbose: u8) -> Result<()> {
         } else {
             0.0
         };
-        println!(
+        render_println!(
             "  │ Input token pricing:   {}  ({:.1}%)           │",
             format_usd(weighted_savings).trim_end(),
             weighted_pct
         );
         if let Some(input_cpt) = totals.weighted_input_cpt {
-            println!(
+            render_println!(
                 "  │ Derived input CPT:     {}               │",
                 format_cpt(input_cpt)
             );
         }
     } else {
-        println!("  │ Input token pricing:   —                         │");
+        render_println!("  │ Input token pricing:   —                         │");
     }
 
-    println!("  └─────────────────────────────────────────────────┘");
-    println!();
+    render_println!("  └─────────────────────────────────────────────────┘");
+    render_println!();
 
-    println!("  How it works:");
-    println!("  RTK compresses CLI outputs before they enter Claude's context.");
-    println!("  Savings derived using API price ratios (out=5x, cache_w=1.25x, cache_r=0.1x).");
-    println!();
+    render_println!("  How it works:");
+    render_println!("  RTK compresses CLI outputs before they enter Claude's context.");
+    render_println!(
+        "  Savings derived using API price ratios (out=5x, cache_w=1.25x, cache_r=0.1x)."
+    );
+    render_println!();
 
     // Verbose mode: legacy metrics
     if verbose > 0 {
-        println!("  Legacy metrics (reference only):");
+        render_println!("  Legacy metrics (reference only):");
         if let Some(active_savings) = totals.savings_active {
             let active_pct = if totals.cc_cost > 0.0 {
                 (active_savings / totals.cc_cost) * 100.0
             } else {
                 0.0
             };
-            println!(
+            render_println!(
                 "    Active (OVERESTIMATES):  {}  ({:.1}%)",
                 format_usd(active_savings),
                 active_pct
@@ -528,14 +530,16 @@ fn display_summary(tracker: &Tracker, verbose: u8) -> Result<()> {
             } else {
                 0.0
             };
-            println!(
+            render_println!(
                 "    Blended (UNDERESTIMATES): {}  ({:.2}%)",
                 format_usd(blended_savings),
                 blended_pct
             );
         }
-        println!("  Note: Saved tokens estimated via chars/4 heuristic, not exact tokenizer.");
-        println!();
+        render_println!(
+            "  Note: Saved tokens estimated via chars/4 heuristic, not exact tokenizer."
+        );
+        render_println!();
     }
 
     Ok(())
@@ -549,8 +553,8 @@ fn display_daily(tracker: &Tracker, verbose: u8) -> Result<()> {
         .context("Failed to load daily token savings from database")?;
     let periods = merge_daily(cc_daily, rtk_daily);
 
-    println!("📅 Daily Economics");
-    println!("════════════════════════════════════════════════════");
+    render_println!("📅 Daily Economics");
+    render_println!("════════════════════════════════════════════════════");
     print_period_table(&periods, verbose);
     Ok(())
 }
@@ -563,8 +567,8 @@ fn display_weekly(tracker: &Tracker, verbose: u8) -> Result<()> {
         .context("Failed to load weekly token savings from database")?;
     let periods = merge_weekly(cc_weekly, rtk_weekly);
 
-    println!("📅 Weekly Economics");
-    println!("════════════════════════════════════════════════════");
+    render_println!("📅 Weekly Economics");
+    render_println!("════════════════════════════════════════════════════");
     print_period_table(&periods, verbose);
     Ok(())
 }
@@ -577,24 +581,36 @@ fn display_monthly(tracker: &Tracker, verbose: u8) -> Result<()> {
         .context("Failed to load monthly token savings from database")?;
     let periods = merge_monthly(cc_monthly, rtk_monthly);
 
-    println!("📅 Monthly Economics");
-    println!("════════════════════════════════════════════════════");
+    render_println!("📅 Monthly Economics");
+    render_println!("════════════════════════════════════════════════════");
     print_period_table(&periods, verbose);
     Ok(())
 }
 
 fn print_period_table(periods: &[PeriodEconomics], verbose: u8) {
-    println!();
+    render_println!();
 
     if verbose > 0 {
         // Verbose: include legacy metrics
-        println!(
+        render_println!(
             "{:<12} {:>10} {:>10} {:>10} {:>10} {:>12} {:>12}",
-            "Period", "Spent", "Saved", "Savings", "Active$", "Blended$", "RTK Cmds"
+            "Period",
+            "Spent",
+            "Saved",
+            "Savings",
+            "Active$",
+            "Blended$",
+            "RTK Cmds"
         );
-        println!(
+        render_println!(
             "{:-<12} {:-<10} {:-<10} {:-<10} {:-<10} {:-<12} {:-<12}",
-            "", "", "", "", "", "", ""
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            ""
         );
 
         for p in periods {
@@ -620,20 +636,34 @@ fn print_period_table(periods: &[PeriodEconomics], verbose: u8) {
                 .map(|c| c.to_string())
                 .unwrap_or_else(|| "—".to_string());
 
-            println!(
+            render_println!(
                 "{:<12} {:>10} {:>10} {:>10} {:>10} {:>12} {:>12}",
-                p.label, spent, saved, weighted, active, blended, cmds
+                p.label,
+                spent,
+                saved,
+                weighted,
+                active,
+                blended,
+                cmds
             );
         }
     } else {
         // Default: single Savings column
-        println!(
+        render_println!(
             "{:<12} {:>10} {:>10} {:>10} {:>12}",
-            "Period", "Spent", "Saved", "Savings", "RTK Cmds"
+            "Period",
+            "Spent",
+            "Saved",
+            "Savings",
+            "RTK Cmds"
         );
-        println!(
+        render_println!(
             "{:-<12} {:-<10} {:-<10} {:-<10} {:-<12}",
-            "", "", "", "", ""
+            "",
+            "",
+            "",
+            "",
+            ""
         );
 
         for p in periods {
@@ -651,13 +681,17 @@ fn print_period_table(periods: &[PeriodEconomics], verbose: u8) {
                 .map(|c| c.to_string())
                 .unwrap_or_else(|| "—".to_string());
 
-            println!(
+            render_println!(
                 "{:<12} {:>10} {:>10} {:>10} {:>12}",
-                p.label, spent, saved, weighted, cmds
+                p.label,
+                spent,
+                saved,
+                weighted,
+                cmds
             );
         }
     }
-    println!();
+    render_println!();
 }
 
 // ── Export ──
@@ -713,7 +747,7 @@ fn export_json(
         export.monthly = Some(periods);
     }
 
-    println!(
+    render_println!(
         "{}",
         serde_json::to_string_pretty(&export)
             .context("Failed to serialize economics data to JSON")?
@@ -729,7 +763,7 @@ fn export_csv(
     all: bool,
 ) -> Result<()> {
     // Header (new columns: input_tokens, output_tokens, cache_create, cache_read, weighted_savings)
-    println!("period,spent,input_tokens,output_tokens,cache_create,cache_read,active_tokens,total_tokens,saved_tokens,weighted_savings,active_savings,blended_savings,rtk_commands");
+    render_println!("period,spent,input_tokens,output_tokens,cache_create,cache_read,active_tokens,total_tokens,saved_tokens,weighted_savings,active_savings,blended_savings,rtk_commands");
 
     if all || daily {
         let cc = ccusage::fetch(Granularity::Daily)
@@ -808,7 +842,7 @@ fn print_csv_row(p: &PeriodEconomics) {
         .unwrap_or_default();
     let cmds = p.rtk_commands.map(|c| c.to_string()).unwrap_or_default();
 
-    println!(
+    render_println!(
         "{},{},{},{},{},{},{},{},{},{},{},{},{}",
         p.label,
         spent,
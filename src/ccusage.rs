@@ -115,6 +115,7 @@ fn build_command() -> Option<Command> {
 }
 
 /// Check if ccusage CLI is available (binary or via npx)
+#[allow(dead_code)]
 pub fn is_available() -> bool {
     build_command().is_some()
 }
@@ -128,7 +129,9 @@ pub fn fetch(granularity: Granularity) -> Result<Option<Vec<CcusagePeriod>>> {
     let mut cmd = match build_command() {
         Some(cmd) => cmd,
         None => {
-            eprintln!("⚠️  ccusage not found. Install: npm i -g ccusage (or use npx ccusage)");
+            render_eprintln!(
+                "⚠️  ccusage not found. Install: npm i -g ccusage (or use npx ccusage)"
+            );
             return Ok(None);
         }
     };
@@ -148,7 +151,7 @@ pub fn fetch(granularity: Granularity) -> Result<Option<Vec<CcusagePeriod>>> {
 
     let output = match output {
         Err(e) => {
-            eprintln!("⚠️  ccusage execution failed: {}", e);
+            render_eprintln!("⚠️  ccusage execution failed: {}", e);
             return Ok(None);
         }
         Ok(o) => o,
@@ -156,7 +159,7 @@ pub fn fetch(granularity: Granularity) -> Result<Option<Vec<CcusagePeriod>>> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!(
+        render_eprintln!(
             "⚠️  ccusage exited with {}: {}",
             output.status,
             stderr.trim()
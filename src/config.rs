@@ -1,6 +1,7 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -10,6 +11,14 @@ pub struct Config {
     pub display: DisplayConfig,
     #[serde(default)]
     pub filters: FilterConfig,
+    #[serde(default)]
+    pub tee: TeeConfig,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default)]
+    pub redact: RedactConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +27,9 @@ pub struct TrackingConfig {
     pub history_days: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<PathBuf>,
+    /// How tokens are counted for savings stats
+    #[serde(default)]
+    pub tokenizer: Tokenizer,
 }
 
 impl Default for TrackingConfig {
@@ -26,15 +38,35 @@ impl Default for TrackingConfig {
             enabled: true,
             history_days: 90,
             database_path: None,
+            tokenizer: Tokenizer::default(),
         }
     }
 }
 
+/// Token counter used for tracking (`tracking.tokenizer`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tokenizer {
+    /// ~4 bytes per token, no setup cost
+    #[default]
+    Estimate,
+    /// Exact BPE count with the bundled cl100k_base vocabulary (GPT-4)
+    Cl100k,
+    /// Exact BPE count with the bundled o200k_base vocabulary (GPT-4o)
+    O200k,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DisplayConfig {
     pub colors: bool,
     pub emoji: bool,
     pub max_width: usize,
+    /// Default output budget in tokens (0 = no limit, see `--max-tokens`)
+    #[serde(default)]
+    pub max_tokens: usize,
+    /// Show only changes since the previous run of test/lint commands (see `--delta`)
+    #[serde(default)]
+    pub delta: bool,
 }
 
 impl Default for DisplayConfig {
@@ -43,45 +75,336 @@ impl Default for DisplayConfig {
             colors: true,
             emoji: true,
             max_width: 120,
+            max_tokens: 0,
+            delta: false,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FilterConfig {
+    /// Directory names (globs) hidden by ls, tree, find and grep
     pub ignore_dirs: Vec<String>,
+    /// File names (globs) hidden by ls, tree and grep
     pub ignore_files: Vec<String>,
+    /// User-defined filters applied by `rtk run` (`[[filters.custom]]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<CustomFilterConfig>,
 }
 
 impl Default for FilterConfig {
     fn default() -> Self {
         Self {
-            ignore_dirs: vec![
-                ".git".into(),
-                "node_modules".into(),
-                "target".into(),
-                "__pycache__".into(),
-                ".venv".into(),
-                "vendor".into(),
-            ],
-            ignore_files: vec!["*.lock".into(), "*.min.js".into(), "*.min.css".into()],
+            ignore_dirs: [
+                ".git",
+                "node_modules",
+                "target",
+                "__pycache__",
+                ".venv",
+                "vendor",
+                ".next",
+                "dist",
+                "build",
+                ".cache",
+                ".turbo",
+                ".vercel",
+                ".pytest_cache",
+                ".mypy_cache",
+                ".tox",
+                "venv",
+                "coverage",
+                ".nyc_output",
+                ".idea",
+                ".vscode",
+                ".vs",
+                "*.egg-info",
+                ".eggs",
+            ]
+            .map(String::from)
+            .to_vec(),
+            ignore_files: ["*.lock", "*.min.js", "*.min.css", ".DS_Store", "Thumbs.db"]
+                .map(String::from)
+                .to_vec(),
+            custom: Vec::new(),
         }
     }
 }
 
-impl Config {
+/// A declarative filter for a command rtk has no module for
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomFilterConfig {
+    /// Label shown in `rtk run -v` and `rtk discover`
+    pub name: String,
+    /// Regex matched against the full command line
+    pub command: String,
+    /// Keep only lines matching one of these (empty = keep everything)
+    pub keep: Vec<String>,
+    /// Drop lines matching any of these
+    pub drop: Vec<String>,
+    /// Collapse runs of identical lines
+    pub dedupe: bool,
+    /// Keep at most the first N lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<usize>,
+    /// Keep at most the last N lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+    /// Lines matching this are always kept and printed last
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// What to print when the command exits non-zero
+    pub on_failure: FailurePolicy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailurePolicy {
+    /// Filter as usual
+    #[default]
+    Filter,
+    /// Print the raw output unfiltered
+    Passthrough,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeeConfig {
+    pub enabled: bool,
+    /// Per-run cap; larger outputs keep their head and tail
+    pub max_file_kb: u64,
+    /// Whole-store cap; oldest runs are pruned first
+    pub max_total_mb: u64,
+}
+
+impl Default for TeeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_file_kb: 1024,
+            max_total_mb: 50,
+        }
+    }
+}
+
+/// Time limits per command family (`rtk <family> ...`); `--timeout` wins
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimeoutConfig {
+    /// Limit for families without their own entry, e.g. "10m" ("" = none)
+    #[serde(default)]
+    pub default: String,
+    /// Family -> limit, e.g. `pytest = "5m"`, `kubectl = "30s"`
+    #[serde(flatten)]
+    pub commands: HashMap<String, String>,
+}
+
+impl TimeoutConfig {
+    /// Limit for `family`; unparsable values are ignored with a warning
+    pub fn for_family(&self, family: &str) -> Option<std::time::Duration> {
+        let value = self.commands.get(family).unwrap_or(&self.default).trim();
+        if value.is_empty() || value == "0" {
+            return None;
+        }
+        match crate::timeout::parse_duration(value) {
+            Ok(limit) => Some(limit),
+            Err(e) => {
+                render_eprintln!("rtk: ignoring timeouts.{}: {}", family, e);
+                None
+            }
+        }
+    }
+}
+
+/// Secret redaction applied to all output and stores (see `redact.rs`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedactConfig {
+    pub enabled: bool,
+    /// Extra patterns (`[[redact.patterns]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<RedactPattern>,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactPattern {
+    /// Type shown in the `[REDACTED:<name>]` marker
+    pub name: String,
+    /// Regex; if it has a capture group, only group 1 is replaced
+    pub regex: String,
+}
+
+/// Projects whose `.rtk.toml` may set restricted keys (global config only)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectsConfig {
+    /// Directories containing a trusted `.rtk.toml`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted: Vec<PathBuf>,
+}
+
+/// Project-local config, searched upward from the current directory
+const LOCAL_CONFIG_NAME: &str = ".rtk.toml";
+
+/// Keys a project `.rtk.toml` may only set once its directory is in `projects.trusted`:
+/// a cloned repo must not turn off redaction, move the history database or add filters
+const RESTRICTED_LOCAL_KEYS: &[&str] = &["redact", "tracking.database_path", "filters.custom"];
+
+/// Env vars that don't follow the `RTK_<SECTION>_<KEY>` naming
+const ENV_ALIASES: &[(&str, &str)] = &[("RTK_DB_PATH", "tracking.database_path")];
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    Global,
+    Local,
+    Env(String),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::Global => write!(f, "global"),
+            ConfigSource::Local => write!(f, "local"),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+        }
+    }
+}
+
+/// Effective config with the layer each value came from:
+/// defaults < global config.toml < project .rtk.toml < `RTK_*` env vars
+pub struct LayeredConfig {
+    pub config: Config,
+    pub global_path: PathBuf,
+    pub local_path: Option<PathBuf>,
+    merged: toml::Table,
+    /// Leaf path ("display.max_width") -> source
+    sources: HashMap<String, ConfigSource>,
+    /// Arrays of tables ("filters.custom") -> source of each entry
+    entry_sources: HashMap<String, Vec<ConfigSource>>,
+}
+
+impl LayeredConfig {
     pub fn load() -> Result<Self> {
-        let path = get_config_path()?;
+        let global_path = get_config_path()?;
+        let local_path = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| find_local_config(&cwd));
+        Self::load_from(global_path, local_path, |var| std::env::var(var).ok())
+    }
+
+    fn load_from(
+        global_path: PathBuf,
+        local_path: Option<PathBuf>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut layered = Self {
+            config: Config::default(),
+            merged: toml::Table::try_from(Config::default())?,
+            global_path,
+            local_path,
+            sources: HashMap::new(),
+            entry_sources: HashMap::new(),
+        };
+
+        if layered.global_path.exists() {
+            let layer = read_layer(&layered.global_path)?;
+            layered.merge(layer, ConfigSource::Global);
+        }
+        if let Some(path) = layered.local_path.clone() {
+            let mut layer = read_layer(&path)?;
+            remove_path(&mut layer, "projects");
+            if !layered.is_trusted(&path) {
+                for key in RESTRICTED_LOCAL_KEYS {
+                    if remove_path(&mut layer, key).is_some() {
+                        render_eprintln!(
+                            "rtk: ignoring {} in {} (add its directory to projects.trusted in {})",
+                            key,
+                            path.display(),
+                            layered.global_path.display()
+                        );
+                    }
+                }
+            }
+            layered.merge(layer, ConfigSource::Local);
+        }
+        layered.apply_env(env);
 
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+        layered.config = toml::Value::Table(layered.merged.clone())
+            .try_into()
+            .context("Invalid config after merging layers")?;
+        Ok(layered)
+    }
+
+    fn merge(&mut self, layer: toml::Table, source: ConfigSource) {
+        let mut merged = std::mem::take(&mut self.merged);
+        merge_table(&mut merged, layer, "", &source, self);
+        self.merged = merged;
+    }
+
+    /// Override leaves from `RTK_<SECTION>_<KEY>` (parsed as the leaf's current type)
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) {
+        let mut targets: Vec<(String, String)> = Vec::new();
+        collect_leaves(&self.merged, "", &mut |path, _| {
+            targets.push((env_var_name(path), path.to_string()));
+        });
+        for (var, path) in ENV_ALIASES {
+            targets.push((var.to_string(), path.to_string()));
+        }
+
+        for (var, path) in targets {
+            let Some(raw) = env(&var) else { continue };
+            let current = lookup(&self.merged, &path);
+            match parse_env_value(&raw, current) {
+                Some(value) => {
+                    set_path(&mut self.merged, &path, value);
+                    self.sources.insert(path, ConfigSource::Env(var));
+                }
+                None => {
+                    render_eprintln!("rtk: ignoring {}={:?} (wrong type for {})", var, raw, path)
+                }
+            }
         }
     }
 
+    /// Whether the global layer lists the directory of `local_path` in `projects.trusted`
+    fn is_trusted(&self, local_path: &Path) -> bool {
+        let Some(dir) = local_path.parent().and_then(|d| d.canonicalize().ok()) else {
+            return false;
+        };
+        let Some(trusted) = lookup(&self.merged, "projects.trusted").and_then(|v| v.as_array())
+        else {
+            return false;
+        };
+        trusted
+            .iter()
+            .filter_map(toml::Value::as_str)
+            .filter_map(|entry| expand_home(entry).canonicalize().ok())
+            .any(|entry| entry == dir)
+    }
+
+    fn source(&self, path: &str) -> ConfigSource {
+        self.sources
+            .get(path)
+            .cloned()
+            .unwrap_or(ConfigSource::Default)
+    }
+}
+
+impl Config {
+    /// Load the effective config (global, project `.rtk.toml` and `RTK_*` env layered)
+    pub fn load() -> Result<Self> {
+        Ok(LayeredConfig::load()?.config)
+    }
+
     pub fn save(&self) -> Result<()> {
         let path = get_config_path()?;
 
@@ -106,20 +429,369 @@ fn get_config_path() -> Result<PathBuf> {
     Ok(config_dir.join("rtk").join("config.toml"))
 }
 
+/// Nearest `.rtk.toml` in `start` or one of its parents
+fn find_local_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(LOCAL_CONFIG_NAME))
+        .find(|path| path.is_file())
+}
+
+/// `~/src/api` -> `$HOME/src/api`
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn read_layer(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Deep-merge `layer` into `base`. Tables merge key by key, arrays of tables
+/// (`[[filters.custom]]`) are prepended so the more specific layer matches first,
+/// anything else is replaced.
+fn merge_table(
+    base: &mut toml::Table,
+    layer: toml::Table,
+    prefix: &str,
+    source: &ConfigSource,
+    layered: &mut LayeredConfig,
+) {
+    for (key, value) in layer {
+        let path = join_path(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+                merge_table(base_table, table, &path, source, layered);
+            }
+            (Some(toml::Value::Array(base_array)), toml::Value::Array(array))
+                if !array.is_empty() && array.iter().all(toml::Value::is_table) =>
+            {
+                let sources = layered.entry_sources.entry(path).or_default();
+                sources.resize(base_array.len(), ConfigSource::Default);
+                sources.splice(0..0, std::iter::repeat_n(source.clone(), array.len()));
+                base_array.splice(0..0, array);
+            }
+            (_, value) => {
+                if let toml::Value::Array(array) = &value {
+                    if array.iter().all(toml::Value::is_table) {
+                        layered
+                            .entry_sources
+                            .insert(path.clone(), vec![source.clone(); array.len()]);
+                    }
+                }
+                layered.sources.insert(path, source.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Visit every leaf (non-table value, excluding arrays of tables)
+fn collect_leaves(table: &toml::Table, prefix: &str, visit: &mut dyn FnMut(&str, &toml::Value)) {
+    for (key, value) in table {
+        let path = join_path(prefix, key);
+        match value {
+            toml::Value::Table(inner) => collect_leaves(inner, &path, visit),
+            toml::Value::Array(array)
+                if !array.is_empty() && array.iter().all(toml::Value::is_table) => {}
+            _ => visit(&path, value),
+        }
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// `display.max_width` -> `RTK_DISPLAY_MAX_WIDTH`
+fn env_var_name(path: &str) -> String {
+    format!("RTK_{}", path.replace('.', "_").to_uppercase())
+}
+
+fn lookup<'a>(table: &'a toml::Table, path: &str) -> Option<&'a toml::Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (lookup(table, parent)?.as_table()?, key),
+        None => (table, path),
+    };
+    parent.get(key)
+}
+
+fn set_path(table: &mut toml::Table, path: &str, value: toml::Value) {
+    let mut current = table;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        if keys.peek().is_none() {
+            current.insert(key.to_string(), value);
+            return;
+        }
+        let entry = current
+            .entry(key.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        match entry {
+            toml::Value::Table(inner) => current = inner,
+            _ => return,
+        }
+    }
+}
+
+fn remove_path(table: &mut toml::Table, path: &str) -> Option<toml::Value> {
+    match path.split_once('.') {
+        Some((key, rest)) => remove_path(table.get_mut(key)?.as_table_mut()?, rest),
+        None => table.remove(path),
+    }
+}
+
+/// Parse an env override using the type of the value it replaces (strings if unset)
+fn parse_env_value(raw: &str, current: Option<&toml::Value>) -> Option<toml::Value> {
+    match current {
+        Some(toml::Value::Boolean(_)) => match raw.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Some(toml::Value::Boolean(true)),
+            "0" | "false" | "no" | "off" => Some(toml::Value::Boolean(false)),
+            _ => None,
+        },
+        Some(toml::Value::Integer(_)) => raw.trim().parse().ok().map(toml::Value::Integer),
+        Some(toml::Value::Float(_)) => raw.trim().parse().ok().map(toml::Value::Float),
+        Some(toml::Value::Array(_)) => Some(toml::Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| toml::Value::String(s.to_string()))
+                .collect(),
+        )),
+        _ => Some(toml::Value::String(raw.to_string())),
+    }
+}
+
 pub fn show_config() -> Result<()> {
-    let path = get_config_path()?;
-    println!("Config: {}", path.display());
-    println!();
+    let layered = LayeredConfig::load()?;
 
-    if path.exists() {
-        let config = Config::load()?;
-        println!("{}", toml::to_string_pretty(&config)?);
+    let global_state = if layered.global_path.exists() {
+        ""
     } else {
-        println!("(default config, file not created)");
-        println!();
-        let config = Config::default();
-        println!("{}", toml::to_string_pretty(&config)?);
+        " (not created)"
+    };
+    render_println!("Global: {}{}", layered.global_path.display(), global_state);
+    match &layered.local_path {
+        Some(path) => render_println!("Local:  {}", path.display()),
+        None => render_println!("Local:  (no {} found)", LOCAL_CONFIG_NAME),
     }
+    render_println!();
 
+    render_print!("{}", render_with_sources(&layered));
     Ok(())
 }
+
+/// The effective config as TOML, each value annotated with its source
+fn render_with_sources(layered: &LayeredConfig) -> String {
+    let mut out = String::new();
+    render_table(layered, &layered.merged, "", &mut out);
+    out.trim_start().to_string()
+}
+
+fn render_table(layered: &LayeredConfig, table: &toml::Table, prefix: &str, out: &mut String) {
+    // Plain values first, then sub-tables, as in a TOML document
+    for (key, value) in table {
+        let path = join_path(prefix, key);
+        match value {
+            toml::Value::Table(_) => {}
+            toml::Value::Array(array)
+                if !array.is_empty() && array.iter().all(toml::Value::is_table) => {}
+            _ => {
+                let line = format!("{} = {}", key, value);
+                out.push_str(&format!("{:<40} # {}\n", line, layered.source(&path)));
+            }
+        }
+    }
+
+    for (key, value) in table {
+        let path = join_path(prefix, key);
+        match value {
+            toml::Value::Table(inner) => {
+                out.push_str(&format!("\n[{}]\n", path));
+                render_table(layered, inner, &path, out);
+            }
+            toml::Value::Array(array)
+                if !array.is_empty() && array.iter().all(toml::Value::is_table) =>
+            {
+                let sources = layered.entry_sources.get(&path);
+                for (i, entry) in array.iter().enumerate() {
+                    let source = sources
+                        .and_then(|s| s.get(i))
+                        .cloned()
+                        .unwrap_or(ConfigSource::Default);
+                    out.push_str(&format!("\n[[{}]]  # {}\n", path, source));
+                    if let Some(entry) = entry.as_table() {
+                        for (key, value) in entry {
+                            out.push_str(&format!("{} = {}\n", key, value));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_local_overrides_global_and_env_overrides_both() {
+        let dir = TempDir::new().unwrap();
+        let global = write(
+            &dir,
+            "config.toml",
+            "[display]\nmax_width = 100\nemoji = false\n[tracking]\nhistory_days = 30\n",
+        );
+        let local = write(&dir, ".rtk.toml", "[display]\nmax_width = 80\n");
+
+        let layered = LayeredConfig::load_from(global, Some(local), |var| {
+            (var == "RTK_TRACKING_ENABLED").then(|| "false".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(layered.config.display.max_width, 80);
+        assert!(!layered.config.display.emoji);
+        assert_eq!(layered.config.tracking.history_days, 30);
+        assert!(!layered.config.tracking.enabled);
+        assert!(layered.config.display.colors);
+
+        assert_eq!(layered.source("display.max_width"), ConfigSource::Local);
+        assert_eq!(layered.source("display.emoji"), ConfigSource::Global);
+        assert_eq!(
+            layered.source("tracking.enabled"),
+            ConfigSource::Env("RTK_TRACKING_ENABLED".to_string())
+        );
+        assert_eq!(layered.source("display.colors"), ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_custom_filters_local_first() {
+        let dir = TempDir::new().unwrap();
+        let global = write(
+            &dir,
+            "config.toml",
+            &format!(
+                "[projects]\ntrusted = [{:?}]\n[[filters.custom]]\nname = \"g\"\ncommand = \"x\"\n",
+                dir.path()
+            ),
+        );
+        let local = write(
+            &dir,
+            ".rtk.toml",
+            "[filters]\nignore_dirs = [\"dist\"]\n[[filters.custom]]\nname = \"l\"\ncommand = \"x\"\n",
+        );
+
+        let layered = LayeredConfig::load_from(global, Some(local), |_| None).unwrap();
+        let names: Vec<&str> = layered
+            .config
+            .filters
+            .custom
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["l", "g"]);
+        assert_eq!(layered.config.filters.ignore_dirs, vec!["dist"]);
+
+        let rendered = render_with_sources(&layered);
+        assert!(rendered.contains("[[filters.custom]]  # local\ncommand = \"x\"\nname = \"l\""));
+        assert!(rendered.contains("[[filters.custom]]  # global\ncommand = \"x\"\nname = \"g\""));
+    }
+
+    #[test]
+    fn test_untrusted_local_cannot_set_restricted_keys() {
+        let dir = TempDir::new().unwrap();
+        let global = write(
+            &dir,
+            "config.toml",
+            "[[filters.custom]]\nname = \"g\"\ncommand = \"x\"\n",
+        );
+        let local = write(
+            &dir,
+            ".rtk.toml",
+            &format!(
+                "[projects]\ntrusted = [{:?}]\n[redact]\nenabled = false\n[tracking]\ndatabase_path = \"/tmp/x.db\"\n[display]\nmax_width = 80\n[[filters.custom]]\nname = \"l\"\ncommand = \"x\"\n",
+                dir.path()
+            ),
+        );
+
+        let layered = LayeredConfig::load_from(global, Some(local), |_| None).unwrap();
+        let config = &layered.config;
+        assert!(config.redact.enabled);
+        assert_eq!(config.tracking.database_path, None);
+        assert_eq!(config.filters.custom.len(), 1);
+        assert_eq!(config.filters.custom[0].name, "g");
+        assert!(config.projects.trusted.is_empty());
+        // Unrestricted keys still apply
+        assert_eq!(config.display.max_width, 80);
+    }
+
+    #[test]
+    fn test_env_alias_and_bad_values() {
+        let dir = TempDir::new().unwrap();
+        let layered =
+            LayeredConfig::load_from(dir.path().join("missing.toml"), None, |var| match var {
+                "RTK_DB_PATH" => Some("/tmp/rtk.db".to_string()),
+                "RTK_DISPLAY_MAX_WIDTH" => Some("wide".to_string()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            layered.config.tracking.database_path,
+            Some(PathBuf::from("/tmp/rtk.db"))
+        );
+        // Unparseable override is ignored
+        assert_eq!(layered.config.display.max_width, 120);
+    }
+
+    #[test]
+    fn test_timeouts_per_family() {
+        let dir = TempDir::new().unwrap();
+        let global = write(
+            &dir,
+            "config.toml",
+            "[timeouts]\ndefault = \"10m\"\npytest = \"90s\"\nkubectl = \"0\"\n",
+        );
+        let layered = LayeredConfig::load_from(global, None, |var| {
+            (var == "RTK_TIMEOUTS_DEFAULT").then(|| "20m".to_string())
+        })
+        .unwrap();
+        let timeouts = &layered.config.timeouts;
+        assert_eq!(timeouts.for_family("pytest"), Some(Duration::from_secs(90)));
+        assert_eq!(
+            timeouts.for_family("cargo"),
+            Some(Duration::from_secs(1200))
+        );
+        assert_eq!(timeouts.for_family("kubectl"), None);
+        assert_eq!(TimeoutConfig::default().for_family("cargo"), None);
+    }
+
+    #[test]
+    fn test_find_local_config_searches_upward() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_local_config(&nested), None);
+
+        let path = write(&dir, ".rtk.toml", "");
+        assert_eq!(find_local_config(&nested), Some(path));
+    }
+}
@@ -1,7 +1,9 @@
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::ffi::OsString;
-use std::process::Command;
+use std::process::{Command, Output};
 
 #[derive(Debug, Clone, Copy)]
 pub enum ContainerCmd {
@@ -29,7 +31,7 @@ fn docker_ps(_verbose: u8) -> Result<()> {
 
     let raw = Command::new("docker")
         .args(["ps"])
-        .output()
+        .output_timed()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
@@ -39,15 +41,16 @@ fn docker_ps(_verbose: u8) -> Result<()> {
             "--format",
             "{{.ID}}\t{{.Names}}\t{{.Status}}\t{{.Image}}\t{{.Ports}}",
         ])
-        .output()
+        .output_timed()
         .context("Failed to run docker ps")?;
+    exit_if_failed(&output);
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let mut rtk = String::new();
 
     if stdout.trim().is_empty() {
         rtk.push_str("🐳 0 containers");
-        println!("{}", rtk);
+        render_println!("{}", rtk);
         timer.track("docker ps", "rtk docker ps", &raw, &rtk);
         return Ok(());
     }
@@ -76,7 +79,7 @@ fn docker_ps(_verbose: u8) -> Result<()> {
         rtk.push_str(&format!("  ... +{} more", count - 15));
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track("docker ps", "rtk docker ps", &raw, &rtk);
     Ok(())
 }
@@ -86,14 +89,15 @@ fn docker_images(_verbose: u8) -> Result<()> {
 
     let raw = Command::new("docker")
         .args(["images"])
-        .output()
+        .output_timed()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("docker")
         .args(["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.Size}}"])
-        .output()
+        .output_timed()
         .context("Failed to run docker images")?;
+    exit_if_failed(&output);
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let lines: Vec<&str> = stdout.lines().collect();
@@ -101,7 +105,7 @@ fn docker_images(_verbose: u8) -> Result<()> {
 
     if lines.is_empty() {
         rtk.push_str("🐳 0 images");
-        println!("{}", rtk);
+        render_println!("{}", rtk);
         timer.track("docker images", "rtk docker images", &raw, &rtk);
         return Ok(());
     }
@@ -146,7 +150,7 @@ fn docker_images(_verbose: u8) -> Result<()> {
         rtk.push_str(&format!("  ... +{} more", lines.len() - 15));
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track("docker images", "rtk docker images", &raw, &rtk);
     Ok(())
 }
@@ -156,13 +160,13 @@ fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
 
     let container = args.first().map(|s| s.as_str()).unwrap_or("");
     if container.is_empty() {
-        println!("Usage: rtk docker logs <container>");
+        render_println!("Usage: rtk docker logs <container>");
         return Ok(());
     }
 
     let output = Command::new("docker")
         .args(["logs", "--tail", "100", container])
-        .output()
+        .output_timed()
         .context("Failed to run docker logs")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -171,13 +175,15 @@ fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
 
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("🐳 Logs for {}:\n{}", container, analyzed);
-    println!("{}", rtk);
+    render_println!("{}", rtk);
+    tee::print_hint(&format!("docker logs {}", container), &raw, &rtk);
     timer.track(
         &format!("docker logs {}", container),
         "rtk docker logs",
         &raw,
         &rtk,
     );
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &rtk);
     Ok(())
 }
 
@@ -190,7 +196,10 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl get pods")?;
+    let output = cmd
+        .output_timed()
+        .context("Failed to run kubectl get pods")?;
+    exit_if_failed(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let mut rtk = String::new();
 
@@ -198,7 +207,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         Ok(v) => v,
         Err(_) => {
             rtk.push_str("☸️  No pods found");
-            println!("{}", rtk);
+            render_println!("{}", rtk);
             timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
             return Ok(());
         }
@@ -207,7 +216,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
     let items = json["items"].as_array();
     if items.is_none() || items.unwrap().is_empty() {
         rtk.push_str("☸️  No pods found");
-        println!("{}", rtk);
+        render_println!("{}", rtk);
         timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
         return Ok(());
     }
@@ -277,7 +286,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         }
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
     Ok(())
 }
@@ -291,7 +300,10 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl get services")?;
+    let output = cmd
+        .output_timed()
+        .context("Failed to run kubectl get services")?;
+    exit_if_failed(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let mut rtk = String::new();
 
@@ -299,7 +311,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         Ok(v) => v,
         Err(_) => {
             rtk.push_str("☸️  No services found");
-            println!("{}", rtk);
+            render_println!("{}", rtk);
             timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
             return Ok(());
         }
@@ -308,7 +320,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
     let items = json["items"].as_array();
     if items.is_none() || items.unwrap().is_empty() {
         rtk.push_str("☸️  No services found");
-        println!("{}", rtk);
+        render_println!("{}", rtk);
         timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
         return Ok(());
     }
@@ -351,7 +363,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         rtk.push_str(&format!("  ... +{} more", services.len() - 15));
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
     Ok(())
 }
@@ -361,7 +373,7 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
 
     let pod = args.first().map(|s| s.as_str()).unwrap_or("");
     if pod.is_empty() {
-        println!("Usage: rtk kubectl logs <pod>");
+        render_println!("Usage: rtk kubectl logs <pod>");
         return Ok(());
     }
 
@@ -371,11 +383,13 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run kubectl logs")?;
+    let output = cmd.output_timed().context("Failed to run kubectl logs")?;
+    exit_if_failed(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("☸️  Logs for {}:\n{}", pod, analyzed);
-    println!("{}", rtk);
+    render_println!("{}", rtk);
+    tee::print_hint(&format!("kubectl logs {}", pod), &raw, &rtk);
     timer.track(
         &format!("kubectl logs {}", pod),
         "rtk kubectl logs",
@@ -385,6 +399,14 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// Print stderr and exit with the command's code if it failed
+fn exit_if_failed(output: &Output) {
+    if !output.status.success() {
+        render_eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        crate::utils::exit(output.status.code().unwrap_or(1));
+    }
+}
+
 fn compact_ports(ports: &str) -> String {
     if ports.is_empty() {
         return "-".to_string();
@@ -412,11 +434,11 @@ pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("docker passthrough: {:?}", args);
+        render_eprintln!("docker passthrough: {:?}", args);
     }
     let status = Command::new("docker")
         .args(args)
-        .status()
+        .status_timed()
         .context("Failed to run docker")?;
 
     let args_str = tracking::args_display(args);
@@ -426,7 +448,7 @@ pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
     Ok(())
 }
@@ -436,11 +458,11 @@ pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("kubectl passthrough: {:?}", args);
+        render_eprintln!("kubectl passthrough: {:?}", args);
     }
     let status = Command::new("kubectl")
         .args(args)
-        .status()
+        .status_timed()
         .context("Failed to run kubectl")?;
 
     let args_str = tracking::args_display(args);
@@ -450,7 +472,7 @@ pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
     Ok(())
 }
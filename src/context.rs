@@ -0,0 +1,280 @@
+//! Display, filter and tracking settings shared by every command.
+//!
+//! Built once from the layered config (see [`crate::config`]). rtk's own
+//! output is printed with `render_println!` / `render_eprintln!` (defined in
+//! `main.rs`), which go through [`render`], so secret redaction,
+//! `display.emoji = false` and `display.colors = false` apply to every module
+//! without each one checking the config. Content that is not rtk's own (stored
+//! raw output, patches, file contents, matched lines) is printed with [`raw`]
+//! instead, which leaves it untouched.
+
+use crate::config::{Config, RedactPattern, Tokenizer};
+use crate::parser::OutputFormat;
+use crate::redact;
+use crate::utils::{glob_match, strip_ansi};
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static CONTEXT: OnceLock<Context> = OnceLock::new();
+
+pub struct Context {
+    pub emoji: bool,
+    pub colors: bool,
+    /// Upper bound for truncated lines (0 = no limit)
+    pub max_width: usize,
+    /// Output budget in tokens (`--max-tokens`, `display.max_tokens`)
+    pub max_tokens: Option<usize>,
+    /// `--format` (command line only)
+    pub format: OutputFormat,
+    /// Report changes since the last run (`--delta`, `display.delta`)
+    pub delta: bool,
+    /// Limit for the wrapped command (`--timeout`, `timeouts.<family>`)
+    pub timeout: Option<Duration>,
+    pub tracking_enabled: bool,
+    pub history_days: u32,
+    pub tokenizer: Tokenizer,
+    pub ignore_dirs: Vec<String>,
+    pub ignore_files: Vec<String>,
+    /// Mask secrets in output and stores (`redact.enabled`)
+    pub redact: bool,
+    pub redact_patterns: Vec<RedactPattern>,
+}
+
+impl Context {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            emoji: config.display.emoji,
+            colors: config.display.colors,
+            max_width: config.display.max_width,
+            max_tokens: Some(config.display.max_tokens).filter(|n| *n > 0),
+            format: OutputFormat::Text,
+            delta: config.display.delta,
+            timeout: None,
+            tracking_enabled: config.tracking.enabled,
+            history_days: config.tracking.history_days,
+            tokenizer: config.tracking.tokenizer,
+            ignore_dirs: config.filters.ignore_dirs.clone(),
+            ignore_files: config.filters.ignore_files.clone(),
+            redact: config.redact.enabled,
+            redact_patterns: config.redact.patterns.clone(),
+        }
+    }
+
+    /// Line width for a module whose own limit is `default`, capped by `display.max_width`
+    pub fn width(&self, default: usize) -> usize {
+        if self.max_width == 0 {
+            default
+        } else {
+            default.min(self.max_width)
+        }
+    }
+
+    /// Directory name matches `filters.ignore_dirs`
+    pub fn is_ignored_dir(&self, name: &str) -> bool {
+        self.ignore_dirs.iter().any(|p| glob_match(p, name))
+    }
+
+    /// File name matches `filters.ignore_files`
+    pub fn is_ignored_file(&self, name: &str) -> bool {
+        self.ignore_files.iter().any(|p| glob_match(p, name))
+    }
+
+    fn render<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let out = if self.redact {
+            redact::output(text)
+        } else {
+            Cow::Borrowed(text)
+        };
+        match self.display(&out) {
+            Cow::Owned(displayed) => Cow::Owned(displayed),
+            Cow::Borrowed(_) => out,
+        }
+    }
+
+    /// `display.colors` and `display.emoji`
+    fn display<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        if !self.colors && text.contains('\x1b') {
+            out = Cow::Owned(strip_ansi(&out));
+        }
+        if !self.emoji && !text.is_ascii() {
+            out = Cow::Owned(to_ascii(&out));
+        }
+        out
+    }
+}
+
+/// Command-line flags that take precedence over config
+#[derive(Default)]
+pub struct Overrides {
+    pub max_tokens: Option<usize>,
+    pub format: OutputFormat,
+    pub delta: bool,
+    pub timeout: Option<Duration>,
+    /// Subcommand being run, for per-family config (e.g. "pytest")
+    pub family: String,
+}
+
+/// Build the context from config plus CLI overrides (call once, before any output)
+pub fn init(overrides: Overrides) {
+    let config = Config::load().unwrap_or_default();
+    let mut context = Context::from_config(&config);
+    if let Some(max_tokens) = overrides.max_tokens {
+        context.max_tokens = Some(max_tokens).filter(|n| *n > 0);
+    }
+    context.format = overrides.format;
+    context.delta |= overrides.delta;
+    context.timeout = match overrides.timeout {
+        Some(limit) => Some(limit).filter(|l| !l.is_zero()),
+        None => config.timeouts.for_family(&overrides.family),
+    };
+    let _ = CONTEXT.set(context);
+}
+
+/// The process-wide context (config defaults if [`init`] was not called)
+pub fn get() -> &'static Context {
+    CONTEXT.get_or_init(|| Context::from_config(&Config::load().unwrap_or_default()))
+}
+
+/// Shorthand for `get().width(default)`
+pub fn width(default: usize) -> usize {
+    get().width(default)
+}
+
+/// Apply the display settings to a piece of output
+pub fn render(text: &str) -> Cow<'_, str> {
+    get().render(text)
+}
+
+/// Print content that is not rtk's own (`rtk show` output, passthrough
+/// patches, file contents) exactly as given: it only counts against
+/// `--max-tokens`, with no redaction, ANSI stripping or emoji mapping
+pub fn raw(bytes: &[u8]) {
+    if crate::budget::limit().is_some() {
+        crate::budget::print(&String::from_utf8_lossy(bytes));
+        return;
+    }
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(bytes);
+    let _ = stdout.flush();
+}
+
+/// Console output [`render`] held back as a possible private key that never
+/// got its END line; printed before rtk exits
+pub fn render_held() -> String {
+    let ctx = get();
+    if !ctx.redact {
+        return String::new();
+    }
+    ctx.display(&redact::finish_output()).into_owned()
+}
+
+/// Replace status symbols with ASCII markers and drop decorative emoji
+fn to_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(ascii) = ascii_symbol(c) {
+            out.push_str(ascii);
+            if chars.peek() == Some(&'\u{FE0F}') {
+                chars.next();
+            }
+        } else if is_emoji(c) {
+            // "📦 deps" -> "deps"
+            while matches!(chars.peek(), Some('\u{FE0F}' | '\u{200D}')) {
+                chars.next();
+            }
+            if chars.peek() == Some(&' ') {
+                chars.next();
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn ascii_symbol(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        '✅' | '✓' | '✔' => "[ok]",
+        '❌' | '✗' | '✕' | '✖' => "[x]",
+        '⚠' => "[!]",
+        '⏳' | '⌛' => "[..]",
+        '💡' => "[i]",
+        '🟢' => "[+]",
+        '🔴' => "[-]",
+        '🟣' => "[m]",
+        '⚪' => "[ ]",
+        '⭐' => "*",
+        '═' | '━' => "=",
+        '─' => "-",
+        '│' | '┃' | '║' => "|",
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' | '╔' | '╗' | '╚' | '╝' => {
+            "+"
+        }
+        '•' => "*",
+        '×' => "x",
+        '…' => "...",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(emoji: bool, colors: bool) -> Context {
+        let mut config = Config::default();
+        config.display.emoji = emoji;
+        config.display.colors = colors;
+        config.display.max_width = 60;
+        Context::from_config(&config)
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        let ctx = context(false, true);
+        assert_eq!(
+            ctx.render("✓ cargo build (3 crates)"),
+            "[ok] cargo build (3 crates)"
+        );
+        assert_eq!(ctx.render("⚠️  2 warnings"), "[!]  2 warnings");
+        assert_eq!(ctx.render("📦 deps: 4"), "deps: 4");
+        assert_eq!(ctx.render("═══ [×3] done"), "=== [x3] done");
+        // Non-emoji text is left alone
+        assert_eq!(ctx.render("café → naïve"), "café → naïve");
+    }
+
+    #[test]
+    fn test_render_defaults_untouched() {
+        let ctx = context(true, true);
+        assert!(matches!(
+            ctx.render("✅ \x1b[32mok\x1b[0m"),
+            Cow::Borrowed(_)
+        ));
+        let ctx = context(true, false);
+        assert_eq!(ctx.render("\x1b[31merror\x1b[0m"), "error");
+    }
+
+    #[test]
+    fn test_width_and_ignores() {
+        let ctx = context(true, true);
+        assert_eq!(ctx.width(80), 60);
+        assert_eq!(ctx.width(50), 50);
+        assert!(ctx.is_ignored_dir("node_modules"));
+        assert!(ctx.is_ignored_dir("foo.egg-info"));
+        assert!(!ctx.is_ignored_dir("src"));
+        assert!(ctx.is_ignored_file("Cargo.lock"));
+        assert!(!ctx.is_ignored_file("Cargo.toml"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::json_cmd;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -14,10 +15,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: curl -s {}", args.join(" "));
+        render_eprintln!("Running: curl -s {}", args.join(" "));
     }
 
-    let output = cmd.output().context("Failed to run curl")?;
+    let output = cmd.output_timed().context("Failed to run curl")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
@@ -27,15 +28,15 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         } else {
             stderr.trim().to_string()
         };
-        eprintln!("FAILED: curl {}", msg);
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("FAILED: curl {}", msg);
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let raw = stdout.to_string();
 
     // Auto-detect JSON and pipe through filter
     let filtered = filter_curl_output(&stdout);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     timer.track(
         &format!("curl {}", args.join(" ")),
@@ -0,0 +1,283 @@
+//! `rtk run`: user-defined filters from `[[filters.custom]]` in config.toml.
+//!
+//! ```toml
+//! [[filters.custom]]
+//! name = "deploy"
+//! command = "^\\./deploy\\.sh"
+//! drop = ["^\\s*$", "^DEBUG"]
+//! dedupe = true
+//! tail = 30
+//! summary = "^(Deployed|FAILED)"
+//! on_failure = "passthrough"
+//! ```
+
+use crate::config::{Config, CustomFilterConfig, FailurePolicy};
+use crate::context;
+use crate::redact;
+use crate::tee;
+use crate::timeout::TimedCommand;
+use crate::tracking;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::{Regex, RegexSet};
+use std::process::Command;
+
+lazy_static! {
+    static ref FILTERS: Vec<CustomFilter> = load_filters();
+}
+
+/// A `[[filters.custom]]` entry with its regexes compiled
+pub struct CustomFilter {
+    pub name: String,
+    command: Regex,
+    keep: RegexSet,
+    drop: RegexSet,
+    dedupe: bool,
+    head: Option<usize>,
+    tail: Option<usize>,
+    summary: Option<Regex>,
+    on_failure: FailurePolicy,
+}
+
+impl CustomFilter {
+    pub fn new(config: &CustomFilterConfig) -> Result<Self> {
+        let name = if config.name.is_empty() {
+            config.command.clone()
+        } else {
+            config.name.clone()
+        };
+        Ok(Self {
+            command: Regex::new(&config.command).context("invalid command regex")?,
+            keep: RegexSet::new(&config.keep).context("invalid keep regex")?,
+            drop: RegexSet::new(&config.drop).context("invalid drop regex")?,
+            dedupe: config.dedupe,
+            head: config.head,
+            tail: config.tail,
+            summary: config
+                .summary
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .context("invalid summary regex")?,
+            on_failure: config.on_failure,
+            name,
+        })
+    }
+
+    pub fn matches(&self, command: &str) -> bool {
+        self.command.is_match(command)
+    }
+
+    /// Apply keep/drop, dedupe and head/tail to the output; summary lines go last
+    pub fn apply(&self, output: &str) -> String {
+        let mut body: Vec<&str> = Vec::new();
+        let mut summary: Vec<&str> = Vec::new();
+
+        for line in output.lines() {
+            if self.summary.as_ref().is_some_and(|re| re.is_match(line)) {
+                summary.push(line);
+                continue;
+            }
+            if self.drop.is_match(line) {
+                continue;
+            }
+            if !self.keep.is_empty() && !self.keep.is_match(line) {
+                continue;
+            }
+            body.push(line);
+        }
+
+        let mut lines = if self.dedupe {
+            dedupe_lines(&body)
+        } else {
+            body.iter().map(|l| l.to_string()).collect()
+        };
+        lines = limit_lines(lines, self.head, self.tail);
+        lines.extend(summary.iter().map(|l| l.to_string()));
+        lines.join("\n")
+    }
+}
+
+/// Load and compile the configured filters, skipping (and reporting) broken entries
+fn load_filters() -> Vec<CustomFilter> {
+    let config = Config::load().unwrap_or_default();
+    config
+        .filters
+        .custom
+        .iter()
+        .filter_map(|entry| match CustomFilter::new(entry) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                render_eprintln!("rtk: skipping [[filters.custom]] '{}': {:#}", entry.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// First configured filter whose `command` regex matches
+pub fn find(command: &str) -> Option<&'static CustomFilter> {
+    FILTERS.iter().find(|f| f.matches(command))
+}
+
+/// Run a command through its matching custom filter (raw output if none matches)
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    if args.is_empty() {
+        anyhow::bail!("run requires a command to execute\nUsage: rtk run <command> [args...]");
+    }
+
+    let timer = tracking::TimedExecution::start();
+    let command_line = args.join(" ");
+    let filter = find(&command_line);
+
+    if verbose > 0 {
+        render_eprintln!(
+            "Running: {} (filter: {})",
+            command_line,
+            filter.map(|f| f.name.as_str()).unwrap_or("none")
+        );
+    }
+
+    let output = Command::new(&args[0])
+        .args(&args[1..])
+        .output_timed()
+        .with_context(|| format!("Failed to execute command: {}", args[0]))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw = format!("{}{}", stdout, stderr);
+
+    let filter =
+        filter.filter(|f| output.status.success() || f.on_failure == FailurePolicy::Filter);
+    let filtered = match filter {
+        Some(f) => {
+            let filtered = f.apply(&raw);
+            if !filtered.is_empty() {
+                context::raw(redact::text(&format!("{}\n", filtered)).as_bytes());
+            }
+            tee::print_hint(&command_line, &raw, &filtered);
+            filtered
+        }
+        None => {
+            context::raw(redact::text(&stdout).as_bytes());
+            eprint!("{}", redact::text(&stderr));
+            raw.clone()
+        }
+    };
+
+    timer.track(
+        &command_line,
+        &format!("rtk run {}", command_line),
+        &raw,
+        &filtered,
+    );
+
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
+    Ok(())
+}
+
+/// Collapse consecutive identical lines into `[×N] line`
+fn dedupe_lines(lines: &[&str]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let count = lines[i..].iter().take_while(|l| **l == lines[i]).count();
+        if count > 1 {
+            result.push(format!("[×{}] {}", count, lines[i]));
+        } else {
+            result.push(lines[i].to_string());
+        }
+        i += count;
+    }
+    result
+}
+
+/// Keep the first `head` and last `tail` lines, marking what was cut
+fn limit_lines(lines: Vec<String>, head: Option<usize>, tail: Option<usize>) -> Vec<String> {
+    let total = lines.len();
+    let (head, tail) = (head.unwrap_or(0), tail.unwrap_or(0));
+    if (head == 0 && tail == 0) || total <= head + tail {
+        return lines;
+    }
+
+    let mut result: Vec<String> = lines[..head].to_vec();
+    result.push(format!("... {} lines omitted", total - head - tail));
+    result.extend_from_slice(&lines[total - tail..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter_from(toml_src: &str) -> CustomFilter {
+        let config: Config = toml::from_str(toml_src).unwrap();
+        CustomFilter::new(&config.filters.custom[0]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_config_entry() {
+        let config: Config = toml::from_str(
+            r#"
+[[filters.custom]]
+name = "deploy"
+command = "^\\./deploy\\.sh"
+on_failure = "passthrough"
+"#,
+        )
+        .unwrap();
+        let entry = &config.filters.custom[0];
+        assert_eq!(entry.name, "deploy");
+        assert_eq!(entry.on_failure, FailurePolicy::Passthrough);
+        assert!(!config.filters.ignore_dirs.is_empty());
+    }
+
+    #[test]
+    fn test_keep_drop_and_summary() {
+        let filter = filter_from(
+            r#"
+[[filters.custom]]
+command = "^deploy"
+keep = ["^(INFO|ERROR)"]
+drop = ["healthcheck"]
+summary = "^Deployed"
+"#,
+        );
+        assert!(filter.matches("deploy --prod"));
+        assert!(!filter.matches("./other"));
+
+        let output =
+            "Deployed v2 in 3s\nDEBUG conn\nINFO step 1\nINFO healthcheck ok\nERROR retry\n";
+        assert_eq!(
+            filter.apply(output),
+            "INFO step 1\nERROR retry\nDeployed v2 in 3s"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_and_limits() {
+        let filter = filter_from(
+            r#"
+[[filters.custom]]
+command = "x"
+dedupe = true
+head = 1
+tail = 1
+"#,
+        );
+        let output = "start\nwaiting\nwaiting\nwaiting\nstep\nend\n";
+        assert_eq!(filter.apply(output), "start\n... 2 lines omitted\nend");
+
+        let short = filter_from("[[filters.custom]]\ncommand = \"x\"\ndedupe = true\n");
+        assert_eq!(short.apply("a\na\nb"), "[×2] a\nb");
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        let config = CustomFilterConfig {
+            command: "(".into(),
+            ..Default::default()
+        };
+        assert!(CustomFilter::new(&config).is_err());
+    }
+}
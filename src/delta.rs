@@ -0,0 +1,405 @@
+//! Delta mode (`--delta`, `display.delta`): report what changed since the
+//! previous run of the same command in the same directory.
+//!
+//! Test and lint wrappers hand their parsed [`TestResult`] / [`LintResult`] to
+//! [`report`]. The result is saved under `~/.local/share/rtk/delta/`, one file
+//! per (cwd, command) pair, and compared with the previous one: newly failing
+//! items are shown in full, fixed and still-failing ones by name only.
+
+use crate::context;
+use crate::parser::{LintResult, ParseResult, TestResult};
+use crate::redact;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// New items shown in full before "+N more"
+const MAX_NEW: usize = 20;
+
+/// Fixed / still-failing names listed before "+N more"
+const MAX_NAMES: usize = 10;
+
+/// A parsed result whose failing items can be compared across runs
+pub trait Diffable: Serialize + DeserializeOwned {
+    /// Stored with the snapshot so a lint result is never compared to a test result
+    const KIND: &'static str;
+
+    /// (identity, name, detail) of each failing item. The identity ignores
+    /// line numbers, which shift while the agent edits the file.
+    fn items(&self) -> Vec<(String, String, String)>;
+
+    /// Counts for the summary line
+    fn totals(&self) -> String;
+}
+
+impl Diffable for TestResult {
+    const KIND: &'static str = "test";
+
+    fn items(&self) -> Vec<(String, String, String)> {
+        self.failures
+            .iter()
+            .map(|f| {
+                let detail = f
+                    .error_message
+                    .lines()
+                    .map(str::trim)
+                    .find(|l| !l.is_empty())
+                    .unwrap_or("")
+                    .to_string();
+                (
+                    format!("{}::{}", f.file_path, f.test_name),
+                    f.test_name.clone(),
+                    detail,
+                )
+            })
+            .collect()
+    }
+
+    fn totals(&self) -> String {
+        let mut totals = format!("{} failed, {} passed", self.failed, self.passed);
+        if self.skipped > 0 {
+            totals.push_str(&format!(", {} skipped", self.skipped));
+        }
+        totals
+    }
+}
+
+impl Diffable for LintResult {
+    const KIND: &'static str = "lint";
+
+    fn items(&self) -> Vec<(String, String, String)> {
+        self.issues
+            .iter()
+            .map(|i| {
+                (
+                    format!("{}|{}|{}", i.file_path, i.rule_id, i.message),
+                    format!("{}:{}", i.file_path, i.line),
+                    format!("{} {}", i.rule_id, i.message).trim().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn totals(&self) -> String {
+        format!("{} errors, {} warnings", self.errors, self.warnings)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot<T> {
+    kind: String,
+    cwd: String,
+    command: String,
+    result: T,
+}
+
+/// `--delta` or `display.delta` is on
+pub fn requested() -> bool {
+    context::get().delta
+}
+
+/// In delta mode, compare the parsed result of `command` with its previous run
+/// here and save it for the next one.
+///
+/// Returns the delta report, or `None` when the caller should print its normal
+/// output: delta mode off, first run, unparseable output, or a `--timeout` kill
+/// (a partial result would report every unseen failure as fixed).
+pub fn report<T: Diffable>(
+    command: &str,
+    parse: impl FnOnce() -> ParseResult<T>,
+) -> Option<String> {
+    if !requested() || crate::timeout::expired() {
+        return None;
+    }
+    let result = parse();
+    result.note();
+    match result {
+        ParseResult::Full(data) | ParseResult::Degraded(data, _) => compare(command, data),
+        ParseResult::Passthrough(_) => None,
+    }
+}
+
+/// [`report`] for a result the caller has already parsed (and noted)
+pub fn report_parsed<T: Diffable + Clone>(
+    command: &str,
+    result: &ParseResult<T>,
+) -> Option<String> {
+    if !requested() || crate::timeout::expired() {
+        return None;
+    }
+    match result {
+        ParseResult::Full(data) | ParseResult::Degraded(data, _) => compare(command, data.clone()),
+        ParseResult::Passthrough(_) => None,
+    }
+}
+
+fn compare<T: Diffable>(command: &str, current: T) -> Option<String> {
+    // Snapshots are stored redacted, so compare redacted against redacted
+    let command = redact::text(command.trim());
+    let command: &str = &command;
+    let current = redacted(current);
+    let cwd = std::env::current_dir()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+
+    let dir = delta_dir();
+    let previous = load_in::<T>(&dir, &cwd, command);
+    if let Err(e) = save_in(&dir, &cwd, command, &current) {
+        render_eprintln!("rtk: delta state not saved: {}", e);
+    }
+    previous.map(|previous| render(command, &previous, &current))
+}
+
+/// `result` with secrets in its strings (messages, test names) redacted
+fn redacted<T: Diffable>(result: T) -> T {
+    let Ok(mut value) = serde_json::to_value(&result) else {
+        return result;
+    };
+    redact_strings(&mut value);
+    serde_json::from_value(value).unwrap_or(result)
+}
+
+fn redact_strings(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            if let Cow::Owned(replaced) = redact::text(text) {
+                *text = replaced;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_strings),
+        Value::Object(fields) => fields.values_mut().for_each(redact_strings),
+        _ => {}
+    }
+}
+
+/// Directory holding the last results (`RTK_DELTA_DIR` overrides the default)
+fn delta_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("RTK_DELTA_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rtk")
+        .join("delta")
+}
+
+fn snapshot_path(dir: &Path, cwd: &str, command: &str) -> PathBuf {
+    dir.join(format!(
+        "{:016x}.json",
+        crate::utils::stable_hash(&[cwd, command])
+    ))
+}
+
+fn load_in<T: Diffable>(dir: &Path, cwd: &str, command: &str) -> Option<T> {
+    let content = fs::read_to_string(snapshot_path(dir, cwd, command)).ok()?;
+    let snapshot: Snapshot<T> = serde_json::from_str(&content).ok()?;
+    // Guard against hash collisions and a command switching parsers
+    (snapshot.kind == T::KIND && snapshot.cwd == cwd && snapshot.command == command)
+        .then_some(snapshot.result)
+}
+
+fn save_in<T: Diffable>(dir: &Path, cwd: &str, command: &str, result: &T) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create delta directory: {}", dir.display()))?;
+    let snapshot = Snapshot {
+        kind: T::KIND.to_string(),
+        cwd: cwd.to_string(),
+        command: command.to_string(),
+        result,
+    };
+    let path = snapshot_path(dir, cwd, command);
+    fs::write(&path, serde_json::to_string(&snapshot)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    prune(dir, u64::from(context::get().history_days));
+    Ok(())
+}
+
+/// Drop results older than `tracking.history_days` (0 = keep forever)
+fn prune(dir: &Path, history_days: u64) {
+    if history_days == 0 {
+        return;
+    }
+    let Some(cutoff) = SystemTime::now().checked_sub(Duration::from_secs(history_days * 24 * 3600))
+    else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let modified = entry.metadata().and_then(|m| m.modified());
+        if modified.is_ok_and(|m| m < cutoff) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn render<T: Diffable>(command: &str, previous: &T, current: &T) -> String {
+    let before = previous.items();
+    let after = current.items();
+    let before_keys: HashSet<&str> = before.iter().map(|(key, _, _)| key.as_str()).collect();
+    let after_keys: HashSet<&str> = after.iter().map(|(key, _, _)| key.as_str()).collect();
+
+    let new: Vec<_> = after
+        .iter()
+        .filter(|(key, _, _)| !before_keys.contains(key.as_str()))
+        .collect();
+    let still: Vec<_> = after
+        .iter()
+        .filter(|(key, _, _)| before_keys.contains(key.as_str()))
+        .collect();
+    let fixed: Vec<_> = before
+        .iter()
+        .filter(|(key, _, _)| !after_keys.contains(key.as_str()))
+        .collect();
+
+    let mut out = if new.is_empty() && fixed.is_empty() {
+        format!(
+            "{}: no change since last run, {} still failing ({})\n",
+            command,
+            still.len(),
+            current.totals()
+        )
+    } else {
+        format!(
+            "{}: {} new, {} fixed, {} still failing ({})\n",
+            command,
+            new.len(),
+            fixed.len(),
+            still.len(),
+            current.totals()
+        )
+    };
+
+    let width = context::width(120);
+    if !new.is_empty() {
+        out.push_str(&format!("\nNew ({}):\n", new.len()));
+        for (_, name, detail) in new.iter().take(MAX_NEW) {
+            out.push_str(&format!("  ✗ {}\n", truncate(name, width)));
+            if !detail.is_empty() {
+                out.push_str(&format!("    {}\n", truncate(detail, width)));
+            }
+        }
+        if new.len() > MAX_NEW {
+            out.push_str(&format!("  ... +{} more\n", new.len() - MAX_NEW));
+        }
+    }
+    if !fixed.is_empty() {
+        out.push_str(&format!("\nFixed ({}):\n", fixed.len()));
+        push_names(&mut out, &fixed, "✓", width);
+    }
+    if !still.is_empty() {
+        out.push_str(&format!("\nStill failing ({}):\n", still.len()));
+        push_names(&mut out, &still, "·", width);
+    }
+    out.trim_end().to_string()
+}
+
+fn push_names(out: &mut String, items: &[&(String, String, String)], mark: &str, width: usize) {
+    for (_, name, _) in items.iter().take(MAX_NAMES) {
+        out.push_str(&format!("  {} {}\n", mark, truncate(name, width)));
+    }
+    if items.len() > MAX_NAMES {
+        out.push_str(&format!("  ... +{} more\n", items.len() - MAX_NAMES));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{LintIssue, LintSeverity, TestFailure};
+
+    fn tests_result(failing: &[&str], passed: usize) -> TestResult {
+        TestResult {
+            total: failing.len() + passed,
+            passed,
+            failed: failing.len(),
+            skipped: 0,
+            duration_ms: None,
+            failures: failing
+                .iter()
+                .map(|name| TestFailure {
+                    test_name: name.to_string(),
+                    file_path: "tests/api.rs".to_string(),
+                    error_message: format!("\nassertion failed in {}\nmore", name),
+                    stack_trace: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_render_new_fixed_still() {
+        let previous = tests_result(&["login", "logout", "refresh"], 10);
+        let current = tests_result(&["logout", "refresh", "signup"], 10);
+        let out = render("cargo test", &previous, &current);
+
+        assert!(
+            out.starts_with("cargo test: 1 new, 1 fixed, 2 still failing (3 failed, 10 passed)")
+        );
+        assert!(out.contains("New (1):\n  ✗ signup\n    assertion failed in signup"));
+        assert!(out.contains("Fixed (1):\n  ✓ login"));
+        assert!(out.contains("Still failing (2):\n  · logout\n  · refresh"));
+        // Details are only repeated for new failures
+        assert!(!out.contains("assertion failed in logout"));
+    }
+
+    #[test]
+    fn test_render_no_change() {
+        let result = tests_result(&["login"], 4);
+        let out = render("pytest", &result, &result);
+        assert!(out.starts_with("pytest: no change since last run, 1 still failing"));
+        assert!(!out.contains("New"));
+    }
+
+    #[test]
+    fn test_lint_identity_ignores_line_shift() {
+        let issue = |line| LintIssue {
+            file_path: "src/app.ts".to_string(),
+            line,
+            column: 1,
+            severity: LintSeverity::Error,
+            rule_id: "TS2322".to_string(),
+            message: "Type 'string' is not assignable".to_string(),
+        };
+        let previous = LintResult::from_issues(vec![issue(10)]);
+        let current = LintResult::from_issues(vec![issue(14)]);
+        let out = render("tsc", &previous, &current);
+        assert!(out.starts_with("tsc: no change since last run, 1 still failing"));
+        assert!(out.contains("src/app.ts:14"));
+    }
+
+    #[test]
+    fn test_save_and_load_per_cwd_and_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = tests_result(&["login"], 2);
+        save_in(dir.path(), "/repo", "cargo test", &result).unwrap();
+
+        let loaded: TestResult = load_in(dir.path(), "/repo", "cargo test").unwrap();
+        assert_eq!(loaded.failures[0].test_name, "login");
+        assert!(load_in::<TestResult>(dir.path(), "/other", "cargo test").is_none());
+        assert!(load_in::<TestResult>(dir.path(), "/repo", "cargo test --lib").is_none());
+        // Same key, different parser
+        assert!(load_in::<LintResult>(dir.path(), "/repo", "cargo test").is_none());
+    }
+
+    #[test]
+    fn test_snapshot_redacted() {
+        let mut result = tests_result(&["login"], 2);
+        result.failures[0].error_message =
+            "request failed: DATABASE_PASSWORD=s3cr3tP4ssw0rd!".to_string();
+        let result = redacted(result);
+        assert_eq!(
+            result.failures[0].error_message,
+            "request failed: DATABASE_PASSWORD=[REDACTED:secret]"
+        );
+        assert_eq!(result.failures[0].test_name, "login");
+    }
+}
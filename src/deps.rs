@@ -15,7 +15,7 @@ pub fn run(path: &Path, verbose: u8) -> Result<()> {
     };
 
     if verbose > 0 {
-        eprintln!("Scanning dependencies in: {}", dir.display());
+        render_eprintln!("Scanning dependencies in: {}", dir.display());
     }
 
     let mut found = false;
@@ -66,7 +66,7 @@ pub fn run(path: &Path, verbose: u8) -> Result<()> {
         rtk.push_str(&format!("No dependency files found in {}", dir.display()));
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track("cat */deps", "rtk deps", &raw, &rtk);
     Ok(())
 }
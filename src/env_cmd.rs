@@ -1,6 +1,6 @@
+use crate::redact;
 use crate::tracking;
 use anyhow::Result;
-use std::collections::HashSet;
 use std::env;
 
 /// Show filtered environment variables (hide sensitive data)
@@ -8,10 +8,9 @@ pub fn run(filter: Option<&str>, show_all: bool, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Environment variables:");
+        render_eprintln!("Environment variables:");
     }
 
-    let sensitive_patterns = get_sensitive_patterns();
     let mut vars: Vec<(String, String)> = env::vars().collect();
     vars.sort_by(|a, b| a.0.cmp(&b.0));
 
@@ -31,9 +30,7 @@ pub fn run(filter: Option<&str>, show_all: bool, verbose: u8) -> Result<()> {
         }
 
         // Check if sensitive
-        let is_sensitive = sensitive_patterns
-            .iter()
-            .any(|p| key.to_lowercase().contains(p));
+        let is_sensitive = redact::is_sensitive_key(key);
 
         let display_value = if is_sensitive && !show_all {
             mask_value(value)
@@ -62,52 +59,52 @@ pub fn run(filter: Option<&str>, show_all: bool, verbose: u8) -> Result<()> {
 
     // Print categorized
     if !path_vars.is_empty() {
-        println!("📂 PATH Variables:");
+        render_println!("📂 PATH Variables:");
         for (k, v) in &path_vars {
             if k == "PATH" {
                 // Split PATH for readability
                 let paths: Vec<&str> = v.split(':').collect();
-                println!("  PATH ({} entries):", paths.len());
+                render_println!("  PATH ({} entries):", paths.len());
                 for p in paths.iter().take(5) {
-                    println!("    {}", p);
+                    render_println!("    {}", p);
                 }
                 if paths.len() > 5 {
-                    println!("    ... +{} more", paths.len() - 5);
+                    render_println!("    ... +{} more", paths.len() - 5);
                 }
             } else {
-                println!("  {}={}", k, v);
+                render_println!("  {}={}", k, v);
             }
         }
     }
 
     if !lang_vars.is_empty() {
-        println!("\n🔧 Language/Runtime:");
+        render_println!("\n🔧 Language/Runtime:");
         for (k, v) in &lang_vars {
-            println!("  {}={}", k, v);
+            render_println!("  {}={}", k, v);
         }
     }
 
     if !cloud_vars.is_empty() {
-        println!("\n☁️  Cloud/Services:");
+        render_println!("\n☁️  Cloud/Services:");
         for (k, v) in &cloud_vars {
-            println!("  {}={}", k, v);
+            render_println!("  {}={}", k, v);
         }
     }
 
     if !tool_vars.is_empty() {
-        println!("\n🛠️  Tools:");
+        render_println!("\n🛠️  Tools:");
         for (k, v) in &tool_vars {
-            println!("  {}={}", k, v);
+            render_println!("  {}={}", k, v);
         }
     }
 
     if !other_vars.is_empty() {
-        println!("\n📋 Other:");
+        render_println!("\n📋 Other:");
         for (k, v) in other_vars.iter().take(20) {
-            println!("  {}={}", k, v);
+            render_println!("  {}={}", k, v);
         }
         if other_vars.len() > 20 {
-            println!("  ... +{} more", other_vars.len() - 20);
+            render_println!("  ... +{} more", other_vars.len() - 20);
         }
     }
 
@@ -118,7 +115,7 @@ pub fn run(filter: Option<&str>, show_all: bool, verbose: u8) -> Result<()> {
         + tool_vars.len()
         + other_vars.len().min(20);
     if filter.is_none() {
-        println!("\n📊 Total: {} vars (showing {} relevant)", total, shown);
+        render_println!("\n📊 Total: {} vars (showing {} relevant)", total, shown);
     }
 
     let raw: String = vars.iter().map(|(k, v)| format!("{}={}\n", k, v)).collect();
@@ -127,22 +124,6 @@ pub fn run(filter: Option<&str>, show_all: bool, verbose: u8) -> Result<()> {
     Ok(())
 }
 
-fn get_sensitive_patterns() -> HashSet<&'static str> {
-    let mut set = HashSet::new();
-    set.insert("key");
-    set.insert("secret");
-    set.insert("password");
-    set.insert("token");
-    set.insert("credential");
-    set.insert("auth");
-    set.insert("private");
-    set.insert("api_key");
-    set.insert("apikey");
-    set.insert("access_key");
-    set.insert("jwt");
-    set
-}
-
 fn mask_value(value: &str) -> String {
     let chars: Vec<char> = value.chars().collect();
     if chars.len() <= 4 {
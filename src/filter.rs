@@ -394,7 +394,7 @@ mod tests {
         let code = r#"
 // This is a comment
 fn main() {
-    println!("Hello");
+    render_println!("Hello");
 }
 "#;
         let filter = MinimalFilter;
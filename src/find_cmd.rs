@@ -1,28 +1,11 @@
+use crate::context;
 use crate::tracking;
+use crate::utils::glob_match;
 use anyhow::Result;
 use ignore::WalkBuilder;
 use std::collections::HashMap;
 use std::path::Path;
 
-/// Match a filename against a glob pattern (supports `*` and `?`).
-fn glob_match(pattern: &str, name: &str) -> bool {
-    glob_match_inner(pattern.as_bytes(), name.as_bytes())
-}
-
-fn glob_match_inner(pat: &[u8], name: &[u8]) -> bool {
-    match (pat.first(), name.first()) {
-        (None, None) => true,
-        (Some(b'*'), _) => {
-            // '*' matches zero or more characters
-            glob_match_inner(&pat[1..], name)
-                || (!name.is_empty() && glob_match_inner(pat, &name[1..]))
-        }
-        (Some(b'?'), Some(_)) => glob_match_inner(&pat[1..], &name[1..]),
-        (Some(&p), Some(&n)) if p == n => glob_match_inner(&pat[1..], &name[1..]),
-        _ => false,
-    }
-}
-
 pub fn run(
     pattern: &str,
     path: &str,
@@ -36,7 +19,7 @@ pub fn run(
     let effective_pattern = if pattern == "." { "*" } else { pattern };
 
     if verbose > 0 {
-        eprintln!("find: {} in {}", effective_pattern, path);
+        render_eprintln!("find: {} in {}", effective_pattern, path);
     }
 
     let want_dirs = file_type == "d";
@@ -46,6 +29,12 @@ pub fn run(
         .git_ignore(true) // respect .gitignore
         .git_global(true)
         .git_exclude(true)
+        // skip filters.ignore_dirs (the search root itself is always walked)
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_some_and(|t| t.is_dir())
+                || !context::get().is_ignored_dir(&e.file_name().to_string_lossy())
+        })
         .build();
 
     let mut files: Vec<String> = Vec::new();
@@ -97,7 +86,7 @@ pub fn run(
 
     if files.is_empty() {
         let msg = format!("0 for '{}'", effective_pattern);
-        println!("{}", msg);
+        render_println!("{}", msg);
         timer.track(
             &format!("find {} -name '{}'", path, effective_pattern),
             "rtk find",
@@ -129,8 +118,8 @@ pub fn run(
     let dirs_count = dirs.len();
     let total_files = files.len();
 
-    println!("📁 {}F {}D:", total_files, dirs_count);
-    println!();
+    render_println!("📁 {}F {}D:", total_files, dirs_count);
+    render_println!();
 
     // Display with proper --max limiting (count individual files)
     let mut shown = 0;
@@ -148,7 +137,7 @@ pub fn run(
 
         let remaining_budget = max_results - shown;
         if files_in_dir.len() <= remaining_budget {
-            println!("{}/ {}", dir_display, files_in_dir.join(" "));
+            render_println!("{}/ {}", dir_display, files_in_dir.join(" "));
             shown += files_in_dir.len();
         } else {
             // Partial display: show only what fits in budget
@@ -157,14 +146,14 @@ pub fn run(
                 .take(remaining_budget)
                 .cloned()
                 .collect();
-            println!("{}/ {}", dir_display, partial.join(" "));
+            render_println!("{}/ {}", dir_display, partial.join(" "));
             shown += partial.len();
             break;
         }
     }
 
     if shown < total_files {
-        println!("+{} more", total_files - shown);
+        render_println!("+{} more", total_files - shown);
     }
 
     // Extension summary
@@ -179,7 +168,7 @@ pub fn run(
 
     let mut ext_line = String::new();
     if by_ext.len() > 1 {
-        println!();
+        render_println!();
         let mut exts: Vec<_> = by_ext.iter().collect();
         exts.sort_by(|a, b| b.1.cmp(a.1));
         let ext_str: Vec<String> = exts
@@ -188,7 +177,7 @@ pub fn run(
             .map(|(e, c)| format!(".{}({})", e, c))
             .collect();
         ext_line = format!("ext: {}", ext_str.join(" "));
-        println!("{}", ext_line);
+        render_println!("{}", ext_line);
     }
 
     let rtk_output = format!("{}F {}D + {}", total_files, dirs_count, ext_line);
@@ -1,5 +1,7 @@
 use crate::prettier_cmd;
 use crate::ruff_cmd;
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::package_manager_exec;
 use anyhow::{Context, Result};
@@ -65,8 +67,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     };
 
     if verbose > 0 {
-        eprintln!("Detected formatter: {}", formatter);
-        eprintln!("Arguments: {}", args[start_idx..].join(" "));
+        render_eprintln!("Detected formatter: {}", formatter);
+        render_eprintln!("Arguments: {}", args[start_idx..].join(" "));
     }
 
     // Build command based on formatter
@@ -107,10 +109,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: {} {}", formatter, user_args.join(" "));
+        render_eprintln!("Running: {} {}", formatter, user_args.join(" "));
     }
 
-    let output = cmd.output().context(format!(
+    let output = cmd.output_timed().context(format!(
         "Failed to run {}. Is it installed? Try: pip install {} (or npm/pnpm for JS formatters)",
         formatter, formatter
     ))?;
@@ -127,7 +129,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         _ => raw.trim().to_string(),
     };
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("{} {}", formatter, user_args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("{} {}", formatter, user_args.join(" ")),
@@ -137,9 +140,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
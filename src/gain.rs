@@ -1,67 +1,175 @@
 use crate::display_helpers::{format_duration, print_period_table};
-use crate::tracking::{DayStats, MonthStats, Tracker, WeekStats};
-use crate::utils::format_tokens;
+use crate::tracking::{
+    get_db_path, is_exact_tokenizer, BundleRow, CommandDetail, DayStats, GainSummary, MonthStats,
+    ParseRun, ProjectStats, Scope, Tracker, WeekStats,
+};
+use crate::utils::{format_tokens, project_root_of, truncate};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-pub fn run(
-    graph: bool,
-    history: bool,
-    quota: bool,
-    tier: &str,
-    daily: bool,
-    weekly: bool,
-    monthly: bool,
-    all: bool,
-    format: &str,
-    _verbose: u8,
-) -> Result<()> {
-    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+/// Runs listed under "Worst runs" in the `--command` drill-down
+const WORST_RUNS: usize = 5;
+
+/// Rise of the degraded/passthrough rate (percentage points) reported by `--health`
+const HEALTH_MIN_RISE: f64 = 5.0;
+
+/// Two-proportion z-score below which `--health` treats a rise as chance
+const HEALTH_MIN_Z: f64 = 2.0;
+
+/// Row filters as given on the `rtk gain` command line
+#[derive(Debug, Default)]
+pub struct Filters {
+    /// `--project [PATH]`
+    pub project: Option<String>,
+    /// `--session <ID>`
+    pub session: Option<String>,
+    /// `--since <WHEN>`
+    pub since: Option<String>,
+    /// `--until <WHEN>`
+    pub until: Option<String>,
+    /// `--command <GLOB>`
+    pub command: Option<String>,
+}
+
+impl Filters {
+    /// Resolve paths, dates and globs into the query scope
+    fn scope(&self) -> Result<Scope> {
+        Ok(Scope {
+            project: self.project.as_deref().map(resolve_project).transpose()?,
+            session: self.session.clone(),
+            since: self
+                .since
+                .as_deref()
+                .map(|v| parse_time_bound(v, false))
+                .transpose()?,
+            until: self
+                .until
+                .as_deref()
+                .map(|v| parse_time_bound(v, true))
+                .transpose()?,
+            command: self.command.as_deref().map(command_glob),
+        })
+    }
+}
+
+/// Views, output format and filters of `rtk gain`
+#[derive(Debug, Default)]
+pub struct GainOptions {
+    /// `--graph`
+    pub graph: bool,
+    /// `--history`
+    pub history: bool,
+    /// `--quota`
+    pub quota: bool,
+    /// `--tier <TIER>`
+    pub tier: String,
+    /// `--daily`
+    pub daily: bool,
+    /// `--weekly`
+    pub weekly: bool,
+    /// `--monthly`
+    pub monthly: bool,
+    /// `--all`
+    pub all: bool,
+    /// `--format <FORMAT>`
+    pub format: String,
+    pub filters: Filters,
+    /// `--by-project`
+    pub by_project: bool,
+    /// `--top <N>`
+    pub top: Option<usize>,
+    /// `--output <PATH>`
+    pub output: Option<PathBuf>,
+    /// `--db <PATH>`
+    pub db: Option<PathBuf>,
+}
+
+pub fn run(options: &GainOptions, _verbose: u8) -> Result<()> {
+    let GainOptions {
+        graph,
+        history,
+        quota,
+        daily,
+        weekly,
+        monthly,
+        all,
+        by_project,
+        top,
+        ..
+    } = *options;
+    let tier = options.tier.as_str();
+    let output = options.output.as_deref();
+    let scope = options.filters.scope()?;
+    let tracker = Tracker::open_or_default(options.db.as_deref())
+        .context("Failed to initialize tracking database")?
+        .with_scope(scope.clone());
 
     // Handle export formats
-    match format {
-        "json" => return export_json(&tracker, daily, weekly, monthly, all),
-        "csv" => return export_csv(&tracker, daily, weekly, monthly, all),
+    match options.format.as_str() {
+        "json" => return export_json(&tracker, daily, weekly, monthly, all, by_project, top),
+        "csv" => return export_csv(&tracker, daily, weekly, monthly, all, by_project, top),
+        "html" => return export_html(&tracker, top, output),
+        "openmetrics" => return export_openmetrics(&tracker, output),
         _ => {} // Continue with text format
     }
 
-    let summary = tracker
-        .get_summary()
-        .context("Failed to load token savings summary from database")?;
+    let summary = load_summary(&tracker, top)?;
 
     if summary.total_commands == 0 {
-        println!("No tracking data yet.");
-        println!("Run some rtk commands to start tracking savings.");
+        if scope.is_all() {
+            render_println!("No tracking data yet.");
+            render_println!("Run some rtk commands to start tracking savings.");
+        } else {
+            render_println!("No tracking data for {}.", describe_scope(&scope));
+        }
         return Ok(());
     }
 
     // Default view (summary)
     if !daily && !weekly && !monthly && !all {
-        println!("📊 RTK Token Savings");
-        println!("════════════════════════════════════════");
-        println!();
-
-        println!("Total commands:    {}", summary.total_commands);
-        println!("Input tokens:      {}", format_tokens(summary.total_input));
-        println!("Output tokens:     {}", format_tokens(summary.total_output));
-        println!(
+        render_println!("📊 RTK Token Savings");
+        render_println!("════════════════════════════════════════");
+        if !scope.is_all() {
+            render_println!("Scope: {}", describe_scope(&scope));
+        }
+        render_println!();
+
+        render_println!("Total commands:    {}", summary.total_commands);
+        render_println!("Input tokens:      {}", format_tokens(summary.total_input));
+        render_println!("Output tokens:     {}", format_tokens(summary.total_output));
+        render_println!(
             "Tokens saved:      {} ({:.1}%)",
             format_tokens(summary.total_saved),
             summary.avg_savings_pct
         );
-        println!(
+        render_println!(
             "Total exec time:   {} (avg {})",
             format_duration(summary.total_time_ms),
             format_duration(summary.avg_time_ms)
         );
-        println!();
+        render_println!(
+            "Token counts:      {}",
+            describe_tokenizers(&summary.by_tokenizer)
+        );
+        if summary.timed_out > 0 {
+            render_println!("Timed out:         {} (--timeout)", summary.timed_out);
+        }
+        render_println!();
 
         if !summary.by_command.is_empty() {
-            println!("By Command:");
-            println!("────────────────────────────────────────");
-            println!(
+            render_println!("By Command:");
+            render_println!("────────────────────────────────────────");
+            render_println!(
                 "{:<20} {:>6} {:>10} {:>8} {:>8}",
-                "Command", "Count", "Saved", "Avg%", "Time"
+                "Command",
+                "Count",
+                "Saved",
+                "Avg%",
+                "Time"
             );
             for (cmd, count, saved, pct, avg_time) in &summary.by_command {
                 let cmd_short = if cmd.len() > 18 {
@@ -69,7 +177,7 @@ pub fn run(
                 } else {
                     cmd.clone()
                 };
-                println!(
+                render_println!(
                     "{:<20} {:>6} {:>10} {:>7.1}% {:>8}",
                     cmd_short,
                     count,
@@ -78,21 +186,31 @@ pub fn run(
                     format_duration(*avg_time)
                 );
             }
-            println!();
+            render_println!();
+        }
+
+        if by_project {
+            print_by_project(&tracker.get_by_project()?, top);
+        }
+
+        if let Some(command) = &scope.command {
+            if let Some(detail) = tracker.get_command_detail(WORST_RUNS)? {
+                print_command_detail(command, &detail);
+            }
         }
 
         if graph && !summary.by_day.is_empty() {
-            println!("Daily Savings (last 30 days):");
-            println!("────────────────────────────────────────");
+            render_println!("Daily Savings (last 30 days):");
+            render_println!("────────────────────────────────────────");
             print_ascii_graph(&summary.by_day);
-            println!();
+            render_println!();
         }
 
         if history {
             let recent = tracker.get_recent(10)?;
             if !recent.is_empty() {
-                println!("Recent Commands:");
-                println!("────────────────────────────────────────");
+                render_println!("Recent Commands:");
+                render_println!("────────────────────────────────────────");
                 for rec in recent {
                     let time = rec.timestamp.format("%m-%d %H:%M");
                     let cmd_short = if rec.rtk_cmd.len() > 25 {
@@ -100,7 +218,7 @@ pub fn run(
                     } else {
                         rec.rtk_cmd.clone()
                     };
-                    println!(
+                    render_println!(
                         "{} {:<25} -{:.0}% ({})",
                         time,
                         cmd_short,
@@ -108,7 +226,7 @@ pub fn run(
                         format_tokens(rec.saved_tokens)
                     );
                 }
-                println!();
+                render_println!();
             }
         }
 
@@ -124,24 +242,28 @@ pub fn run(
 
             let quota_pct = (summary.total_saved as f64 / quota_tokens as f64) * 100.0;
 
-            println!("Monthly Quota Analysis:");
-            println!("────────────────────────────────────────");
-            println!("Subscription tier:        {}", tier_name);
-            println!("Estimated monthly quota:  {}", format_tokens(quota_tokens));
-            println!(
+            render_println!("Monthly Quota Analysis:");
+            render_println!("────────────────────────────────────────");
+            render_println!("Subscription tier:        {}", tier_name);
+            render_println!("Estimated monthly quota:  {}", format_tokens(quota_tokens));
+            render_println!(
                 "Tokens saved (lifetime):  {}",
                 format_tokens(summary.total_saved)
             );
-            println!("Quota preserved:          {:.1}%", quota_pct);
-            println!();
-            println!("Note: Heuristic estimate based on ~44K tokens/5h (Pro baseline)");
-            println!("      Actual limits use rolling 5-hour windows, not monthly caps.");
+            render_println!("Quota preserved:          {:.1}%", quota_pct);
+            render_println!();
+            render_println!("Note: Heuristic estimate based on ~44K tokens/5h (Pro baseline)");
+            render_println!("      Actual limits use rolling 5-hour windows, not monthly caps.");
         }
 
         return Ok(());
     }
 
     // Time breakdown views
+    if all || by_project {
+        print_by_project(&tracker.get_by_project()?, top);
+    }
+
     if all || daily {
         print_daily_full(&tracker)?;
     }
@@ -157,6 +279,520 @@ pub fn run(
     Ok(())
 }
 
+/// `rtk gain --db-info`: where the tracking data lives and its schema version
+pub fn run_db_info(format: &str, db: Option<&Path>) -> Result<()> {
+    let tracker = Tracker::open_or_default(db).context("Failed to initialize tracking database")?;
+    let info = tracker.db_info()?;
+    if format == "json" {
+        render_println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let size = if info.size_bytes < 1024 * 1024 {
+        format!("{:.1} KB", info.size_bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", info.size_bytes as f64 / (1024.0 * 1024.0))
+    };
+    render_println!("📦 RTK Tracking Database");
+    render_println!("════════════════════════════════════════");
+    render_println!("Path:              {}", info.path.display());
+    render_println!(
+        "Schema version:    {} (latest {})",
+        info.schema_version,
+        info.latest_version
+    );
+    render_println!("Rows:              {}", info.rows);
+    render_println!("Size:              {}", size);
+    if let (Some(oldest), Some(newest)) = (&info.oldest, &info.newest) {
+        render_println!(
+            "Range:             {} → {}",
+            oldest.get(..10).unwrap_or(oldest),
+            newest.get(..10).unwrap_or(newest)
+        );
+    }
+    Ok(())
+}
+
+/// A command whose parser degraded more often from `since` on
+#[derive(Debug, Serialize)]
+struct Regression {
+    command: String,
+    /// First day (UTC) of the higher rate
+    since: String,
+    /// Degraded + passthrough runs, in percent, before and from `since`
+    rate_before: f64,
+    rate_after: f64,
+    runs_after: usize,
+    degraded: usize,
+    passthrough: usize,
+    /// Latest warning from `since` on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_warning: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HealthReport {
+    /// Runs with a recorded parser tier
+    parsed_runs: usize,
+    commands: usize,
+    regressions: Vec<Regression>,
+}
+
+/// `rtk gain --health`: commands whose Degraded/Passthrough rate went up
+pub fn run_health(format: &str, filters: &Filters, db: Option<&Path>) -> Result<()> {
+    let tracker = Tracker::open_or_default(db)
+        .context("Failed to initialize tracking database")?
+        .with_scope(filters.scope()?);
+    let runs = tracker.get_parse_runs()?;
+    let cli = <crate::Cli as clap::CommandFactory>::command();
+    let family = |rtk_cmd: &str| crate::gain_metrics::command_family(&cli, rtk_cmd);
+    let commands: std::collections::HashSet<String> =
+        runs.iter().map(|r| family(&r.rtk_cmd)).collect();
+    let report = HealthReport {
+        parsed_runs: runs.len(),
+        commands: commands.len(),
+        regressions: find_regressions(&runs, family),
+    };
+
+    if format == "json" {
+        render_println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    render_println!("🩺 RTK Parser Health");
+    render_println!("════════════════════════════════════════");
+    if report.parsed_runs == 0 {
+        render_println!("No parsed runs recorded yet.");
+        return Ok(());
+    }
+    if report.regressions.is_empty() {
+        render_println!(
+            "✅ No regressions: {} parsed runs across {} commands",
+            report.parsed_runs,
+            report.commands
+        );
+        return Ok(());
+    }
+    render_println!("Degraded/Passthrough rate went up for:");
+    render_println!();
+    render_println!(
+        "{:<24} {:<10} {:>7} {:>7} {:>5} {:>5} {:>5}",
+        "Command",
+        "Since",
+        "Before",
+        "After",
+        "Runs",
+        "Degr",
+        "Pass"
+    );
+    for r in &report.regressions {
+        render_println!(
+            "{:<24} {:<10} {:>6.1}% {:>6.1}% {:>5} {:>5} {:>5}",
+            truncate(&r.command, 24),
+            r.since,
+            r.rate_before,
+            r.rate_after,
+            r.runs_after,
+            r.degraded,
+            r.passthrough
+        );
+        if let Some(warning) = &r.last_warning {
+            render_println!("  ⚠️  {}", truncate(warning, 70));
+        }
+    }
+    Ok(())
+}
+
+/// Per command family, find the day that best splits its runs into a lower
+/// and a higher degraded/passthrough rate (highest z-score); report it when
+/// the rise is at least [`HEALTH_MIN_RISE`] points and [`HEALTH_MIN_Z`].
+/// Biggest rise first.
+fn find_regressions(runs: &[ParseRun], family: impl Fn(&str) -> String) -> Vec<Regression> {
+    // family -> day -> runs in order
+    let mut by_family: BTreeMap<String, BTreeMap<&str, Vec<&ParseRun>>> = BTreeMap::new();
+    for run in runs {
+        let day = run.timestamp.get(..10).unwrap_or(&run.timestamp);
+        by_family
+            .entry(family(&run.rtk_cmd))
+            .or_default()
+            .entry(day)
+            .or_default()
+            .push(run);
+    }
+
+    let rate = |bad: usize, total: usize| bad as f64 * 100.0 / total.max(1) as f64;
+    let mut regressions = Vec::new();
+    for (command, days) in by_family {
+        let days: Vec<(&str, Vec<&ParseRun>)> = days.into_iter().collect();
+        let total: usize = days.iter().map(|(_, runs)| runs.len()).sum();
+        let total_bad: usize = days
+            .iter()
+            .flat_map(|(_, runs)| runs)
+            .filter(|r| r.tier > 1)
+            .count();
+
+        // (day index, z-score, rate before, rate after)
+        let mut best: Option<(usize, f64, f64, f64)> = None;
+        let (mut seen, mut seen_bad) = (0, 0);
+        for (i, (_, runs)) in days.iter().enumerate() {
+            let bad = runs.iter().filter(|r| r.tier > 1).count();
+            if seen > 0 && bad > 0 {
+                let z = z_score(seen_bad, seen, total_bad - seen_bad, total - seen);
+                if best.is_none_or(|(_, best_z, _, _)| z > best_z) {
+                    let before = rate(seen_bad, seen);
+                    let after = rate(total_bad - seen_bad, total - seen);
+                    best = Some((i, z, before, after));
+                }
+            }
+            seen += runs.len();
+            seen_bad += bad;
+        }
+
+        let Some((start, z, rate_before, rate_after)) = best else {
+            continue;
+        };
+        if z < HEALTH_MIN_Z || rate_after - rate_before < HEALTH_MIN_RISE {
+            continue;
+        }
+        let after: Vec<&ParseRun> = days[start..]
+            .iter()
+            .flat_map(|(_, runs)| runs.iter().copied())
+            .collect();
+        regressions.push(Regression {
+            command,
+            since: days[start].0.to_string(),
+            rate_before,
+            rate_after,
+            runs_after: after.len(),
+            degraded: after.iter().filter(|r| r.tier == 2).count(),
+            passthrough: after.iter().filter(|r| r.tier == 3).count(),
+            last_warning: after.iter().rev().find_map(|r| r.warnings.last().cloned()),
+        });
+    }
+
+    regressions
+        .sort_by(|a, b| (b.rate_after - b.rate_before).total_cmp(&(a.rate_after - a.rate_before)));
+    regressions
+}
+
+/// How far the rate `bad_b / total_b` is above `bad_a / total_a`, in standard errors
+fn z_score(bad_a: usize, total_a: usize, bad_b: usize, total_b: usize) -> f64 {
+    let (pa, pb) = (bad_a as f64 / total_a as f64, bad_b as f64 / total_b as f64);
+    let pooled = (bad_a + bad_b) as f64 / (total_a + total_b) as f64;
+    let se = (pooled * (1.0 - pooled) * (1.0 / total_a as f64 + 1.0 / total_b as f64)).sqrt();
+    if se == 0.0 {
+        return 0.0;
+    }
+    (pb - pa) / se
+}
+
+/// `rtk gain export --bundle`: rows in scope as JSON lines, labelled with
+/// this machine's host and user name unless given
+pub fn run_export(
+    filters: &Filters,
+    db: Option<&Path>,
+    bundle: &Path,
+    host: Option<String>,
+    user: Option<String>,
+) -> Result<()> {
+    let tracker = Tracker::open_or_default(db)
+        .context("Failed to initialize tracking database")?
+        .with_scope(filters.scope()?);
+    let host = host.unwrap_or_else(host_name);
+    let user = user.unwrap_or_else(user_name);
+    let rows = tracker.get_bundle_rows(&host, &user)?;
+
+    let mut contents = String::new();
+    for row in &rows {
+        contents.push_str(&serde_json::to_string(row)?);
+        contents.push('\n');
+    }
+    write_atomic(bundle, &contents)?;
+    render_println!(
+        "Exported {} rows to {} (host {}, user {})",
+        rows.len(),
+        bundle.display(),
+        host,
+        user
+    );
+    Ok(())
+}
+
+/// `rtk gain import`: merge bundles into a team database, by default
+/// `team.db` next to the tracking database. Never writes to the latter.
+pub fn run_import(bundles: &[PathBuf], db: Option<&Path>) -> Result<()> {
+    let personal = get_db_path()?;
+    let target = db
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| personal.with_file_name("team.db"));
+    if same_file(&target, &personal) {
+        anyhow::bail!(
+            "Refusing to import into the tracking database {}; pass a team database with --db",
+            personal.display()
+        );
+    }
+
+    // Parse everything first so a bad bundle imports nothing
+    let mut rows = Vec::new();
+    for bundle in bundles {
+        rows.extend(read_bundle(bundle)?);
+    }
+
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tracker = Tracker::open(&target)?;
+    let inserted = tracker.import_bundle(&rows)?;
+    render_println!(
+        "Imported {} rows from {} bundle(s) into {}",
+        inserted,
+        bundles.len(),
+        target.display()
+    );
+    if inserted < rows.len() {
+        render_println!("Skipped {} duplicate rows", rows.len() - inserted);
+    }
+    render_println!("View with: rtk gain --db {}", target.display());
+    Ok(())
+}
+
+fn read_bundle(path: &Path) -> Result<Vec<BundleRow>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read bundle: {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: invalid bundle row", path.display(), i + 1))
+        })
+        .collect()
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn host_name() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer outlives the call and its length is passed along
+        let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        if rc == 0 && len > 0 {
+            return String::from_utf8_lossy(&buf[..len]).into_owned();
+        }
+    }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn user_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Summary with the `--top` commands, 10 by default
+fn load_summary(tracker: &Tracker, top: Option<usize>) -> Result<GainSummary> {
+    match top {
+        Some(n) => tracker.get_summary_top(n),
+        None => tracker.get_summary(),
+    }
+    .context("Failed to load token savings summary from database")
+}
+
+/// Project root recorded for commands run under `path`
+fn resolve_project(path: &str) -> Result<String> {
+    let dir =
+        std::fs::canonicalize(path).with_context(|| format!("Project path not found: {}", path))?;
+    Ok(project_root_of(&dir).display().to_string())
+}
+
+/// "project /repo, session abc"
+fn describe_scope(scope: &Scope) -> String {
+    let mut parts = Vec::new();
+    if let Some(project) = &scope.project {
+        parts.push(format!("project {}", project));
+    }
+    if let Some(session) = &scope.session {
+        parts.push(format!("session {}", session));
+    }
+    if let Some(since) = &scope.since {
+        parts.push(format!("since {}", local_time(since, "%Y-%m-%d %H:%M")));
+    }
+    if let Some(until) = &scope.until {
+        parts.push(format!("before {}", local_time(until, "%Y-%m-%d %H:%M")));
+    }
+    if let Some(command) = &scope.command {
+        parts.push(format!("command {}", command));
+    }
+    parts.join(", ")
+}
+
+/// `--since`/`--until` value as an RFC3339 UTC bound. Accepts a date (local
+/// midnight; for `until` the next midnight, so the day is included), an age
+/// such as `12h`, `7d` or `2w`, or an RFC3339 timestamp.
+fn parse_time_bound(value: &str, until: bool) -> Result<String> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last().filter(|c| "hdw".contains(*c)) {
+        if let Ok(n) = value[..value.len() - 1].parse::<i64>() {
+            let hours = match unit {
+                'h' => Some(n),
+                'd' => n.checked_mul(24),
+                _ => n.checked_mul(24 * 7),
+            };
+            let flag = if until { "--until" } else { "--since" };
+            return hours
+                .and_then(chrono::TimeDelta::try_hours)
+                .and_then(|age| Utc::now().checked_sub_signed(age))
+                .map(|t| t.to_rfc3339())
+                .with_context(|| format!("{} value '{}' is out of range", flag, value));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if until {
+            date.succ_opt().unwrap_or(date)
+        } else {
+            date
+        };
+        let midnight = Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .earliest()
+            .with_context(|| format!("No local midnight on {}", date))?;
+        return Ok(midnight.with_timezone(&Utc).to_rfc3339());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+        .with_context(|| {
+            format!(
+                "Invalid time '{}': expected YYYY-MM-DD, an age like 7d/12h/2w, or RFC3339",
+                value
+            )
+        })
+}
+
+/// Match `--command` against recorded rtk commands: "git*" means "rtk git*"
+fn command_glob(pattern: &str) -> String {
+    let pattern = pattern.trim();
+    if pattern == "rtk" || pattern.starts_with("rtk ") || pattern.starts_with('*') {
+        pattern.to_string()
+    } else {
+        format!("rtk {}", pattern)
+    }
+}
+
+/// RFC3339 timestamp in local time
+fn local_time(timestamp: &str, format: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Local).format(format).to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn print_command_detail(command: &str, detail: &CommandDetail) {
+    let runs = if detail.commands > 1 {
+        format!("{} commands, {} runs", detail.commands, detail.runs)
+    } else {
+        format!("{} runs", detail.runs)
+    };
+    render_println!("Command Detail: {} ({})", command, runs);
+    render_println!("────────────────────────────────────────");
+    render_println!(
+        "Savings:           p50 {:.1}%, p90 {:.1}%",
+        detail.savings_p50,
+        detail.savings_p90
+    );
+    render_println!(
+        "Exec time:         p50 {}, p90 {}, total {}",
+        format_duration(detail.time_p50_ms),
+        format_duration(detail.time_p90_ms),
+        format_duration(detail.total_time_ms)
+    );
+    render_println!("Tokens saved:      {}", format_tokens(detail.saved_tokens));
+    if !detail.worst.is_empty() {
+        render_println!("Worst runs:");
+        for run in &detail.worst {
+            render_println!(
+                "  {} {:<30} {:>5.1}% ({} in)",
+                local_time(&run.timestamp, "%m-%d %H:%M"),
+                truncate(&run.original_cmd, 30),
+                run.savings_pct,
+                format_tokens(run.input_tokens)
+            );
+        }
+    }
+    render_println!();
+}
+
+fn print_by_project(projects: &[ProjectStats], top: Option<usize>) {
+    if projects.is_empty() {
+        return;
+    }
+    let projects = &projects[..projects.len().min(top.unwrap_or(usize::MAX))];
+    let home = dirs::home_dir().map(|h| h.display().to_string());
+    render_println!("By Project:");
+    render_println!("────────────────────────────────────────");
+    render_println!(
+        "{:<32} {:>6} {:>10} {:>8} {:>8}",
+        "Project",
+        "Count",
+        "Saved",
+        "Avg%",
+        "Sessions"
+    );
+    for p in projects {
+        let name = match &home {
+            Some(home) if p.project.starts_with(home.as_str()) => {
+                format!("~{}", &p.project[home.len()..])
+            }
+            _ => p.project.clone(),
+        };
+        render_println!(
+            "{:<32} {:>6} {:>10} {:>7.1}% {:>8}",
+            truncate(&name, 32),
+            p.commands,
+            format_tokens(p.saved_tokens),
+            p.savings_pct,
+            p.sessions
+        );
+    }
+    render_println!();
+}
+
+/// Whether the token numbers are exact, estimated or a mix of both
+fn token_count_kind(by_tokenizer: &[(String, usize)]) -> &'static str {
+    let exact = by_tokenizer
+        .iter()
+        .filter(|(n, _)| is_exact_tokenizer(n))
+        .count();
+    match exact {
+        0 => "estimated",
+        n if n == by_tokenizer.len() => "exact",
+        _ => "mixed",
+    }
+}
+
+/// "exact (cl100k)", "estimated (~4 chars/token)" or "mixed: 120 cl100k, 30 estimate"
+fn describe_tokenizers(by_tokenizer: &[(String, usize)]) -> String {
+    match (token_count_kind(by_tokenizer), by_tokenizer) {
+        ("estimated", _) => "estimated (~4 chars/token)".to_string(),
+        ("exact", [(name, _)]) => format!("exact ({})", name),
+        _ => {
+            let parts: Vec<String> = by_tokenizer
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect();
+            format!("{}: {}", token_count_kind(by_tokenizer), parts.join(", "))
+        }
+    }
+}
+
 fn print_ascii_graph(data: &[(String, usize)]) {
     if data.is_empty() {
         return;
@@ -177,7 +813,7 @@ fn print_ascii_graph(data: &[(String, usize)]) {
         let bar: String = "█".repeat(bar_len);
         let spaces: String = " ".repeat(width - bar_len);
 
-        println!(
+        render_println!(
             "{} │{}{} {}",
             date_short,
             bar,
@@ -207,7 +843,17 @@ fn print_monthly(tracker: &Tracker) -> Result<()> {
 
 #[derive(Serialize)]
 struct ExportData {
+    /// Filters applied (`--project`, `--session`); omitted when exporting everything
+    #[serde(skip_serializing_if = "Scope::is_all")]
+    scope: Scope,
     summary: ExportSummary,
+    /// Top commands by tokens saved: (command, count, saved, avg %, avg ms)
+    by_command: Vec<(String, usize, usize, f64, u64)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    by_project: Option<Vec<ProjectStats>>,
+    /// `--command` drill-down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command_detail: Option<CommandDetail>,
     #[serde(skip_serializing_if = "Option::is_none")]
     daily: Option<Vec<DayStats>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -225,6 +871,10 @@ struct ExportSummary {
     avg_savings_pct: f64,
     total_time_ms: u64,
     avg_time_ms: u64,
+    /// "exact", "estimated" or "mixed"
+    token_counts: &'static str,
+    /// Commands per token counter
+    tokenizers: BTreeMap<String, usize>,
 }
 
 fn export_json(
@@ -233,12 +883,13 @@ fn export_json(
     weekly: bool,
     monthly: bool,
     all: bool,
+    by_project: bool,
+    top: Option<usize>,
 ) -> Result<()> {
-    let summary = tracker
-        .get_summary()
-        .context("Failed to load token savings summary from database")?;
+    let summary = load_summary(tracker, top)?;
 
     let export = ExportData {
+        scope: tracker.scope().clone(),
         summary: ExportSummary {
             total_commands: summary.total_commands,
             total_input: summary.total_input,
@@ -247,6 +898,20 @@ fn export_json(
             avg_savings_pct: summary.avg_savings_pct,
             total_time_ms: summary.total_time_ms,
             avg_time_ms: summary.avg_time_ms,
+            token_counts: token_count_kind(&summary.by_tokenizer),
+            tokenizers: summary.by_tokenizer.iter().cloned().collect(),
+        },
+        by_command: summary.by_command.clone(),
+        by_project: if all || by_project {
+            let mut projects = tracker.get_by_project()?;
+            projects.truncate(top.unwrap_or(usize::MAX));
+            Some(projects)
+        } else {
+            None
+        },
+        command_detail: match tracker.scope().command {
+            Some(_) => tracker.get_command_detail(WORST_RUNS)?,
+            None => None,
         },
         daily: if all || daily {
             Some(tracker.get_all_days()?)
@@ -266,24 +931,120 @@ fn export_json(
     };
 
     let json = serde_json::to_string_pretty(&export)?;
-    println!("{}", json);
+    render_println!("{}", json);
 
     Ok(())
 }
 
+/// Offline HTML report, written to `output` or stdout
+fn export_html(tracker: &Tracker, top: Option<usize>, output: Option<&Path>) -> Result<()> {
+    let summary = load_summary(tracker, top)?;
+    let generated = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let html = crate::gain_html::render(&crate::gain_html::Report {
+        summary: &summary,
+        scope: tracker.scope(),
+        days: &tracker.get_all_days()?,
+        weeks: &tracker.get_by_week()?,
+        months: &tracker.get_by_month()?,
+        histogram: &tracker.get_savings_histogram()?,
+        generated: &generated,
+    });
+
+    match output {
+        Some(path) => {
+            write_atomic(path, &html)?;
+            render_println!("Report written: {}", path.display());
+        }
+        // Raw: the HTML must not go through console rendering
+        None => std::io::stdout().write_all(html.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn export_openmetrics(tracker: &Tracker, output: Option<&Path>) -> Result<()> {
+    let metrics = crate::gain_metrics::render(&tracker.get_runs()?);
+    match output {
+        // Silent: meant to be refreshed from cron or a hook
+        Some(path) => write_atomic(path, &metrics),
+        None => {
+            std::io::stdout().write_all(metrics.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+/// Replace `path` via a temp file in the same directory, so readers such as
+/// node_exporter never see a partially written file
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to create temp file in {}", dir.display()))?;
+    file.write_all(contents.as_bytes())?;
+    // NamedTempFile is created 0600; keep the result readable by other users
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.as_file()
+            .set_permissions(std::fs::Permissions::from_mode(0o644))?;
+    }
+    file.persist(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
 fn export_csv(
     tracker: &Tracker,
     daily: bool,
     weekly: bool,
     monthly: bool,
     all: bool,
+    by_project: bool,
+    top: Option<usize>,
 ) -> Result<()> {
+    if all || top.is_some() {
+        let summary = load_summary(tracker, top)?;
+        render_println!("# By Command");
+        render_println!("command,count,saved_tokens,avg_savings_pct,avg_time_ms");
+        for (cmd, count, saved, pct, avg_time) in summary.by_command {
+            render_println!(
+                "{},{},{},{:.2},{}",
+                csv_field(&cmd),
+                count,
+                saved,
+                pct,
+                avg_time
+            );
+        }
+        render_println!();
+    }
+
+    if all || by_project {
+        let mut projects = tracker.get_by_project()?;
+        projects.truncate(top.unwrap_or(usize::MAX));
+        render_println!("# By Project");
+        render_println!("project,commands,saved_tokens,savings_pct,sessions");
+        for p in projects {
+            render_println!(
+                "{},{},{},{:.2},{}",
+                csv_field(&p.project),
+                p.commands,
+                p.saved_tokens,
+                p.savings_pct,
+                p.sessions
+            );
+        }
+        render_println!();
+    }
+
     if all || daily {
         let days = tracker.get_all_days()?;
-        println!("# Daily Data");
-        println!("date,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
+        render_println!("# Daily Data");
+        render_println!("date,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
         for day in days {
-            println!(
+            render_println!(
                 "{},{},{},{},{},{:.2},{},{}",
                 day.date,
                 day.commands,
@@ -295,17 +1056,17 @@ fn export_csv(
                 day.avg_time_ms
             );
         }
-        println!();
+        render_println!();
     }
 
     if all || weekly {
         let weeks = tracker.get_by_week()?;
-        println!("# Weekly Data");
-        println!(
+        render_println!("# Weekly Data");
+        render_println!(
             "week_start,week_end,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms"
         );
         for week in weeks {
-            println!(
+            render_println!(
                 "{},{},{},{},{},{},{:.2},{},{}",
                 week.week_start,
                 week.week_end,
@@ -318,15 +1079,15 @@ fn export_csv(
                 week.avg_time_ms
             );
         }
-        println!();
+        render_println!();
     }
 
     if all || monthly {
         let months = tracker.get_by_month()?;
-        println!("# Monthly Data");
-        println!("month,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
+        render_println!("# Monthly Data");
+        render_println!("month,commands,input_tokens,output_tokens,saved_tokens,savings_pct,total_time_ms,avg_time_ms");
         for month in months {
-            println!(
+            render_println!(
                 "{},{},{},{},{},{:.2},{},{}",
                 month.month,
                 month.commands,
@@ -342,3 +1103,98 @@ fn export_csv(
 
     Ok(())
 }
+
+/// Quote a CSV field containing a separator, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_tokenizers() {
+        let estimate = vec![("estimate".to_string(), 4)];
+        assert_eq!(describe_tokenizers(&estimate), "estimated (~4 chars/token)");
+
+        let exact = vec![("cl100k".to_string(), 4)];
+        assert_eq!(describe_tokenizers(&exact), "exact (cl100k)");
+
+        let mixed = vec![("cl100k".to_string(), 120), ("estimate".to_string(), 30)];
+        assert_eq!(token_count_kind(&mixed), "mixed");
+        assert_eq!(
+            describe_tokenizers(&mixed),
+            "mixed: 120 cl100k, 30 estimate"
+        );
+    }
+
+    #[test]
+    fn test_parse_time_bound() {
+        let since = parse_time_bound("2026-02-03", false).unwrap();
+        let until = parse_time_bound("2026-02-03", true).unwrap();
+        let hours = DateTime::parse_from_rfc3339(&until).unwrap()
+            - DateTime::parse_from_rfc3339(&since).unwrap();
+        assert_eq!(hours.num_hours(), 24);
+
+        let week = DateTime::parse_from_rfc3339(&parse_time_bound("1w", false).unwrap()).unwrap();
+        assert_eq!((Utc::now() - week.with_timezone(&Utc)).num_days(), 7);
+        assert_eq!(
+            parse_time_bound("2026-02-03T10:00:00+02:00", false).unwrap(),
+            "2026-02-03T08:00:00+00:00"
+        );
+        assert!(parse_time_bound("yesterday", false).is_err());
+        for oversized in ["99999999999w", "9999999999999d", "-9999999999999999h"] {
+            let err = parse_time_bound(oversized, false).unwrap_err();
+            assert!(err.to_string().contains("out of range"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_command_glob() {
+        assert_eq!(command_glob("git*"), "rtk git*");
+        assert_eq!(command_glob("rtk cargo test"), "rtk cargo test");
+        assert_eq!(command_glob("*test*"), "*test*");
+    }
+
+    #[test]
+    fn test_find_regressions() {
+        let run = |day: u32, rtk_cmd: &str, tier: u8| ParseRun {
+            timestamp: format!("2026-02-{:02}T12:00:00+00:00", day),
+            rtk_cmd: rtk_cmd.to_string(),
+            tier,
+            warnings: if tier == 2 {
+                vec![format!("unknown field on day {}", day)]
+            } else {
+                vec![]
+            },
+        };
+        let mut runs = Vec::new();
+        for day in 1..=10 {
+            // vitest breaks on day 6; an isolated degraded run on day 3
+            let tier = if day >= 6 || day == 3 { 2 } else { 1 };
+            runs.push(run(day, "rtk vitest run", tier));
+            runs.push(run(day, "rtk vitest run", if day >= 6 { 2 } else { 1 }));
+            // pytest degrades now and then, at a steady rate
+            runs.push(run(day, "rtk pytest -x", if day % 2 == 0 { 3 } else { 1 }));
+        }
+
+        let family = |cmd: &str| cmd.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+        let regressions = find_regressions(&runs, family);
+        assert_eq!(regressions.len(), 1);
+        let vitest = &regressions[0];
+        assert_eq!(vitest.command, "rtk vitest");
+        assert_eq!(vitest.since, "2026-02-06");
+        assert_eq!(vitest.rate_before, 10.0);
+        assert_eq!(vitest.rate_after, 100.0);
+        assert_eq!((vitest.runs_after, vitest.degraded), (10, 10));
+        assert_eq!(
+            vitest.last_warning.as_deref(),
+            Some("unknown field on day 10")
+        );
+    }
+}
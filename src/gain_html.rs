@@ -0,0 +1,393 @@
+//! Self-contained HTML savings report (`rtk gain --format html`).
+//!
+//! One file with inline CSS and SVG charts, no scripts or external assets, so
+//! it can be attached to a ticket or opened offline. Charts: daily savings,
+//! share of savings per command, savings-percentage histogram and average exec
+//! time per day; followed by the weekly and monthly tables.
+
+use crate::display_helpers::{format_duration, PeriodStats};
+use crate::tracking::{DayStats, GainSummary, MonthStats, Scope, WeekStats};
+use crate::utils::format_tokens;
+use std::fmt::Write;
+
+/// Chart area in SVG user units
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 180.0;
+
+/// Left margin holding the y-axis labels
+const AXIS_WIDTH: f64 = 56.0;
+
+const STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,sans-serif;max-width:900px;margin:2em auto;padding:0 1em;color:#1f2328;background:#fff}
+h1{font-size:1.6em;margin-bottom:.2em}h2{font-size:1.15em;margin-top:2em;border-bottom:1px solid #d0d7de;padding-bottom:.3em}
+.meta{color:#656d76;font-size:.9em}
+.cards{display:flex;flex-wrap:wrap;gap:12px;margin:1.5em 0}
+.card{flex:1 1 150px;border:1px solid #d0d7de;border-radius:6px;padding:10px 14px}
+.card b{display:block;font-size:1.4em}.card span{color:#656d76;font-size:.85em}
+table{border-collapse:collapse;width:100%;font-size:.9em}
+th,td{padding:4px 8px;border-bottom:1px solid #eaeef2;text-align:right}
+th:first-child,td:first-child{text-align:left}
+tfoot td{font-weight:600;border-top:2px solid #d0d7de}
+svg text{font-size:11px;fill:#656d76}
+.bar{fill:#2da44e}.time{fill:#0969da}.hist{fill:#8250df}.grid{stroke:#eaeef2}
+.empty{color:#656d76;font-style:italic}";
+
+/// Data for one report
+pub struct Report<'a> {
+    pub summary: &'a GainSummary,
+    pub scope: &'a Scope,
+    pub days: &'a [DayStats],
+    pub weeks: &'a [WeekStats],
+    pub months: &'a [MonthStats],
+    /// Runs per savings-percentage decile (0-10%, ..., 90-100%)
+    pub histogram: &'a [usize; 10],
+    /// Generation time, shown in the header
+    pub generated: &'a str,
+}
+
+/// Render the whole report as one HTML document
+pub fn render(report: &Report) -> String {
+    let summary = report.summary;
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>RTK Token Savings Report</title>\n");
+    let _ = writeln!(html, "<style>\n{}\n</style>\n</head>\n<body>", STYLE);
+
+    html.push_str("<h1>RTK Token Savings Report</h1>\n");
+    let _ = write!(
+        html,
+        "<p class=\"meta\">Generated {}",
+        escape(report.generated)
+    );
+    if let (Some(first), Some(last)) = (report.days.first(), report.days.last()) {
+        let _ = write!(html, " &middot; {} to {}", first.date, last.date);
+    }
+    let scope = describe_scope(report.scope);
+    if !scope.is_empty() {
+        let _ = write!(html, " &middot; {}", escape(&scope));
+    }
+    html.push_str("</p>\n");
+
+    html.push_str("<div class=\"cards\">\n");
+    for (value, label) in [
+        (format_tokens(summary.total_saved), "tokens saved"),
+        (
+            format!("{:.1}%", summary.avg_savings_pct),
+            "average savings",
+        ),
+        (summary.total_commands.to_string(), "commands"),
+        (format_duration(summary.total_time_ms), "total exec time"),
+    ] {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><b>{}</b><span>{}</span></div>",
+            escape(&value),
+            label
+        );
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Daily savings</h2>\n");
+    let daily: Vec<(String, f64)> = report
+        .days
+        .iter()
+        .map(|d| (d.date.clone(), d.saved_tokens as f64))
+        .collect();
+    html.push_str(&bar_chart(&daily, "bar", |v| format_tokens(v as usize)));
+
+    html.push_str("<h2>Savings by command</h2>\n");
+    html.push_str(&command_share(summary));
+
+    html.push_str("<h2>Savings per run</h2>\n");
+    let histogram: Vec<(String, f64)> = report
+        .histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| (format!("{}-{}%", i * 10, i * 10 + 10), n as f64))
+        .collect();
+    let runs: usize = report.histogram.iter().sum();
+    html.push_str(&bar_chart(
+        if runs > 0 { &histogram } else { &[] },
+        "hist",
+        |v| format!("{}", v as usize),
+    ));
+
+    html.push_str("<h2>Average exec time per day</h2>\n");
+    let time: Vec<(String, f64)> = report
+        .days
+        .iter()
+        .map(|d| (d.date.clone(), d.avg_time_ms as f64))
+        .collect();
+    html.push_str(&bar_chart(&time, "time", |v| format_duration(v as u64)));
+
+    html.push_str("<h2>Weekly</h2>\n");
+    html.push_str(&period_table(report.weeks));
+    html.push_str("<h2>Monthly</h2>\n");
+    html.push_str(&period_table(report.months));
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// "project /repo, since 2026-02-01T00:00:00+00:00"
+fn describe_scope(scope: &Scope) -> String {
+    [
+        ("project", &scope.project),
+        ("session", &scope.session),
+        ("since", &scope.since),
+        ("before", &scope.until),
+        ("command", &scope.command),
+    ]
+    .iter()
+    .filter_map(|(name, value)| value.as_ref().map(|v| format!("{} {}", name, v)))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Vertical bar chart with a max-value axis; labels are thinned to fit
+fn bar_chart(data: &[(String, f64)], class: &str, format: impl Fn(f64) -> String) -> String {
+    if data.is_empty() {
+        return "<p class=\"empty\">No data.</p>\n".to_string();
+    }
+    let max = data.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let plot_width = CHART_WIDTH - AXIS_WIDTH;
+    let slot = plot_width / data.len() as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+    let label_every = (data.len() as f64 / 12.0).ceil().max(1.0) as usize;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">",
+        CHART_WIDTH,
+        CHART_HEIGHT + 30.0
+    );
+    for (y, value) in [(0.0, max), (CHART_HEIGHT / 2.0, max / 2.0)] {
+        let _ = writeln!(
+            svg,
+            "<line class=\"grid\" x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\"/><text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+            AXIS_WIDTH,
+            CHART_WIDTH,
+            AXIS_WIDTH - 6.0,
+            y + 10.0,
+            escape(&format(value)),
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<line class=\"grid\" x1=\"{}\" y1=\"{h}\" x2=\"{}\" y2=\"{h}\"/>",
+        AXIS_WIDTH,
+        CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    for (i, (label, value)) in data.iter().enumerate() {
+        let height = if max > 0.0 {
+            value / max * CHART_HEIGHT
+        } else {
+            0.0
+        };
+        let x = AXIS_WIDTH + i as f64 * slot + (slot - bar_width) / 2.0;
+        let _ = writeln!(
+            svg,
+            "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\"><title>{}: {}</title></rect>",
+            class,
+            x,
+            CHART_HEIGHT - height,
+            bar_width,
+            height,
+            escape(label),
+            escape(&format(*value))
+        );
+        if i % label_every == 0 {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                x + bar_width / 2.0,
+                CHART_HEIGHT + 16.0,
+                escape(short_label(label))
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// "2026-02-03" → "02-03"; other labels unchanged
+fn short_label(label: &str) -> &str {
+    match label.get(5..) {
+        Some(rest) if label.len() == 10 && label.as_bytes()[4] == b'-' => rest,
+        _ => label,
+    }
+}
+
+/// Horizontal bars: each command's share of the tokens saved
+fn command_share(summary: &GainSummary) -> String {
+    let total: usize = summary.by_command.iter().map(|c| c.2).sum();
+    if total == 0 {
+        return "<p class=\"empty\">No data.</p>\n".to_string();
+    }
+    let row_height = 22.0;
+    let label_width = 220.0;
+    let bar_space = CHART_WIDTH - label_width - 90.0;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">",
+        CHART_WIDTH,
+        summary.by_command.len() as f64 * row_height
+    );
+    for (i, (cmd, count, saved, pct, _)) in summary.by_command.iter().enumerate() {
+        let share = *saved as f64 / total as f64;
+        let y = i as f64 * row_height;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\
+             <rect class=\"bar\" x=\"{}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\"><title>{}: {} runs, {} saved, {:.1}% avg</title></rect>\
+             <text x=\"{:.1}\" y=\"{:.1}\">{:.1}% ({})</text>",
+            label_width - 8.0,
+            y + 15.0,
+            escape(&crate::utils::truncate(cmd, 32)),
+            label_width,
+            y + 4.0,
+            (share * bar_space).max(1.0),
+            row_height - 8.0,
+            escape(cmd),
+            count,
+            format_tokens(*saved),
+            pct,
+            label_width + (share * bar_space).max(1.0) + 6.0,
+            y + 15.0,
+            share * 100.0,
+            format_tokens(*saved)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg.push_str(
+        "<p class=\"meta\">Share of tokens saved among the commands listed (rtk gain --top N lists more).</p>\n",
+    );
+    svg
+}
+
+/// Weekly/monthly table with a total row
+fn period_table<T: PeriodStats>(data: &[T]) -> String {
+    if data.is_empty() {
+        return "<p class=\"empty\">No data.</p>\n".to_string();
+    }
+    let mut html = String::from(
+        "<table>\n<thead><tr><th>Period</th><th>Commands</th><th>Input</th><th>Output</th><th>Saved</th><th>Savings</th><th>Avg time</th></tr></thead>\n<tbody>\n",
+    );
+    for row in data {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td></tr>",
+            escape(&row.period()),
+            row.commands(),
+            format_tokens(row.input_tokens()),
+            format_tokens(row.output_tokens()),
+            format_tokens(row.saved_tokens()),
+            row.savings_pct(),
+            format_duration(row.avg_time_ms())
+        );
+    }
+    let commands: usize = data.iter().map(|d| d.commands()).sum();
+    let input: usize = data.iter().map(|d| d.input_tokens()).sum();
+    let output: usize = data.iter().map(|d| d.output_tokens()).sum();
+    let saved: usize = data.iter().map(|d| d.saved_tokens()).sum();
+    let time: u64 = data.iter().map(|d| d.total_time_ms()).sum();
+    let _ = writeln!(
+        html,
+        "</tbody>\n<tfoot><tr><td>Total</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td></tr></tfoot>\n</table>",
+        commands,
+        format_tokens(input),
+        format_tokens(output),
+        format_tokens(saved),
+        if input > 0 {
+            saved as f64 / input as f64 * 100.0
+        } else {
+            0.0
+        },
+        format_duration(if commands > 0 {
+            time / commands as u64
+        } else {
+            0
+        })
+    );
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> GainSummary {
+        GainSummary {
+            total_commands: 3,
+            total_input: 1000,
+            total_output: 200,
+            total_saved: 800,
+            avg_savings_pct: 80.0,
+            total_time_ms: 30,
+            avg_time_ms: 10,
+            by_command: vec![
+                ("rtk git <log>".to_string(), 2, 600, 75.0, 10),
+                ("rtk ls".to_string(), 1, 200, 90.0, 10),
+            ],
+            by_day: vec![],
+            by_tokenizer: vec![("estimate".to_string(), 3)],
+            timed_out: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_is_self_contained() {
+        let summary = summary();
+        let days = vec![DayStats {
+            date: "2026-02-03".to_string(),
+            commands: 3,
+            input_tokens: 1000,
+            output_tokens: 200,
+            saved_tokens: 800,
+            savings_pct: 80.0,
+            total_time_ms: 30,
+            avg_time_ms: 10,
+        }];
+        let html = render(&Report {
+            summary: &summary,
+            scope: &Scope::default(),
+            days: &days,
+            weeks: &[],
+            months: &[],
+            histogram: &[0, 0, 0, 0, 0, 0, 0, 1, 1, 1],
+            generated: "2026-02-03 10:00",
+        });
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<svg").count(), 4);
+        assert!(html.contains("rtk git &lt;log&gt;"));
+        assert!(html.contains("75.0% (600)"));
+        assert!(html.contains(">02-03</text>"));
+        // Offline: no scripts, stylesheets or images fetched from elsewhere
+        for external in ["<script", "src=", "href=", "http://", "https://"] {
+            assert!(!html.contains(external), "found {}", external);
+        }
+    }
+
+    #[test]
+    fn test_bar_chart_scales_to_max() {
+        let data = vec![("a".to_string(), 50.0), ("b".to_string(), 100.0)];
+        let svg = bar_chart(&data, "bar", |v| v.to_string());
+        assert!(svg.contains(&format!("height=\"{:.1}\"", CHART_HEIGHT)));
+        assert!(svg.contains(&format!("height=\"{:.1}\"", CHART_HEIGHT / 2.0)));
+        assert_eq!(
+            bar_chart(&[], "bar", |v| v.to_string()),
+            "<p class=\"empty\">No data.</p>\n"
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! OpenMetrics export of tracking data (`rtk gain --format openmetrics`).
+//!
+//! Meant for node_exporter's textfile collector: `--write` replaces the file
+//! atomically, so a cron job or hook can refresh it while it is scraped.
+//! Series are labelled by command family ("rtk git status", "rtk ls") rather
+//! than the full command line, which keeps the label set small.
+//!
+//! Counters cover the rows kept in the database; when old rows are pruned
+//! (`tracking.history_days`) they drop, which Prometheus treats as a reset.
+
+use crate::tracking::RunRecord;
+use clap::CommandFactory;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Upper bounds of the exec time histogram, in seconds
+const BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+#[derive(Default)]
+struct Family {
+    commands: usize,
+    input_tokens: usize,
+    output_tokens: usize,
+    saved_tokens: usize,
+    /// Cumulative counts per bucket in [`BUCKETS`]
+    buckets: Vec<usize>,
+    exec_time_ms: u64,
+}
+
+/// Render `runs` as an OpenMetrics exposition, ending with `# EOF`
+pub fn render(runs: &[RunRecord]) -> String {
+    let cli = crate::Cli::command();
+    let mut families: BTreeMap<String, Family> = BTreeMap::new();
+    for run in runs {
+        let family = families
+            .entry(command_family(&cli, &run.rtk_cmd))
+            .or_default();
+        family.commands += 1;
+        family.input_tokens += run.input_tokens;
+        family.output_tokens += run.output_tokens;
+        family.saved_tokens += run.saved_tokens;
+        family.exec_time_ms += run.exec_time_ms;
+        let seconds = run.exec_time_ms as f64 / 1000.0;
+        family.buckets.resize(BUCKETS.len(), 0);
+        for (count, bound) in family.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    write_counter(
+        &mut out,
+        &families,
+        "rtk_commands",
+        "Commands run through rtk.",
+        |f| f.commands,
+    );
+    write_counter(
+        &mut out,
+        &families,
+        "rtk_input_tokens",
+        "Tokens of the raw command output.",
+        |f| f.input_tokens,
+    );
+    write_counter(
+        &mut out,
+        &families,
+        "rtk_output_tokens",
+        "Tokens of the filtered output.",
+        |f| f.output_tokens,
+    );
+    write_counter(
+        &mut out,
+        &families,
+        "rtk_saved_tokens",
+        "Tokens saved by filtering.",
+        |f| f.saved_tokens,
+    );
+
+    let _ = writeln!(
+        out,
+        "# HELP rtk_exec_time_seconds Execution time of commands run through rtk."
+    );
+    let _ = writeln!(out, "# TYPE rtk_exec_time_seconds histogram");
+    let _ = writeln!(out, "# UNIT rtk_exec_time_seconds seconds");
+    for (command, family) in &families {
+        let label = escape_label(command);
+        for (count, bound) in family.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(
+                out,
+                "rtk_exec_time_seconds_bucket{{command=\"{}\",le=\"{}\"}} {}",
+                label,
+                format_float(*bound),
+                count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "rtk_exec_time_seconds_bucket{{command=\"{}\",le=\"+Inf\"}} {}",
+            label, family.commands
+        );
+        let _ = writeln!(
+            out,
+            "rtk_exec_time_seconds_count{{command=\"{}\"}} {}",
+            label, family.commands
+        );
+        let _ = writeln!(
+            out,
+            "rtk_exec_time_seconds_sum{{command=\"{}\"}} {}",
+            label,
+            format_float(family.exec_time_ms as f64 / 1000.0)
+        );
+    }
+
+    let input: usize = families.values().map(|f| f.input_tokens).sum();
+    let saved: usize = families.values().map(|f| f.saved_tokens).sum();
+    let _ = writeln!(
+        out,
+        "# HELP rtk_savings_ratio Tokens saved divided by raw output tokens, all commands."
+    );
+    let _ = writeln!(out, "# TYPE rtk_savings_ratio gauge");
+    let ratio = if input > 0 {
+        saved as f64 / input as f64
+    } else {
+        0.0
+    };
+    let _ = writeln!(out, "rtk_savings_ratio {}", format_float(ratio));
+
+    let last = runs
+        .iter()
+        .filter_map(|r| chrono::DateTime::parse_from_rfc3339(&r.timestamp).ok())
+        .max();
+    if let Some(last) = last {
+        let _ = writeln!(
+            out,
+            "# HELP rtk_last_command_timestamp_seconds When the last tracked command ran."
+        );
+        let _ = writeln!(out, "# TYPE rtk_last_command_timestamp_seconds gauge");
+        let _ = writeln!(
+            out,
+            "rtk_last_command_timestamp_seconds {}",
+            last.timestamp()
+        );
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+fn write_counter(
+    out: &mut String,
+    families: &BTreeMap<String, Family>,
+    name: &str,
+    help: &str,
+    value: impl Fn(&Family) -> usize,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (command, family) in families {
+        let _ = writeln!(
+            out,
+            "{}_total{{command=\"{}\"}} {}",
+            name,
+            escape_label(command),
+            value(family)
+        );
+    }
+}
+
+/// "rtk git log -20 src/" → "rtk git log": the words that name rtk subcommands.
+/// The first word is always kept, since some commands are tracked under
+/// internal names ("rtk run-test") that are not CLI subcommands.
+pub fn command_family(cli: &clap::Command, rtk_cmd: &str) -> String {
+    let mut words = rtk_cmd.split_whitespace();
+    if words.next() != Some("rtk") {
+        return "other".to_string();
+    }
+    let Some(first) = words.next() else {
+        return "rtk".to_string();
+    };
+    let mut family = format!("rtk {}", first);
+    let mut command = match cli.find_subcommand(first) {
+        Some(sub) => sub,
+        None => return family,
+    };
+    for word in words {
+        match command.find_subcommand(word) {
+            Some(sub) => {
+                family.push(' ');
+                family.push_str(word);
+                command = sub;
+            }
+            None => break,
+        }
+    }
+    family
+}
+
+/// Shortest representation that round-trips ("0.1", "60", "1.5")
+fn format_float(value: f64) -> String {
+    let text = format!("{}", value);
+    text.strip_suffix(".0").map(String::from).unwrap_or(text)
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(rtk_cmd: &str, input: usize, output: usize, exec_time_ms: u64) -> RunRecord {
+        RunRecord {
+            timestamp: "2026-02-03T10:00:00+00:00".to_string(),
+            original_cmd: rtk_cmd.trim_start_matches("rtk ").to_string(),
+            rtk_cmd: rtk_cmd.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            saved_tokens: input.saturating_sub(output),
+            savings_pct: 0.0,
+            exec_time_ms,
+        }
+    }
+
+    #[test]
+    fn test_command_family() {
+        let cli = crate::Cli::command();
+        assert_eq!(command_family(&cli, "rtk git log -20 src/"), "rtk git log");
+        assert_eq!(command_family(&cli, "rtk ls src"), "rtk ls");
+        assert_eq!(
+            command_family(&cli, "rtk cargo test --lib"),
+            "rtk cargo test"
+        );
+        assert_eq!(command_family(&cli, "rtk run-test"), "rtk run-test");
+        assert_eq!(command_family(&cli, "ls -la"), "other");
+    }
+
+    #[test]
+    fn test_render_openmetrics() {
+        let out = render(&[
+            run("rtk git status", 100, 20, 8),
+            run("rtk git status --short", 50, 10, 300),
+            run("rtk ls src", 50, 10, 2),
+        ]);
+
+        assert!(out.contains("# TYPE rtk_commands counter\n"));
+        assert!(out.contains("rtk_commands_total{command=\"rtk git status\"} 2\n"));
+        assert!(out.contains("rtk_saved_tokens_total{command=\"rtk git status\"} 120\n"));
+        assert!(out.contains("rtk_output_tokens_total{command=\"rtk ls\"} 10\n"));
+        assert!(out
+            .contains("rtk_exec_time_seconds_bucket{command=\"rtk git status\",le=\"0.01\"} 1\n"));
+        assert!(
+            out.contains("rtk_exec_time_seconds_bucket{command=\"rtk git status\",le=\"0.5\"} 2\n")
+        );
+        assert!(out.contains("rtk_exec_time_seconds_sum{command=\"rtk git status\"} 0.308\n"));
+        assert!(out.contains("rtk_savings_ratio 0.8\n"));
+        assert!(out.contains("rtk_last_command_timestamp_seconds 1770112800\n"));
+        assert!(out.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
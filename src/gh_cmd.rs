@@ -3,8 +3,10 @@
 //! Provides token-optimized alternatives to verbose `gh` commands.
 //! Focuses on extracting essential information from JSON outputs.
 
+use crate::context;
 use crate::git;
 use crate::json_cmd;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::{ok_confirmation, truncate};
 use anyhow::{Context, Result};
@@ -61,14 +63,14 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr list")?;
+    let output = cmd.output_timed().context("Failed to run gh pr list")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr list", "rtk gh pr list", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -79,10 +81,10 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     if let Some(prs) = json.as_array() {
         if ultra_compact {
             filtered.push_str("PRs\n");
-            println!("PRs");
+            render_println!("PRs");
         } else {
             filtered.push_str("📋 Pull Requests\n");
-            println!("📋 Pull Requests");
+            render_println!("📋 Pull Requests");
         }
 
         for pr in prs.iter().take(20) {
@@ -115,13 +117,13 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
                 author
             );
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
 
         if prs.len() > 20 {
             let more_line = format!("  ... {} more (use gh pr list for all)\n", prs.len() - 20);
             filtered.push_str(&more_line);
-            print!("{}", more_line);
+            render_print!("{}", more_line);
         }
     }
 
@@ -147,7 +149,7 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         "number,title,state,author,body,url,mergeable,reviews,statusCheckRollup",
     ]);
 
-    let output = cmd.output().context("Failed to run gh pr view")?;
+    let output = cmd.output_timed().context("Failed to run gh pr view")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
@@ -158,8 +160,8 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
             &stderr,
             &stderr,
         );
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -193,11 +195,11 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
 
     let line = format!("{} PR #{}: {}\n", state_icon, number, title);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  {}\n", author);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let mergeable_str = match mergeable {
         "MERGEABLE" => "✓",
@@ -206,7 +208,7 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     };
     let line = format!("  {} | {}\n", state, mergeable_str);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     // Show reviews summary
     if let Some(reviews) = json["reviews"]["nodes"].as_array() {
@@ -225,7 +227,7 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
                 approved, changes
             );
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
     }
 
@@ -251,44 +253,44 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
             if failed > 0 {
                 let line = format!("  ✗{}/{}  {} fail\n", passed, total, failed);
                 filtered.push_str(&line);
-                print!("{}", line);
+                render_print!("{}", line);
             } else {
                 let line = format!("  ✓{}/{}\n", passed, total);
                 filtered.push_str(&line);
-                print!("{}", line);
+                render_print!("{}", line);
             }
         } else {
             let line = format!("  Checks: {}/{} passed\n", passed, total);
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
             if failed > 0 {
                 let line = format!("  ⚠️  {} checks failed\n", failed);
                 filtered.push_str(&line);
-                print!("{}", line);
+                render_print!("{}", line);
             }
         }
     }
 
     let line = format!("  {}\n", url);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     // Show body summary (first 3 lines max)
     if let Some(body) = json["body"].as_str() {
         if !body.is_empty() {
             filtered.push('\n');
-            println!();
+            render_println!();
             for line in body.lines().take(3) {
                 if !line.trim().is_empty() {
-                    let formatted = format!("  {}\n", truncate(line, 80));
+                    let formatted = format!("  {}\n", truncate(line, context::width(80)));
                     filtered.push_str(&formatted);
-                    print!("{}", formatted);
+                    render_print!("{}", formatted);
                 }
             }
             if body.lines().count() > 3 {
                 let line = format!("  ... (gh pr view {} for full)\n", pr_number);
                 filtered.push_str(&line);
-                print!("{}", line);
+                render_print!("{}", line);
             }
         }
     }
@@ -314,7 +316,7 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
     let mut cmd = Command::new("gh");
     cmd.args(["pr", "checks", pr_number]);
 
-    let output = cmd.output().context("Failed to run gh pr checks")?;
+    let output = cmd.output_timed().context("Failed to run gh pr checks")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
@@ -325,8 +327,8 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
             &stderr,
             &stderr,
         );
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -352,30 +354,30 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
 
     let line = "🔍 CI Checks Summary:\n";
     filtered.push_str(line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  ✅ Passed: {}\n", passed);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  ❌ Failed: {}\n", failed);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     if pending > 0 {
         let line = format!("  ⏳ Pending: {}\n", pending);
         filtered.push_str(&line);
-        print!("{}", line);
+        render_print!("{}", line);
     }
 
     if !failed_checks.is_empty() {
         let line = "\n  Failed checks:\n";
         filtered.push_str(line);
-        print!("{}", line);
+        render_print!("{}", line);
         for check in failed_checks {
             let line = format!("    {}\n", check);
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
     }
 
@@ -399,14 +401,14 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
         "currentBranch,createdBy,reviewDecision,statusCheckRollup",
     ]);
 
-    let output = cmd.output().context("Failed to run gh pr status")?;
+    let output = cmd.output_timed().context("Failed to run gh pr status")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr status", "rtk gh pr status", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -417,14 +419,14 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
     if let Some(created_by) = json["createdBy"].as_array() {
         let line = format!("📝 Your PRs ({}):\n", created_by.len());
         filtered.push_str(&line);
-        print!("{}", line);
+        render_print!("{}", line);
         for pr in created_by.iter().take(5) {
             let number = pr["number"].as_i64().unwrap_or(0);
             let title = pr["title"].as_str().unwrap_or("???");
             let reviews = pr["reviewDecision"].as_str().unwrap_or("PENDING");
             let line = format!("  #{} {} [{}]\n", number, truncate(title, 50), reviews);
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
     }
 
@@ -454,14 +456,14 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh issue list")?;
+    let output = cmd.output_timed().context("Failed to run gh issue list")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh issue list", "rtk gh issue list", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -472,10 +474,10 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
     if let Some(issues) = json.as_array() {
         if ultra_compact {
             filtered.push_str("Issues\n");
-            println!("Issues");
+            render_println!("Issues");
         } else {
             filtered.push_str("🐛 Issues\n");
-            println!("🐛 Issues");
+            render_println!("🐛 Issues");
         }
         for issue in issues.iter().take(20) {
             let number = issue["number"].as_i64().unwrap_or(0);
@@ -497,13 +499,13 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
             };
             let line = format!("  {} #{} {}\n", icon, number, truncate(title, 60));
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
 
         if issues.len() > 20 {
             let line = format!("  ... {} more\n", issues.len() - 20);
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
     }
 
@@ -529,7 +531,7 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
         "number,title,state,author,body,url",
     ]);
 
-    let output = cmd.output().context("Failed to run gh issue view")?;
+    let output = cmd.output_timed().context("Failed to run gh issue view")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
@@ -540,8 +542,8 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
             &stderr,
         );
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -559,30 +561,30 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
 
     let line = format!("{} Issue #{}: {}\n", icon, number, title);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  Author: @{}\n", author);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  Status: {}\n", state);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  URL: {}\n", url);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     if let Some(body) = json["body"].as_str() {
         if !body.is_empty() {
             let line = "\n  Description:\n";
             filtered.push_str(line);
-            print!("{}", line);
+            render_print!("{}", line);
             for line in body.lines().take(3) {
                 if !line.trim().is_empty() {
-                    let formatted = format!("    {}\n", truncate(line, 80));
+                    let formatted = format!("    {}\n", truncate(line, context::width(80)));
                     filtered.push_str(&formatted);
-                    print!("{}", formatted);
+                    render_print!("{}", formatted);
                 }
             }
         }
@@ -625,14 +627,14 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh run list")?;
+    let output = cmd.output_timed().context("Failed to run gh run list")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh run list", "rtk gh run list", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -643,10 +645,10 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     if let Some(runs) = json.as_array() {
         if ultra_compact {
             filtered.push_str("Runs\n");
-            println!("Runs");
+            render_println!("Runs");
         } else {
             filtered.push_str("🏃 Workflow Runs\n");
-            println!("🏃 Workflow Runs");
+            render_println!("🏃 Workflow Runs");
         }
         for run in runs {
             let id = run["databaseId"].as_i64().unwrap_or(0);
@@ -684,7 +686,7 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
 
             let line = format!("  {} {} [{}]\n", icon, truncate(name, 50), id);
             filtered.push_str(&line);
-            print!("{}", line);
+            render_print!("{}", line);
         }
     }
 
@@ -704,7 +706,7 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
     let mut cmd = Command::new("gh");
     cmd.args(["run", "view", run_id]);
 
-    let output = cmd.output().context("Failed to run gh run view")?;
+    let output = cmd.output_timed().context("Failed to run gh run view")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
@@ -715,8 +717,8 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
             &stderr,
         );
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // Parse output and show only failures
@@ -727,7 +729,7 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
 
     let line = format!("🏃 Workflow Run #{}\n", run_id);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     for line in stdout.lines() {
         if line.contains("JOBS") {
@@ -742,12 +744,12 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
             if line.contains('✗') || line.contains("fail") {
                 let formatted = format!("  ❌ {}\n", line.trim());
                 filtered.push_str(&formatted);
-                print!("{}", formatted);
+                render_print!("{}", formatted);
             }
         } else if line.contains("Status:") || line.contains("Conclusion:") {
             let formatted = format!("  {}\n", line.trim());
             filtered.push_str(&formatted);
-            print!("{}", formatted);
+            render_print!("{}", formatted);
         }
     }
 
@@ -786,14 +788,14 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
         "name,owner,description,url,stargazerCount,forkCount,isPrivate",
     ]);
 
-    let output = cmd.output().context("Failed to run gh repo view")?;
+    let output = cmd.output_timed().context("Failed to run gh repo view")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh repo view", "rtk gh repo view", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let json: Value =
@@ -817,25 +819,25 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
 
     let line = format!("📦 {}/{}\n", owner, name);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  {}\n", visibility);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     if !description.is_empty() {
-        let line = format!("  {}\n", truncate(description, 80));
+        let line = format!("  {}\n", truncate(description, context::width(80)));
         filtered.push_str(&line);
-        print!("{}", line);
+        render_print!("{}", line);
     }
 
     let line = format!("  ⭐ {} stars | 🔱 {} forks\n", stars, forks);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     let line = format!("  {}\n", url);
     filtered.push_str(&line);
-    print!("{}", line);
+    render_print!("{}", line);
 
     timer.track("gh repo view", "rtk gh repo view", &raw, &filtered);
     Ok(())
@@ -850,14 +852,14 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr create")?;
+    let output = cmd.output_timed().context("Failed to run gh pr create")?;
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
     if !output.status.success() {
         timer.track("gh pr create", "rtk gh pr create", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // gh pr create outputs the URL on success
@@ -873,7 +875,7 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
     };
 
     let filtered = ok_confirmation("created", &detail);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     timer.track("gh pr create", "rtk gh pr create", &stdout, &filtered);
     Ok(())
@@ -888,14 +890,14 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr merge")?;
+    let output = cmd.output_timed().context("Failed to run gh pr merge")?;
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
     if !output.status.success() {
         timer.track("gh pr merge", "rtk gh pr merge", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // Extract PR number from args (first non-flag arg)
@@ -912,7 +914,7 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
     };
 
     let filtered = ok_confirmation("merged", &detail);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     // Use stdout or detail as raw input (gh pr merge doesn't output much)
     let raw = if !stdout.trim().is_empty() {
@@ -934,23 +936,23 @@ fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh pr diff")?;
+    let output = cmd.output_timed().context("Failed to run gh pr diff")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr diff", "rtk gh pr diff", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let filtered = if raw.trim().is_empty() {
         let msg = "No diff\n";
-        print!("{}", msg);
+        render_print!("{}", msg);
         msg.to_string()
     } else {
         let compacted = git::compact_diff(&raw, 100);
-        println!("{}", compacted);
+        render_println!("{}", compacted);
         compacted
     };
 
@@ -969,7 +971,7 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context(format!("Failed to run gh pr {}", action))?;
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
 
@@ -981,8 +983,8 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
             &stderr,
         );
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // Extract PR number from args
@@ -993,7 +995,7 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
         .unwrap_or_default();
 
     let filtered = ok_confirmation(action, &pr_num);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     // Use stdout or pr_num as raw input
     let raw = if !stdout.trim().is_empty() {
@@ -1020,20 +1022,20 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run gh api")?;
+    let output = cmd.output_timed().context("Failed to run gh api")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh api", "rtk gh api", &stderr, &stderr);
-        eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // Try to parse as JSON and filter
     let filtered = match json_cmd::filter_json_string(&raw, 5) {
         Ok(schema) => {
-            println!("{}", schema);
+            render_println!("{}", schema);
             schema
         }
         Err(_) => {
@@ -1042,10 +1044,10 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
             let lines: Vec<&str> = raw.lines().take(20).collect();
             let joined = lines.join("\n");
             result.push_str(&joined);
-            print!("{}", joined);
+            render_print!("{}", joined);
             if raw.lines().count() > 20 {
                 result.push_str("\n... (truncated)");
-                println!("\n... (truncated)");
+                render_println!("\n... (truncated)");
             }
             result
         }
@@ -1065,7 +1067,7 @@ fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
     }
 
     let status = command
-        .status()
+        .status_timed()
         .context(format!("Failed to run {} {}", cmd, subcommand))?;
 
     let args_str = tracking::args_display(&args.iter().map(|s| s.into()).collect::<Vec<_>>());
@@ -1075,7 +1077,7 @@ fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -1,3 +1,6 @@
+use crate::context;
+use crate::parser::{self, truncate_output, GitCommit, GitResult, ParseResult};
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::ffi::OsString;
@@ -20,6 +23,12 @@ pub enum GitCommand {
 }
 
 pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
+    if parser::json_requested() {
+        if let Some(operation) = json_operation(&cmd) {
+            return run_json(operation, args, verbose);
+        }
+    }
+
     match cmd {
         GitCommand::Diff => run_diff(args, max_lines, verbose),
         GitCommand::Log => run_log(args, max_lines, verbose),
@@ -36,6 +45,170 @@ pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose:
     }
 }
 
+/// Separates fields of the commit lines requested for `--format json`
+const FIELD_SEP: char = '\x1f';
+
+/// Subcommands with a canonical `--format json` result; the rest print text
+fn json_operation(cmd: &GitCommand) -> Option<&'static str> {
+    match cmd {
+        GitCommand::Log => Some("log"),
+        GitCommand::Show => Some("show"),
+        GitCommand::Diff => Some("diff"),
+        GitCommand::Status => Some("status"),
+        _ => None,
+    }
+}
+
+/// `--format json`: rerun git with output we can parse and print a [`GitResult`]
+fn run_json(operation: &str, args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let commit_format = format!("--format=%H{0}%an{0}%aI{0}%s", FIELD_SEP);
+
+    let mut cmd = Command::new("git");
+    match operation {
+        "log" => {
+            cmd.arg("log").arg(&commit_format).arg("--shortstat");
+            let has_limit = args.iter().any(|arg| {
+                arg.starts_with('-') && arg.chars().nth(1).is_some_and(|c| c.is_ascii_digit())
+            });
+            if !has_limit {
+                cmd.arg("-10");
+            }
+        }
+        "show" => {
+            cmd.arg("show").arg(&commit_format).arg("--shortstat");
+        }
+        "diff" => {
+            cmd.arg("diff").arg("--shortstat");
+        }
+        _ => {
+            // Changed files from porcelain, line counts from the diff against HEAD
+            cmd.arg("status").arg("--porcelain");
+        }
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        render_eprintln!("Running: git {} (json)", operation);
+    }
+
+    let output = cmd
+        .output_timed()
+        .with_context(|| format!("Failed to run git {}", operation))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let exit_code = output.status.code().unwrap_or(1);
+
+    let result = if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        ParseResult::Passthrough(truncate_output(stderr.trim(), 2000))
+    } else if operation == "status" {
+        let diff = Command::new("git")
+            .args(["diff", "HEAD", "--shortstat"])
+            .output_timed()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string());
+        parse_git_status(&stdout, diff.as_deref())
+    } else {
+        parse_git_output(operation, &stdout)
+    };
+
+    let json = parser::print_json(&format!("git {}", operation), result, exit_code);
+    timer.track(
+        &format!("git {} {}", operation, args.join(" ")),
+        &format!("rtk git {} {}", operation, args.join(" ")),
+        &stdout,
+        &json,
+    );
+
+    if exit_code != 0 {
+        crate::utils::exit(exit_code);
+    }
+    Ok(())
+}
+
+/// " 3 files changed, 10 insertions(+), 2 deletions(-)" -> (3, 10, 2)
+fn parse_shortstat(line: &str) -> Option<(usize, usize, usize)> {
+    if !line.contains("changed") {
+        return None;
+    }
+    let (mut files, mut insertions, mut deletions) = (0, 0, 0);
+    for part in line.split(',') {
+        let mut words = part.split_whitespace();
+        let n: usize = words.next()?.parse().ok()?;
+        match words.next() {
+            Some(w) if w.starts_with("file") => files = n,
+            Some(w) if w.starts_with("insertion") => insertions = n,
+            Some(w) if w.starts_with("deletion") => deletions = n,
+            _ => return None,
+        }
+    }
+    Some((files, insertions, deletions))
+}
+
+/// Parse `git log|show|diff` run with `--shortstat` (and the commit format of [`run_json`])
+fn parse_git_output(operation: &str, output: &str) -> ParseResult<GitResult> {
+    let mut result = GitResult {
+        operation: operation.to_string(),
+        files_changed: 0,
+        insertions: 0,
+        deletions: 0,
+        commits: Vec::new(),
+    };
+    let mut unparsed = 0;
+
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        if let Some((files, insertions, deletions)) = parse_shortstat(line) {
+            result.files_changed += files;
+            result.insertions += insertions;
+            result.deletions += deletions;
+            continue;
+        }
+        let fields: Vec<&str> = line.split(FIELD_SEP).collect();
+        if let [hash, author, timestamp, message] = fields[..] {
+            result.commits.push(GitCommit {
+                hash: hash.to_string(),
+                author: author.to_string(),
+                message: message.to_string(),
+                timestamp: Some(timestamp.to_string()),
+            });
+        } else {
+            // User-supplied --format/--oneline, or patch text from -p
+            unparsed += 1;
+        }
+    }
+
+    if unparsed > 0 {
+        let warning = format!("{} lines not in the expected format were skipped", unparsed);
+        return ParseResult::Degraded(result, vec![warning]);
+    }
+    ParseResult::Full(result)
+}
+
+/// `git status --porcelain` entries plus line counts from `git diff HEAD --shortstat`
+fn parse_git_status(porcelain: &str, diff_shortstat: Option<&str>) -> ParseResult<GitResult> {
+    let mut result = GitResult {
+        operation: "status".to_string(),
+        files_changed: porcelain.lines().filter(|l| l.len() > 3).count(),
+        insertions: 0,
+        deletions: 0,
+        commits: Vec::new(),
+    };
+    match diff_shortstat {
+        Some(stat) => {
+            if let Some((_, insertions, deletions)) = stat.lines().find_map(parse_shortstat) {
+                result.insertions = insertions;
+                result.deletions = deletions;
+            }
+            ParseResult::Full(result)
+        }
+        None => {
+            let warning = "no HEAD to diff against; insertions/deletions not counted".to_string();
+            ParseResult::Degraded(result, vec![warning])
+        }
+    }
+}
+
 fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -55,16 +228,17 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
             cmd.arg(arg);
         }
 
-        let output = cmd.output().context("Failed to run git diff")?;
+        let output = cmd.output_timed().context("Failed to run git diff")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            render_eprintln!("{}", stderr);
+            crate::utils::exit(output.status.code().unwrap_or(1));
         }
 
+        // Patch output is passed on byte for byte
+        context::raw(&output.stdout);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("{}", stdout.trim());
 
         timer.track(
             &format!("git diff {}", args.join(" ")),
@@ -84,15 +258,15 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git diff")?;
+    let output = cmd.output_timed().context("Failed to run git diff")?;
     let stat_stdout = String::from_utf8_lossy(&output.stdout);
 
     if verbose > 0 {
-        eprintln!("Git diff summary:");
+        render_eprintln!("Git diff summary:");
     }
 
     // Print stat summary first
-    println!("{}", stat_stdout.trim());
+    render_println!("{}", stat_stdout.trim());
 
     // Now get actual diff but compact it
     let mut diff_cmd = Command::new("git");
@@ -101,14 +275,14 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         diff_cmd.arg(arg);
     }
 
-    let diff_output = diff_cmd.output().context("Failed to run git diff")?;
+    let diff_output = diff_cmd.output_timed().context("Failed to run git diff")?;
     let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
 
     let mut final_output = stat_stdout.to_string();
     if !diff_stdout.is_empty() {
-        println!("\n--- Changes ---");
+        render_println!("\n--- Changes ---");
         let compacted = compact_diff(&diff_stdout, max_lines.unwrap_or(100));
-        println!("{}", compacted);
+        render_println!("{}", compacted);
         final_output.push_str("\n--- Changes ---\n");
         final_output.push_str(&compacted);
     }
@@ -141,14 +315,15 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git show")?;
+        let output = cmd.output_timed().context("Failed to run git show")?;
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            render_eprintln!("{}", stderr);
+            crate::utils::exit(output.status.code().unwrap_or(1));
         }
+        // Patch output is passed on byte for byte
+        context::raw(&output.stdout);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("{}", stdout.trim());
 
         timer.track(
             &format!("git show {}", args.join(" ")),
@@ -167,7 +342,7 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         raw_cmd.arg(arg);
     }
     let raw_output = raw_cmd
-        .output()
+        .output_timed()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
@@ -177,14 +352,16 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         summary_cmd.arg(arg);
     }
-    let summary_output = summary_cmd.output().context("Failed to run git show")?;
+    let summary_output = summary_cmd
+        .output_timed()
+        .context("Failed to run git show")?;
     if !summary_output.status.success() {
         let stderr = String::from_utf8_lossy(&summary_output.stderr);
-        eprintln!("{}", stderr);
-        std::process::exit(summary_output.status.code().unwrap_or(1));
+        render_eprintln!("{}", stderr);
+        crate::utils::exit(summary_output.status.code().unwrap_or(1));
     }
     let summary = String::from_utf8_lossy(&summary_output.stdout);
-    println!("{}", summary.trim());
+    render_println!("{}", summary.trim());
 
     // Step 2: --stat summary
     let mut stat_cmd = Command::new("git");
@@ -192,11 +369,13 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         stat_cmd.arg(arg);
     }
-    let stat_output = stat_cmd.output().context("Failed to run git show --stat")?;
+    let stat_output = stat_cmd
+        .output_timed()
+        .context("Failed to run git show --stat")?;
     let stat_stdout = String::from_utf8_lossy(&stat_output.stdout);
     let stat_text = stat_stdout.trim();
     if !stat_text.is_empty() {
-        println!("{}", stat_text);
+        render_println!("{}", stat_text);
     }
 
     // Step 3: compacted diff
@@ -205,17 +384,19 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     for arg in args {
         diff_cmd.arg(arg);
     }
-    let diff_output = diff_cmd.output().context("Failed to run git show (diff)")?;
+    let diff_output = diff_cmd
+        .output_timed()
+        .context("Failed to run git show (diff)")?;
     let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
     let diff_text = diff_stdout.trim();
 
     let mut final_output = summary.to_string();
     if !diff_text.is_empty() {
         if verbose > 0 {
-            println!("\n--- Changes ---");
+            render_println!("\n--- Changes ---");
         }
         let compacted = compact_diff(diff_text, max_lines.unwrap_or(100));
-        println!("{}", compacted);
+        render_println!("{}", compacted);
         final_output.push_str(&format!("\n{}", compacted));
     }
 
@@ -342,24 +523,24 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git log")?;
+    let output = cmd.output_timed().context("Failed to run git log")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!("{}", stderr);
+        render_eprintln!("{}", stderr);
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     if verbose > 0 {
-        eprintln!("Git log output:");
+        render_eprintln!("Git log output:");
     }
 
     // Post-process: truncate long messages, cap lines
     let filtered = filter_log_output(&stdout, limit);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     timer.track(
         &format!("git log {}", args.join(" ")),
@@ -373,13 +554,14 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
 
 /// Filter git log output: truncate long messages, cap lines
 fn filter_log_output(output: &str, limit: usize) -> String {
+    let width = context::width(80);
     let lines: Vec<&str> = output.lines().collect();
     let capped: Vec<String> = lines
         .iter()
         .take(limit)
         .map(|line| {
-            if line.len() > 80 {
-                let truncated: String = line.chars().take(77).collect();
+            if line.len() > width {
+                let truncated: String = line.chars().take(width.saturating_sub(3)).collect();
                 format!("{}...", truncated)
             } else {
                 line.to_string()
@@ -531,19 +713,19 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
         let output = Command::new("git")
             .arg("status")
             .args(args)
-            .output()
+            .output_timed()
             .context("Failed to run git status")?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
         if verbose > 0 || !stderr.is_empty() {
-            eprint!("{}", stderr);
+            render_eprint!("{}", stderr);
         }
 
         // Apply minimal filtering: strip ANSI, remove hints, empty lines
         let filtered = filter_status_with_args(&stdout);
-        print!("{}", filtered);
+        render_print!("{}", filtered);
 
         timer.track(
             &format!("git status {}", args.join(" ")),
@@ -552,6 +734,7 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
             &filtered,
         );
 
+        crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &stderr, &filtered);
         return Ok(());
     }
 
@@ -559,13 +742,13 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     // Get raw git status for tracking
     let raw_output = Command::new("git")
         .args(["status"])
-        .output()
+        .output_timed()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("git")
         .args(["status", "--porcelain", "-b"])
-        .output()
+        .output_timed()
         .context("Failed to run git status")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -577,10 +760,11 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
         format_status_output(&stdout)
     };
 
-    println!("{}", formatted);
+    render_println!("{}", formatted);
 
     // Track for statistics
     timer.track("git status", "rtk git status", &raw_output, &formatted);
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &stderr, &formatted);
 
     Ok(())
 }
@@ -600,10 +784,10 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    let output = cmd.output().context("Failed to run git add")?;
+    let output = cmd.output_timed().context("Failed to run git add")?;
 
     if verbose > 0 {
-        eprintln!("git add executed");
+        render_eprintln!("git add executed");
     }
 
     let raw_output = format!(
@@ -616,7 +800,7 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
         // Count what was added
         let status_output = Command::new("git")
             .args(["diff", "--cached", "--stat", "--shortstat"])
-            .output()
+            .output_timed()
             .context("Failed to check staged files")?;
 
         let stat = String::from_utf8_lossy(&status_output.stdout);
@@ -632,7 +816,7 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
             }
         };
 
-        println!("{}", compact);
+        render_println!("{}", compact);
 
         timer.track(
             &format!("git add {}", args.join(" ")),
@@ -643,15 +827,15 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        eprintln!("FAILED: git add");
+        render_eprintln!("FAILED: git add");
         if !stderr.trim().is_empty() {
-            eprintln!("{}", stderr);
+            render_eprintln!("{}", stderr);
         }
         if !stdout.trim().is_empty() {
-            eprintln!("{}", stdout);
+            render_eprintln!("{}", stdout);
         }
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -661,12 +845,12 @@ fn run_commit(message: &str, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git commit -m \"{}\"", message);
+        render_eprintln!("git commit -m \"{}\"", message);
     }
 
     let output = Command::new("git")
         .args(["commit", "-m", message])
-        .output()
+        .output_timed()
         .context("Failed to run git commit")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -690,7 +874,7 @@ fn run_commit(message: &str, verbose: u8) -> Result<()> {
             "ok ✓".to_string()
         };
 
-        println!("{}", compact);
+        render_println!("{}", compact);
 
         timer.track(
             &format!("git commit -m \"{}\"", message),
@@ -700,22 +884,23 @@ fn run_commit(message: &str, verbose: u8) -> Result<()> {
         );
     } else {
         if stderr.contains("nothing to commit") || stdout.contains("nothing to commit") {
-            println!("ok (nothing to commit)");
+            render_println!("nothing to commit");
             timer.track(
                 &format!("git commit -m \"{}\"", message),
                 "rtk git commit",
                 &raw_output,
-                "ok (nothing to commit)",
+                "nothing to commit",
             );
         } else {
-            eprintln!("FAILED: git commit");
+            render_eprintln!("FAILED: git commit");
             if !stderr.trim().is_empty() {
-                eprintln!("{}", stderr);
+                render_eprintln!("{}", stderr);
             }
             if !stdout.trim().is_empty() {
-                eprintln!("{}", stdout);
+                render_eprintln!("{}", stdout);
             }
         }
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -725,7 +910,7 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git push");
+        render_eprintln!("git push");
     }
 
     let mut cmd = Command::new("git");
@@ -734,7 +919,7 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git push")?;
+    let output = cmd.output_timed().context("Failed to run git push")?;
 
     let stderr = String::from_utf8_lossy(&output.stderr);
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -761,7 +946,7 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
             }
         };
 
-        println!("{}", compact);
+        render_println!("{}", compact);
 
         timer.track(
             &format!("git push {}", args.join(" ")),
@@ -770,13 +955,14 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
             &compact,
         );
     } else {
-        eprintln!("FAILED: git push");
+        render_eprintln!("FAILED: git push");
         if !stderr.trim().is_empty() {
-            eprintln!("{}", stderr);
+            render_eprintln!("{}", stderr);
         }
         if !stdout.trim().is_empty() {
-            eprintln!("{}", stdout);
+            render_eprintln!("{}", stdout);
         }
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -786,7 +972,7 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git pull");
+        render_eprintln!("git pull");
     }
 
     let mut cmd = Command::new("git");
@@ -795,7 +981,7 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git pull")?;
+    let output = cmd.output_timed().context("Failed to run git pull")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -846,7 +1032,7 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
                 }
             };
 
-        println!("{}", compact);
+        render_println!("{}", compact);
 
         timer.track(
             &format!("git pull {}", args.join(" ")),
@@ -855,13 +1041,14 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
             &compact,
         );
     } else {
-        eprintln!("FAILED: git pull");
+        render_eprintln!("FAILED: git pull");
         if !stderr.trim().is_empty() {
-            eprintln!("{}", stderr);
+            render_eprintln!("{}", stderr);
         }
         if !stdout.trim().is_empty() {
-            eprintln!("{}", stdout);
+            render_eprintln!("{}", stdout);
         }
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -871,7 +1058,7 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git branch");
+        render_eprintln!("git branch");
     }
 
     let mut cmd = Command::new("git");
@@ -886,7 +1073,7 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git branch")?;
+        let output = cmd.output_timed().context("Failed to run git branch")?;
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
         let combined = format!("{}{}", stdout, stderr);
@@ -905,15 +1092,16 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         );
 
         if output.status.success() {
-            println!("ok ✓");
+            render_println!("ok ✓");
         } else {
-            eprintln!("FAILED: git branch");
+            render_eprintln!("FAILED: git branch");
             if !stderr.trim().is_empty() {
-                eprintln!("{}", stderr);
+                render_eprintln!("{}", stderr);
             }
             if !stdout.trim().is_empty() {
-                eprintln!("{}", stdout);
+                render_eprintln!("{}", stdout);
             }
+            crate::utils::exit(output.status.code().unwrap_or(1));
         }
         return Ok(());
     }
@@ -924,12 +1112,12 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git branch")?;
+    let output = cmd.output_timed().context("Failed to run git branch")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let raw = stdout.to_string();
 
     let filtered = filter_branch_output(&stdout);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
 
     timer.track(
         &format!("git branch {}", args.join(" ")),
@@ -937,6 +1125,11 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         &raw,
         &filtered,
     );
+    crate::utils::exit_on_failure(
+        output.status.code().unwrap_or(1),
+        &String::from_utf8_lossy(&output.stderr),
+        &filtered,
+    );
 
     Ok(())
 }
@@ -999,7 +1192,7 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git fetch");
+        render_eprintln!("git fetch");
     }
 
     let mut cmd = Command::new("git");
@@ -1008,17 +1201,17 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run git fetch")?;
+    let output = cmd.output_timed().context("Failed to run git fetch")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}{}", stdout, stderr);
 
     if !output.status.success() {
-        eprintln!("FAILED: git fetch");
+        render_eprintln!("FAILED: git fetch");
         if !stderr.trim().is_empty() {
-            eprintln!("{}", stderr);
+            render_eprintln!("{}", stderr);
         }
-        return Ok(());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     // Count new refs from stderr (git fetch outputs to stderr)
@@ -1033,7 +1226,7 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
         "ok fetched".to_string()
     };
 
-    println!("{}", msg);
+    render_println!("{}", msg);
     timer.track("git fetch", "rtk git fetch", &raw, &msg);
 
     Ok(())
@@ -1043,28 +1236,33 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git stash {:?}", subcommand);
+        render_eprintln!("git stash {:?}", subcommand);
     }
 
     match subcommand {
         Some("list") => {
             let output = Command::new("git")
                 .args(["stash", "list"])
-                .output()
+                .output_timed()
                 .context("Failed to run git stash list")?;
             let stdout = String::from_utf8_lossy(&output.stdout);
             let raw = stdout.to_string();
 
             if stdout.trim().is_empty() {
                 let msg = "No stashes";
-                println!("{}", msg);
+                render_println!("{}", msg);
                 timer.track("git stash list", "rtk git stash list", &raw, msg);
                 return Ok(());
             }
 
             let filtered = filter_stash_list(&stdout);
-            println!("{}", filtered);
+            render_println!("{}", filtered);
             timer.track("git stash list", "rtk git stash list", &raw, &filtered);
+            crate::utils::exit_on_failure(
+                output.status.code().unwrap_or(1),
+                &String::from_utf8_lossy(&output.stderr),
+                &filtered,
+            );
         }
         Some("show") => {
             let mut cmd = Command::new("git");
@@ -1072,21 +1270,26 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash show")?;
+            let output = cmd.output_timed().context("Failed to run git stash show")?;
             let stdout = String::from_utf8_lossy(&output.stdout);
             let raw = stdout.to_string();
 
             let filtered = if stdout.trim().is_empty() {
                 let msg = "Empty stash";
-                println!("{}", msg);
+                render_println!("{}", msg);
                 msg.to_string()
             } else {
                 let compacted = compact_diff(&stdout, 100);
-                println!("{}", compacted);
+                render_println!("{}", compacted);
                 compacted
             };
 
             timer.track("git stash show", "rtk git stash show", &raw, &filtered);
+            crate::utils::exit_on_failure(
+                output.status.code().unwrap_or(1),
+                &String::from_utf8_lossy(&output.stderr),
+                &filtered,
+            );
         }
         Some("pop") | Some("apply") | Some("drop") | Some("push") => {
             let sub = subcommand.unwrap();
@@ -1095,19 +1298,19 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash")?;
+            let output = cmd.output_timed().context("Failed to run git stash")?;
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             let combined = format!("{}{}", stdout, stderr);
 
             let msg = if output.status.success() {
                 let msg = format!("ok stash {}", sub);
-                println!("{}", msg);
+                render_println!("{}", msg);
                 msg
             } else {
-                eprintln!("FAILED: git stash {}", sub);
+                render_eprintln!("FAILED: git stash {}", sub);
                 if !stderr.trim().is_empty() {
-                    eprintln!("{}", stderr);
+                    render_eprintln!("{}", stderr);
                 }
                 combined.clone()
             };
@@ -1118,6 +1321,9 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 &combined,
                 &msg,
             );
+            if !output.status.success() {
+                crate::utils::exit(output.status.code().unwrap_or(1));
+            }
         }
         _ => {
             // Default: git stash (push)
@@ -1126,7 +1332,7 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             for arg in args {
                 cmd.arg(arg);
             }
-            let output = cmd.output().context("Failed to run git stash")?;
+            let output = cmd.output_timed().context("Failed to run git stash")?;
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             let combined = format!("{}{}", stdout, stderr);
@@ -1134,22 +1340,25 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             let msg = if output.status.success() {
                 if stdout.contains("No local changes") {
                     let msg = "ok (nothing to stash)";
-                    println!("{}", msg);
+                    render_println!("{}", msg);
                     msg.to_string()
                 } else {
                     let msg = "ok stashed";
-                    println!("{}", msg);
+                    render_println!("{}", msg);
                     msg.to_string()
                 }
             } else {
-                eprintln!("FAILED: git stash");
+                render_eprintln!("FAILED: git stash");
                 if !stderr.trim().is_empty() {
-                    eprintln!("{}", stderr);
+                    render_eprintln!("{}", stderr);
                 }
                 combined.clone()
             };
 
             timer.track("git stash", "rtk git stash", &combined, &msg);
+            if !output.status.success() {
+                crate::utils::exit(output.status.code().unwrap_or(1));
+            }
         }
     }
 
@@ -1181,7 +1390,7 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git worktree list");
+        render_eprintln!("git worktree list");
     }
 
     // If args contain "add", "remove", "prune" etc., pass through
@@ -1195,7 +1404,7 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd.output().context("Failed to run git worktree")?;
+        let output = cmd.output_timed().context("Failed to run git worktree")?;
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
         let combined = format!("{}{}", stdout, stderr);
@@ -1214,12 +1423,13 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
         );
 
         if output.status.success() {
-            println!("ok ✓");
+            render_println!("ok ✓");
         } else {
-            eprintln!("FAILED: git worktree {}", args.join(" "));
+            render_eprintln!("FAILED: git worktree {}", args.join(" "));
             if !stderr.trim().is_empty() {
-                eprintln!("{}", stderr);
+                render_eprintln!("{}", stderr);
             }
+            crate::utils::exit(output.status.code().unwrap_or(1));
         }
         return Ok(());
     }
@@ -1227,15 +1437,20 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
     // Default: list mode
     let output = Command::new("git")
         .args(["worktree", "list"])
-        .output()
+        .output_timed()
         .context("Failed to run git worktree list")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let raw = stdout.to_string();
 
     let filtered = filter_worktree_list(&stdout);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
     timer.track("git worktree list", "rtk git worktree", &raw, &filtered);
+    crate::utils::exit_on_failure(
+        output.status.code().unwrap_or(1),
+        &String::from_utf8_lossy(&output.stderr),
+        &filtered,
+    );
 
     Ok(())
 }
@@ -1272,11 +1487,11 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git passthrough: {:?}", args);
+        render_eprintln!("git passthrough: {:?}", args);
     }
     let status = Command::new("git")
         .args(args)
-        .status()
+        .status_timed()
         .context("Failed to run git")?;
 
     let args_str = tracking::args_display(args);
@@ -1286,7 +1501,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
     Ok(())
 }
@@ -1295,6 +1510,57 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_shortstat() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 10 insertions(+), 2 deletions(-)"),
+            Some((3, 10, 2))
+        );
+        assert_eq!(
+            parse_shortstat(" 1 file changed, 1 deletion(-)"),
+            Some((1, 0, 1))
+        );
+        assert_eq!(parse_shortstat("abc1234 fix: files changed"), None);
+    }
+
+    #[test]
+    fn test_parse_git_output_log() {
+        let output = "abc1234\x1fAlice\x1f2024-01-01T10:00:00+00:00\x1ffeat: add thing\n\
+                      \n 2 files changed, 5 insertions(+), 1 deletion(-)\n\
+                      def5678\x1fBob\x1f2024-01-02T10:00:00+00:00\x1ffix: typo\n\
+                      \n 1 file changed, 1 insertion(+), 1 deletion(-)\n";
+        let result = parse_git_output("log", output);
+        assert_eq!(result.tier(), 1);
+        let git = result.unwrap();
+        assert_eq!(git.operation, "log");
+        assert_eq!(git.commits.len(), 2);
+        assert_eq!(git.commits[0].hash, "abc1234");
+        assert_eq!(git.commits[0].author, "Alice");
+        assert_eq!(git.commits[1].message, "fix: typo");
+        assert_eq!(git.files_changed, 3);
+        assert_eq!(git.insertions, 6);
+        assert_eq!(git.deletions, 2);
+    }
+
+    #[test]
+    fn test_parse_git_output_unexpected_lines_degrade() {
+        let result = parse_git_output("log", "abc1234 oneline subject\n");
+        assert_eq!(result.tier(), 2);
+        assert!(result.unwrap().commits.is_empty());
+    }
+
+    #[test]
+    fn test_parse_git_status() {
+        let porcelain = " M src/main.rs\n?? new.txt\n";
+        let result = parse_git_status(porcelain, Some(" 1 file changed, 4 insertions(+)\n"));
+        assert_eq!(result.tier(), 1);
+        let git = result.unwrap();
+        assert_eq!(git.files_changed, 2);
+        assert_eq!(git.insertions, 4);
+
+        assert_eq!(parse_git_status(porcelain, None).tier(), 2);
+    }
+
     #[test]
     fn test_compact_diff() {
         let diff = r#"diff --git a/foo.rs b/foo.rs
@@ -1302,7 +1568,7 @@ mod tests {
 +++ b/foo.rs
 @@ -1,3 +1,4 @@
  fn main() {
-+    println!("hello");
++    render_println!("hello");
  }
 "#;
         let result = compact_diff(diff, 100);
@@ -1469,7 +1735,7 @@ Changes not staged for commit:
 no changes added to commit (use "git add" and/or "git commit -a")
 "#;
         let result = filter_status_with_args(output);
-        eprintln!("Result:\n{}", result);
+        render_eprintln!("Result:\n{}", result);
         assert!(result.contains("On branch main"));
         assert!(result.contains("modified:   src/main.rs"));
         assert!(
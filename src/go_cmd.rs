@@ -1,6 +1,15 @@
+use crate::context;
+use crate::delta;
+use crate::parser::{
+    self, truncate_output, BuildOutput, LintIssue, LintResult, LintSeverity, ParseResult,
+    TestFailure, TestResult,
+};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::ffi::OsString;
@@ -47,24 +56,41 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: go test -json {}", args.join(" "));
+        render_eprintln!("Running: go test -json {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run go test. Is Go installed?")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_go_test_json(&stdout);
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let result = parse_go_test_json(&stdout, &raw);
+        let json = parser::print_json("go test", result, exit_code);
+        timer.track(
+            &format!("go test {}", args.join(" ")),
+            &format!("rtk go test {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
 
-    println!("{}", filtered);
+    let filtered = delta::report(&format!("go test {}", args.join(" ")), || {
+        parse_go_test_json(&stdout, &raw)
+    })
+    .unwrap_or_else(|| filter_go_test_json(&stdout));
+
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("go test {}", args.join(" ")), &raw, &filtered);
 
     // Include stderr if present (build errors, etc.)
     if !stderr.trim().is_empty() {
-        eprintln!("{}", stderr.trim());
+        render_eprintln!("{}", stderr.trim());
     }
 
     timer.track(
@@ -75,9 +101,7 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
@@ -93,21 +117,34 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: go build {}", args.join(" "));
+        render_eprintln!("Running: go build {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run go build. Is Go installed?")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("go build", parse_go_build(&raw, exit_code), exit_code);
+        timer.track(
+            &format!("go build {}", args.join(" ")),
+            &format!("rtk go build {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
+
     let filtered = filter_go_build(&raw);
 
     if !filtered.is_empty() {
-        println!("{}", filtered);
+        render_println!("{}", filtered);
+        tee::print_hint(&format!("go build {}", args.join(" ")), &raw, &filtered);
     }
 
     timer.track(
@@ -118,9 +155,7 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
@@ -136,21 +171,35 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: go vet {}", args.join(" "));
+        render_eprintln!("Running: go vet {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run go vet. Is Go installed?")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_go_vet(&raw);
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("go vet", parse_go_vet(&raw), exit_code);
+        timer.track(
+            &format!("go vet {}", args.join(" ")),
+            &format!("rtk go vet {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
+
+    let filtered = delta::report(&format!("go vet {}", args.join(" ")), || parse_go_vet(&raw))
+        .unwrap_or_else(|| filter_go_vet(&raw));
 
     if !filtered.is_empty() {
-        println!("{}", filtered);
+        render_println!("{}", filtered);
+        tee::print_hint(&format!("go vet {}", args.join(" ")), &raw, &filtered);
     }
 
     timer.track(
@@ -161,9 +210,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
@@ -184,19 +231,19 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: go {} ...", subcommand);
+        render_eprintln!("Running: go {} ...", subcommand);
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .with_context(|| format!("Failed to run go {}", subcommand))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    print!("{}", stdout);
-    eprint!("{}", stderr);
+    render_print!("{}", stdout);
+    render_eprint!("{}", stderr);
 
     timer.track(
         &format!("go {}", subcommand),
@@ -207,16 +254,18 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
 
     // Preserve exit code
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
 }
 
-/// Parse go test -json output (NDJSON format)
-fn filter_go_test_json(output: &str) -> String {
+/// Fold go test -json events (NDJSON) into per-package results.
+/// Also returns how many non-empty lines were not JSON events.
+fn parse_go_test_events(output: &str) -> (HashMap<String, PackageResult>, usize) {
     let mut packages: HashMap<String, PackageResult> = HashMap::new();
     let mut current_test_output: HashMap<(String, String), Vec<String>> = HashMap::new(); // (package, test) -> outputs
+    let mut skipped_lines = 0;
 
     for line in output.lines() {
         let trimmed = line.trim();
@@ -226,7 +275,10 @@ fn filter_go_test_json(output: &str) -> String {
 
         let event: GoTestEvent = match serde_json::from_str(trimmed) {
             Ok(e) => e,
-            Err(_) => continue, // Skip non-JSON lines
+            Err(_) => {
+                skipped_lines += 1;
+                continue; // Skip non-JSON lines
+            }
         };
 
         let package = event.package.unwrap_or_else(|| "unknown".to_string());
@@ -267,6 +319,76 @@ fn filter_go_test_json(output: &str) -> String {
         }
     }
 
+    (packages, skipped_lines)
+}
+
+/// Key lines of a failed test's output (assertion messages, panics)
+fn relevant_failure_lines(outputs: &[String]) -> Vec<&String> {
+    outputs
+        .iter()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            !line.trim().is_empty()
+                && !line.starts_with("=== RUN")
+                && !line.starts_with("--- FAIL")
+                && (lower.contains("error")
+                    || lower.contains("expected")
+                    || lower.contains("got")
+                    || lower.contains("panic")
+                    || line.trim().starts_with("at "))
+        })
+        .take(5)
+        .collect()
+}
+
+/// Canonical result for `--format json`; a failure's `file_path` is its package
+fn parse_go_test_json(stdout: &str, raw: &str) -> ParseResult<TestResult> {
+    let (packages, skipped_lines) = parse_go_test_events(stdout);
+    if packages.is_empty() {
+        return ParseResult::Passthrough(truncate_output(raw.trim(), 2000));
+    }
+
+    let mut names: Vec<&String> = packages.keys().collect();
+    names.sort();
+    let mut result = TestResult {
+        total: 0,
+        passed: 0,
+        failed: 0,
+        skipped: 0,
+        duration_ms: None,
+        failures: Vec::new(),
+    };
+    for name in names {
+        let pkg = &packages[name];
+        result.passed += pkg.pass;
+        result.failed += pkg.fail;
+        result.skipped += pkg.skip;
+        for (test, outputs) in &pkg.failed_tests {
+            result.failures.push(TestFailure {
+                test_name: test.clone(),
+                file_path: name.clone(),
+                error_message: relevant_failure_lines(outputs)
+                    .iter()
+                    .map(|l| l.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                stack_trace: None,
+            });
+        }
+    }
+    result.total = result.passed + result.failed + result.skipped;
+
+    if skipped_lines > 0 {
+        let warning = format!("{} non-JSON lines in go test output skipped", skipped_lines);
+        return ParseResult::Degraded(result, vec![warning]);
+    }
+    ParseResult::Full(result)
+}
+
+/// Parse go test -json output (NDJSON format)
+fn filter_go_test_json(output: &str) -> String {
+    let (packages, _) = parse_go_test_events(output);
+
     // Build summary
     let total_packages = packages.len();
     let total_pass: usize = packages.values().map(|p| p.pass).sum();
@@ -312,24 +434,8 @@ fn filter_go_test_json(output: &str) -> String {
             result.push_str(&format!("  ❌ {}\n", test));
 
             // Show failure output (limit to key lines)
-            let relevant_lines: Vec<&String> = outputs
-                .iter()
-                .filter(|line| {
-                    let lower = line.to_lowercase();
-                    !line.trim().is_empty()
-                        && !line.starts_with("=== RUN")
-                        && !line.starts_with("--- FAIL")
-                        && (lower.contains("error")
-                            || lower.contains("expected")
-                            || lower.contains("got")
-                            || lower.contains("panic")
-                            || line.trim().starts_with("at "))
-                })
-                .take(5)
-                .collect();
-
-            for line in relevant_lines {
-                result.push_str(&format!("     {}\n", truncate(line, 100)));
+            for line in relevant_failure_lines(outputs) {
+                result.push_str(&format!("     {}\n", truncate(line, context::width(100))));
             }
         }
     }
@@ -337,6 +443,53 @@ fn filter_go_test_json(output: &str) -> String {
     result.trim().to_string()
 }
 
+lazy_static::lazy_static! {
+    // Pattern: ./main.go:12:5: undefined: foo (column optional, `vet: ` prefix from go vet)
+    static ref GO_DIAGNOSTIC: Regex =
+        Regex::new(r"^(?:vet: )?(.+?\.go):(\d+)(?::(\d+))?:\s*(.+)$").unwrap();
+}
+
+/// Canonical result for `--format json`: go build only reports errors
+fn parse_go_build(output: &str, exit_code: i32) -> ParseResult<BuildOutput> {
+    let errors = output
+        .lines()
+        .filter(|line| GO_DIAGNOSTIC.is_match(line.trim()))
+        .count();
+    let result = BuildOutput {
+        success: exit_code == 0,
+        duration_ms: None,
+        warnings: 0,
+        errors,
+        bundles: Vec::new(),
+        routes: Vec::new(),
+    };
+    if exit_code != 0 && errors == 0 {
+        let warning = "go build failed without file:line errors".to_string();
+        return ParseResult::Degraded(result, vec![warning]);
+    }
+    ParseResult::Full(result)
+}
+
+/// Canonical result for `--format json`
+fn parse_go_vet(output: &str) -> ParseResult<LintResult> {
+    let issues = output
+        .lines()
+        .filter_map(|line| GO_DIAGNOSTIC.captures(line.trim()))
+        .map(|caps| LintIssue {
+            file_path: caps[1].to_string(),
+            line: caps[2].parse().unwrap_or(0),
+            column: caps
+                .get(3)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(0),
+            severity: LintSeverity::Warning,
+            rule_id: String::new(),
+            message: caps[4].to_string(),
+        })
+        .collect();
+    ParseResult::Full(LintResult::from_issues(issues))
+}
+
 /// Filter go build output - show only errors
 fn filter_go_build(output: &str) -> String {
     let mut errors: Vec<String> = Vec::new();
@@ -370,7 +523,11 @@ fn filter_go_build(output: &str) -> String {
     result.push_str("═══════════════════════════════════════\n");
 
     for (i, error) in errors.iter().take(20).enumerate() {
-        result.push_str(&format!("{}. {}\n", i + 1, truncate(error, 120)));
+        result.push_str(&format!(
+            "{}. {}\n",
+            i + 1,
+            truncate(error, context::width(120))
+        ));
     }
 
     if errors.len() > 20 {
@@ -402,7 +559,11 @@ fn filter_go_vet(output: &str) -> String {
     result.push_str("═══════════════════════════════════════\n");
 
     for (i, issue) in issues.iter().take(20).enumerate() {
-        result.push_str(&format!("{}. {}\n", i + 1, truncate(issue, 120)));
+        result.push_str(&format!(
+            "{}. {}\n",
+            i + 1,
+            truncate(issue, context::width(120))
+        ));
     }
 
     if issues.len() > 20 {
@@ -453,6 +614,52 @@ mod tests {
         assert!(result.contains("expected 5, got 3"));
     }
 
+    #[test]
+    fn test_parse_go_test_json_canonical() {
+        let output = r#"{"Action":"pass","Package":"example.com/foo","Test":"TestOk","Elapsed":0.1}
+{"Action":"output","Package":"example.com/foo","Test":"TestFail","Output":"    Error: expected 5, got 3\n"}
+{"Action":"fail","Package":"example.com/foo","Test":"TestFail","Elapsed":0.5}
+{"Action":"skip","Package":"example.com/bar","Test":"TestSkip","Elapsed":0}"#;
+
+        let result = parse_go_test_json(output, output);
+        assert_eq!(result.tier(), 1);
+        let tests = result.unwrap();
+        assert_eq!(tests.total, 3);
+        assert_eq!(tests.passed, 1);
+        assert_eq!(tests.failed, 1);
+        assert_eq!(tests.skipped, 1);
+        assert_eq!(tests.failures[0].test_name, "TestFail");
+        assert_eq!(tests.failures[0].file_path, "example.com/foo");
+        assert_eq!(tests.failures[0].error_message, "Error: expected 5, got 3");
+    }
+
+    #[test]
+    fn test_parse_go_test_json_tiers() {
+        let build_failure = "# example.com/foo\nfoo.go:3:1: syntax error";
+        assert_eq!(parse_go_test_json("", build_failure).tier(), 3);
+
+        let mixed = "warning: GOPATH set\n{\"Action\":\"pass\",\"Package\":\"p\",\"Test\":\"T\"}";
+        let result = parse_go_test_json(mixed, mixed);
+        assert_eq!(result.tier(), 2);
+        assert_eq!(result.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_go_build_and_vet() {
+        let output =
+            "# example.com/foo\n./main.go:10:5: undefined: missingFunc\n./util.go:3: unused import";
+        let build = parse_go_build(output, 1).unwrap();
+        assert!(!build.success);
+        assert_eq!(build.errors, 2);
+
+        let vet = parse_go_vet(output).unwrap();
+        assert_eq!(vet.total_issues, 2);
+        assert_eq!(vet.issues[0].file_path, "./main.go");
+        assert_eq!(vet.issues[0].column, 5);
+        assert_eq!(vet.issues[1].line, 3);
+        assert_eq!(vet.issues[1].message, "unused import");
+    }
+
     #[test]
     fn test_filter_go_build_success() {
         let output = "";
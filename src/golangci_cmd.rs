@@ -1,3 +1,7 @@
+use crate::delta;
+use crate::parser::{self, truncate_output, LintIssue, LintResult, LintSeverity, ParseResult};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -52,10 +56,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: golangci-lint run --out-format=json");
+        render_eprintln!("Running: golangci-lint run --out-format=json");
     }
 
-    let output = cmd.output().context(
+    let output = cmd.output_timed().context(
         "Failed to run golangci-lint. Is it installed? Try: go install github.com/golangci/golangci-lint/cmd/golangci-lint@latest",
     )?;
 
@@ -63,13 +67,29 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_golangci_json(&stdout);
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("golangci-lint", parse_golangci_json(&stdout), exit_code);
+        timer.track(
+            &format!("golangci-lint {}", args.join(" ")),
+            &format!("rtk golangci-lint {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
+
+    let filtered = delta::report(&format!("golangci-lint {}", args.join(" ")), || {
+        parse_golangci_json(&stdout)
+    })
+    .unwrap_or_else(|| filter_golangci_json(&stdout));
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("golangci-lint {}", args.join(" ")), &raw, &filtered);
 
     // Include stderr if present (config errors, etc.)
     if !stderr.trim().is_empty() && verbose > 0 {
-        eprintln!("{}", stderr.trim());
+        render_eprintln!("{}", stderr.trim());
     }
 
     timer.track(
@@ -79,11 +99,31 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         &filtered,
     );
 
-    // golangci-lint returns exit code 1 when issues found (expected behavior)
-    // Don't exit with error code in that case
+    // golangci-lint exits 1 when issues are found; keep that for CI
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
     Ok(())
 }
 
+/// Canonical result for `--format json`; the linter name is the rule id
+fn parse_golangci_json(output: &str) -> ParseResult<LintResult> {
+    match serde_json::from_str::<GolangciOutput>(output) {
+        Ok(o) => ParseResult::Full(LintResult::from_issues(
+            o.issues
+                .into_iter()
+                .map(|i| LintIssue {
+                    file_path: i.pos.filename,
+                    line: i.pos.line,
+                    column: i.pos.column,
+                    severity: LintSeverity::Error,
+                    rule_id: i.from_linter,
+                    message: i.text,
+                })
+                .collect(),
+        )),
+        Err(_) => ParseResult::Passthrough(truncate_output(output.trim(), 2000)),
+    }
+}
+
 /// Filter golangci-lint JSON output - group by linter and file
 fn filter_golangci_json(output: &str) -> String {
     let result: Result<GolangciOutput, _> = serde_json::from_str(output);
@@ -235,6 +275,20 @@ mod tests {
         assert!(result.contains("utils.go"));
     }
 
+    #[test]
+    fn test_parse_golangci_json() {
+        let output = r#"{"Issues": [{"FromLinter": "errcheck", "Text": "Error return value is not checked",
+            "Pos": {"Filename": "main.go", "Line": 42, "Column": 5}}]}"#;
+        let result = parse_golangci_json(output);
+        assert_eq!(result.tier(), 1);
+        let lint = result.unwrap();
+        assert_eq!(lint.total_issues, 1);
+        assert_eq!(lint.issues[0].rule_id, "errcheck");
+        assert_eq!(lint.issues[0].column, 5);
+
+        assert_eq!(parse_golangci_json("level=error msg=boom").tier(), 3);
+    }
+
     #[test]
     fn test_compact_path() {
         assert_eq!(
@@ -1,3 +1,6 @@
+use crate::context;
+use crate::redact;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
@@ -17,12 +20,21 @@ pub fn run(
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("grep: '{}' in {}", pattern, path);
+        render_eprintln!("grep: '{}' in {}", pattern, path);
     }
 
     let mut rg_cmd = Command::new("rg");
     rg_cmd.args(["-n", "--no-heading", pattern, path]);
 
+    // Skip filters.ignore_dirs / ignore_files (rg already honors .gitignore)
+    let ctx = context::get();
+    for dir in &ctx.ignore_dirs {
+        rg_cmd.arg("--glob").arg(format!("!{}/", dir));
+    }
+    for file in &ctx.ignore_files {
+        rg_cmd.arg("--glob").arg(format!("!{}", file));
+    }
+
     if let Some(ft) = file_type {
         rg_cmd.arg("--type").arg(ft);
     }
@@ -32,23 +44,37 @@ pub fn run(
     }
 
     let output = rg_cmd
-        .output()
-        .or_else(|_| Command::new("grep").args(["-rn", pattern, path]).output())
+        .output_timed()
+        .or_else(|_| {
+            Command::new("grep")
+                .args(["-rn", pattern, path])
+                .args(
+                    ctx.ignore_dirs
+                        .iter()
+                        .map(|d| format!("--exclude-dir={}", d)),
+                )
+                .args(ctx.ignore_files.iter().map(|f| format!("--exclude={}", f)))
+                .output_timed()
+        })
         .context("grep/rg failed")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let exit_code = output.status.code().unwrap_or(1);
 
     let raw_output = stdout.to_string();
 
     if stdout.trim().is_empty() {
         let msg = format!("🔍 0 for '{}'", pattern);
-        println!("{}", msg);
+        render_println!("{}", msg);
         timer.track(
             &format!("grep -rn '{}' {}", pattern, path),
             "rtk grep",
             &raw_output,
             &msg,
         );
+        // Exit 1 means no match, as with grep; 2 is an error shown from stderr
+        crate::utils::exit_on_failure(exit_code, &stderr, &msg);
         return Ok(());
     }
 
@@ -73,8 +99,17 @@ pub fn run(
         by_file.entry(file).or_default().push((line_num, cleaned));
     }
 
+    // Headers are rtk's own; matched lines are file content and print as is
     let mut rtk_output = String::new();
-    rtk_output.push_str(&format!("🔍 {} in {}F:\n\n", total, by_file.len()));
+    let mut emit = |text: String, matched: bool| {
+        if matched {
+            context::raw(redact::text(&text).as_bytes());
+        } else {
+            render_print!("{}", text);
+        }
+        rtk_output.push_str(&text);
+    };
+    emit(format!("🔍 {} in {}F:\n\n", total, by_file.len()), false);
 
     let mut shown = 0;
     let mut files: Vec<_> = by_file.iter().collect();
@@ -86,10 +121,10 @@ pub fn run(
         }
 
         let file_display = compact_path(file);
-        rtk_output.push_str(&format!("📄 {} ({}):\n", file_display, matches.len()));
+        emit(format!("📄 {} ({}):\n", file_display, matches.len()), false);
 
         for (line_num, content) in matches.iter().take(10) {
-            rtk_output.push_str(&format!("  {:>4}: {}\n", line_num, content));
+            emit(format!("  {:>4}: {}\n", line_num, content), true);
             shown += 1;
             if shown >= max_results {
                 break;
@@ -97,16 +132,14 @@ pub fn run(
         }
 
         if matches.len() > 10 {
-            rtk_output.push_str(&format!("  +{}\n", matches.len() - 10));
+            emit(format!("  +{}\n", matches.len() - 10), false);
         }
-        rtk_output.push('\n');
+        emit("\n".to_string(), false);
     }
 
     if total > shown {
-        rtk_output.push_str(&format!("... +{}\n", total - shown));
+        emit(format!("... +{}\n", total - shown), false);
     }
-
-    print!("{}", rtk_output);
     timer.track(
         &format!("grep -rn '{}' {}", pattern, path),
         "rtk grep",
@@ -114,6 +147,7 @@ pub fn run(
         &rtk_output,
     );
 
+    crate::utils::exit_on_failure(exit_code, &stderr, &rtk_output);
     Ok(())
 }
 
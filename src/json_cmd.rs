@@ -10,14 +10,14 @@ pub fn run(file: &Path, max_depth: usize, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Analyzing JSON: {}", file.display());
+        render_eprintln!("Analyzing JSON: {}", file.display());
     }
 
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
 
     let schema = filter_json_string(&content, max_depth)?;
-    println!("{}", schema);
+    render_println!("{}", schema);
     timer.track(
         &format!("cat {}", file.display()),
         "rtk json",
@@ -32,7 +32,7 @@ pub fn run_stdin(max_depth: usize, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Analyzing JSON from stdin");
+        render_eprintln!("Analyzing JSON from stdin");
     }
 
     let mut content = String::new();
@@ -42,7 +42,7 @@ pub fn run_stdin(max_depth: usize, verbose: u8) -> Result<()> {
         .context("Failed to read from stdin")?;
 
     let schema = filter_json_string(&content, max_depth)?;
-    println!("{}", schema);
+    render_println!("{}", schema);
     timer.track("cat - (stdin)", "rtk json -", &content, &schema);
     Ok(())
 }
@@ -5,6 +5,7 @@ use regex::Regex;
 pub enum ErrorType {
     UnknownFlag,
     CommandNotFound,
+    #[allow(dead_code)]
     WrongSyntax,
     WrongPath,
     MissingArg,
@@ -442,7 +443,7 @@ mod tests {
         assert_eq!(command_similarity("git status", "npm install"), 0.0);
         let sim = command_similarity("git commit --amend", "git commit --ammend");
         // Debug: check what similarity actually is
-        println!("Similarity: {}", sim);
+        render_println!("Similarity: {}", sim);
         // Same base (0.5) + both have 1 arg, 0 intersection = 0.5 + 0 = 0.5
         assert_eq!(sim, 0.5);
     }
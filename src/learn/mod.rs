@@ -34,7 +34,7 @@ pub fn run(
     let sessions = provider.discover_sessions(project_filter.as_deref(), Some(since))?;
 
     if sessions.is_empty() {
-        println!("No Claude Code sessions found in the last {} days.", since);
+        render_println!("No Claude Code sessions found in the last {} days.", since);
         return Ok(());
     }
 
@@ -66,7 +66,7 @@ pub fn run(
     let corrections = find_corrections(&all_commands);
 
     if corrections.is_empty() {
-        println!(
+        render_println!(
             "No CLI corrections detected in {} sessions.",
             sessions.len()
         );
@@ -100,17 +100,17 @@ pub fn run(
                     "base_command": r.base_command,
                 })).collect::<Vec<_>>(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            render_println!("{}", serde_json::to_string_pretty(&json)?);
         }
         _ => {
             // Text output
             let report = format_console_report(&rules, filtered.len(), sessions.len(), since);
-            print!("{}", report);
+            render_print!("{}", report);
 
             if write_rules && !rules.is_empty() {
                 let rules_path = ".claude/rules/cli-corrections.md";
                 write_rules_file(&rules, rules_path)?;
-                println!("\nWritten to: {}", rules_path);
+                render_println!("\nWritten to: {}", rules_path);
             }
         }
     }
@@ -1,4 +1,9 @@
+use crate::context;
+use crate::delta;
+use crate::parser::{self, truncate_output, LintIssue, LintResult, LintSeverity, ParseResult};
 use crate::ruff_cmd;
+use crate::tee;
+use crate::timeout::{self, TimedCommand};
 use crate::tracking;
 use crate::utils::{package_manager_exec, truncate};
 use anyhow::{Context, Result};
@@ -37,7 +42,6 @@ struct PylintDiagnostic {
     #[allow(dead_code)]
     obj: String,
     line: usize,
-    #[allow(dead_code)]
     column: usize,
     path: String,
     symbol: String, // rule code like "unused-variable"
@@ -132,33 +136,49 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: {} with structured output", linter);
+        render_eprintln!("Running: {} with structured output", linter);
     }
 
-    let output = cmd.output().context(format!(
+    let output = cmd.output_timed().context(format!(
         "Failed to run {}. Is it installed? Try: pip install {} (or npm/pnpm for JS linters)",
         linter, linter
     ))?;
 
-    // Check if process was killed by signal (SIGABRT, SIGKILL, etc.)
-    if !output.status.success() && output.status.code().is_none() {
+    // Check if process was killed by signal (SIGABRT, SIGKILL, etc.), other than by --timeout
+    if !output.status.success() && output.status.code().is_none() && !timeout::expired() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!("⚠️  Linter process terminated abnormally (possibly out of memory)");
+        render_eprintln!("⚠️  Linter process terminated abnormally (possibly out of memory)");
         if !stderr.is_empty() {
-            eprintln!(
+            render_eprintln!(
                 "stderr: {}",
                 stderr.lines().take(5).collect::<Vec<_>>().join("\n")
             );
         }
-        return Ok(());
+        crate::utils::exit(1);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let result = parse_lint_output(linter, &stdout, &raw);
+        let json = parser::print_json(linter, result, exit_code);
+        timer.track(
+            &format!("{} {}", linter, args.join(" ")),
+            &format!("rtk lint {} {}", linter, args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
+
     // Dispatch to appropriate filter based on linter
-    let filtered = match linter {
+    let filtered = delta::report(&format!("{} {}", linter, args.join(" ")), || {
+        parse_lint_output(linter, &stdout, &raw)
+    })
+    .unwrap_or_else(|| match linter {
         "eslint" => filter_eslint_json(&stdout),
         "ruff" => {
             // Reuse ruff_cmd's JSON parser
@@ -171,9 +191,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         "pylint" => filter_pylint_json(&stdout),
         "mypy" => filter_mypy_output(&raw),
         _ => filter_generic_lint(&raw),
-    };
+    });
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("{} {}", linter, args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("{} {}", linter, args.join(" ")),
@@ -182,9 +203,104 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         &filtered,
     );
 
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
     Ok(())
 }
 
+/// Canonical result for `--format json`, dispatched like the text filters
+fn parse_lint_output(linter: &str, stdout: &str, raw: &str) -> ParseResult<LintResult> {
+    match linter {
+        "eslint" => parse_eslint_json(stdout),
+        "ruff" => ruff_cmd::parse_ruff_check_json(stdout),
+        "pylint" => parse_pylint_json(stdout),
+        "mypy" => parse_mypy_output(raw),
+        // No known format: nothing structured to report
+        _ => ParseResult::Passthrough(truncate_output(raw.trim(), 2000)),
+    }
+}
+
+fn parse_eslint_json(output: &str) -> ParseResult<LintResult> {
+    let results: Vec<EslintResult> = match serde_json::from_str(output) {
+        Ok(r) => r,
+        Err(_) => return ParseResult::Passthrough(truncate_output(output.trim(), 2000)),
+    };
+    let total_files = results.len();
+    let issues = results
+        .into_iter()
+        .flat_map(|r| {
+            let file_path = r.file_path;
+            r.messages.into_iter().map(move |m| LintIssue {
+                file_path: file_path.clone(),
+                line: m.line,
+                column: m.column,
+                severity: if m.severity >= 2 {
+                    LintSeverity::Error
+                } else {
+                    LintSeverity::Warning
+                },
+                rule_id: m.rule_id.unwrap_or_default(),
+                message: m.message,
+            })
+        })
+        .collect();
+    let mut lint = LintResult::from_issues(issues);
+    lint.total_files = total_files;
+    ParseResult::Full(lint)
+}
+
+fn parse_pylint_json(output: &str) -> ParseResult<LintResult> {
+    let diagnostics: Vec<PylintDiagnostic> = match serde_json::from_str(output) {
+        Ok(d) => d,
+        Err(_) => return ParseResult::Passthrough(truncate_output(output.trim(), 2000)),
+    };
+    let issues = diagnostics
+        .into_iter()
+        .map(|d| LintIssue {
+            severity: match d.msg_type.as_str() {
+                "error" | "fatal" => LintSeverity::Error,
+                "warning" => LintSeverity::Warning,
+                _ => LintSeverity::Info,
+            },
+            file_path: d.path,
+            line: d.line,
+            column: d.column,
+            rule_id: d.symbol,
+            message: d.message,
+        })
+        .collect();
+    ParseResult::Full(LintResult::from_issues(issues))
+}
+
+fn parse_mypy_output(output: &str) -> ParseResult<LintResult> {
+    let re =
+        Regex::new(r"^(.+\.py):(\d+):(?:(\d+):)? (error|warning|note): (.+?)(?:\s+\[(.+?)\])?$")
+            .unwrap();
+    let issues: Vec<LintIssue> = output
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .map(|caps| LintIssue {
+            file_path: caps[1].to_string(),
+            line: caps[2].parse().unwrap_or(0),
+            column: caps
+                .get(3)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(0),
+            severity: match &caps[4] {
+                "error" => LintSeverity::Error,
+                "warning" => LintSeverity::Warning,
+                _ => LintSeverity::Info,
+            },
+            message: caps[5].to_string(),
+            rule_id: caps.get(6).map_or("", |m| m.as_str()).to_string(),
+        })
+        .collect();
+
+    if issues.is_empty() && !output.contains("Success") && !output.trim().is_empty() {
+        return ParseResult::Passthrough(truncate_output(output.trim(), 2000));
+    }
+    ParseResult::Full(LintResult::from_issues(issues))
+}
+
 /// Filter ESLint JSON output - group by rule and file
 fn filter_eslint_json(output: &str) -> String {
     let results: Result<Vec<EslintResult>, _> = serde_json::from_str(output);
@@ -539,7 +655,7 @@ fn filter_generic_lint(output: &str) -> String {
     result.push_str("═══════════════════════════════════════\n");
 
     for issue in issues.iter().take(20) {
-        result.push_str(&format!("{}\n", truncate(issue, 100)));
+        result.push_str(&format!("{}\n", truncate(issue, context::width(100))));
     }
 
     if issues.len() > 20 {
@@ -712,6 +828,44 @@ Found 4 errors in 2 files (checked 5 source files)"#;
         assert!(result.contains("utils.py"));
     }
 
+    #[test]
+    fn test_parse_eslint_json_canonical() {
+        let output = r#"[
+  {"filePath": "/p/src/a.ts", "errorCount": 1, "warningCount": 1, "messages": [
+    {"ruleId": "no-unused-vars", "severity": 2, "message": "x is unused", "line": 3, "column": 7},
+    {"ruleId": "semi", "severity": 1, "message": "Missing semicolon", "line": 4, "column": 1}
+  ]},
+  {"filePath": "/p/src/b.ts", "errorCount": 0, "warningCount": 0, "messages": []}
+]"#;
+        let result = parse_lint_output("eslint", output, output);
+        assert_eq!(result.tier(), 1);
+        let lint = result.unwrap();
+        assert_eq!(lint.total_files, 2);
+        assert_eq!(lint.files_with_issues, 1);
+        assert_eq!(lint.errors, 1);
+        assert_eq!(lint.warnings, 1);
+        assert_eq!(lint.issues[0].rule_id, "no-unused-vars");
+        assert_eq!(lint.issues[0].line, 3);
+    }
+
+    #[test]
+    fn test_parse_mypy_output_canonical() {
+        let output = "src/main.py:10: error: Incompatible return value type  [return-value]\n\
+                      src/utils.py:25:5: warning: Unused \"type: ignore\" comment  [unused-ignore]\n\
+                      Found 2 errors in 2 files (checked 5 source files)";
+        let lint = parse_lint_output("mypy", "", output).unwrap();
+        assert_eq!(lint.total_issues, 2);
+        assert_eq!(lint.issues[0].rule_id, "return-value");
+        assert_eq!(lint.issues[0].message, "Incompatible return value type");
+        assert_eq!(lint.issues[1].column, 5);
+        assert_eq!(lint.issues[1].severity, LintSeverity::Warning);
+    }
+
+    #[test]
+    fn test_parse_unknown_linter_is_passthrough() {
+        assert_eq!(parse_lint_output("biome", "", "some output").tier(), 3);
+    }
+
     #[test]
     fn test_is_python_linter() {
         assert!(is_python_linter("ruff"));
@@ -8,7 +8,7 @@ use crate::filter::Language;
 /// Heuristic-based code summarizer - no external model needed
 pub fn run(file: &Path, _model: &str, _force_download: bool, verbose: u8) -> Result<()> {
     if verbose > 0 {
-        eprintln!("Analyzing: {}", file.display());
+        render_eprintln!("Analyzing: {}", file.display());
     }
 
     let content = fs::read_to_string(file)
@@ -22,8 +22,8 @@ pub fn run(file: &Path, _model: &str, _force_download: bool, verbose: u8) -> Res
 
     let summary = analyze_code(&content, &lang);
 
-    println!("{}", summary.line1);
-    println!("{}", summary.line2);
+    render_println!("{}", summary.line1);
+    render_println!("{}", summary.line2);
 
     Ok(())
 }
@@ -1,3 +1,4 @@
+use crate::context;
 use crate::tracking;
 use anyhow::Result;
 use regex::Regex;
@@ -11,12 +12,12 @@ pub fn run_file(file: &Path, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Analyzing log: {}", file.display());
+        render_eprintln!("Analyzing log: {}", file.display());
     }
 
     let content = fs::read_to_string(file)?;
     let result = analyze_logs(&content);
-    println!("{}", result);
+    render_println!("{}", result);
     timer.track(
         &format!("cat {}", file.display()),
         "rtk log",
@@ -38,7 +39,7 @@ pub fn run_stdin(_verbose: u8) -> Result<()> {
     }
 
     let result = analyze_logs(&content);
-    println!("{}", result);
+    render_println!("{}", result);
 
     timer.track("log (stdin)", "rtk log (stdin)", &content, &result);
 
@@ -52,6 +53,7 @@ pub fn run_stdin_str(content: &str) -> String {
 
 fn analyze_logs(content: &str) -> String {
     let mut result = Vec::new();
+    let width = context::width(100);
     let mut error_counts: HashMap<String, usize> = HashMap::new();
     let mut warn_counts: HashMap<String, usize> = HashMap::new();
     let mut info_counts: HashMap<String, usize> = HashMap::new();
@@ -134,8 +136,8 @@ fn analyze_logs(content: &str) -> String {
                 .map(|s| s.as_str())
                 .unwrap_or(normalized);
 
-            let truncated = if original.len() > 100 {
-                let t: String = original.chars().take(97).collect();
+            let truncated = if original.len() > width {
+                let t: String = original.chars().take(width.saturating_sub(3)).collect();
                 format!("{}...", t)
             } else {
                 original.to_string()
@@ -174,8 +176,8 @@ fn analyze_logs(content: &str) -> String {
                 .map(|s| s.as_str())
                 .unwrap_or(normalized);
 
-            let truncated = if original.len() > 100 {
-                let t: String = original.chars().take(97).collect();
+            let truncated = if original.len() > width {
+                let t: String = original.chars().take(width.saturating_sub(3)).collect();
                 format!("{}...", t)
             } else {
                 original.to_string()
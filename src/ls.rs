@@ -1,35 +1,9 @@
+use crate::context;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
 
-/// Noise directories commonly excluded from LLM context
-const NOISE_DIRS: &[&str] = &[
-    "node_modules",
-    ".git",
-    "target",
-    "__pycache__",
-    ".next",
-    "dist",
-    "build",
-    ".cache",
-    ".turbo",
-    ".vercel",
-    ".pytest_cache",
-    ".mypy_cache",
-    ".tox",
-    ".venv",
-    "venv",
-    "coverage",
-    ".nyc_output",
-    ".DS_Store",
-    "Thumbs.db",
-    ".idea",
-    ".vscode",
-    ".vs",
-    "*.egg-info",
-    ".eggs",
-];
-
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -80,19 +54,19 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    let output = cmd.output().context("Failed to run ls")?;
+    let output = cmd.output_timed().context("Failed to run ls")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprint!("{}", stderr);
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let filtered = compact_ls(&raw, show_all);
 
     if verbose > 0 {
-        eprintln!(
+        render_eprintln!(
             "Chars: {} → {} ({}% reduction)",
             raw.len(),
             filtered.len(),
@@ -109,7 +83,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     } else {
         paths.join(" ")
     };
-    print!("{}", filtered);
+    render_print!("{}", filtered);
     timer.track(
         &format!("ls -la {}", target_display),
         "rtk ls",
@@ -131,6 +105,16 @@ fn human_size(bytes: u64) -> String {
     }
 }
 
+/// Entry matches the configured ignore lists
+fn is_ignored(name: &str, is_dir: bool) -> bool {
+    let ctx = context::get();
+    if is_dir {
+        ctx.is_ignored_dir(name)
+    } else {
+        ctx.is_ignored_file(name)
+    }
+}
+
 /// Parse ls -la output into compact format:
 ///   name/  (dirs)
 ///   name  size  (files)
@@ -160,13 +144,13 @@ fn compact_ls(raw: &str, show_all: bool) -> String {
             continue;
         }
 
-        // Filter noise dirs unless -a
-        if !show_all && NOISE_DIRS.iter().any(|noise| name == *noise) {
+        let is_dir = parts[0].starts_with('d');
+
+        // Filter filters.ignore_dirs / ignore_files unless -a
+        if !show_all && is_ignored(&name, is_dir) {
             continue;
         }
 
-        let is_dir = parts[0].starts_with('d');
-
         if is_dir {
             dirs.push(name);
         } else if parts[0].starts_with('-') || parts[0].starts_with('l') {
@@ -257,8 +241,10 @@ mod tests {
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 .git\n\
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 target\n\
                      drwxr-xr-x  2 user  staff  64 Jan  1 12:00 src\n\
+                     -rw-r--r--  1 user  staff  100 Jan  1 12:00 .DS_Store\n\
                      -rw-r--r--  1 user  staff  100 Jan  1 12:00 main.rs\n";
         let output = compact_ls(input, false);
+        assert!(!output.contains(".DS_Store"));
         assert!(!output.contains("node_modules"));
         assert!(!output.contains(".git"));
         assert!(!output.contains("target"));
@@ -1,9 +1,47 @@
+// rtk's own console output: redacted and rendered for the display context
+// (`display.emoji`, `display.colors`); stdout also counts against the
+// `--max-tokens` budget. Content that is not rtk's own (raw output, patches,
+// file contents, matched lines) goes through `context::raw` instead, and
+// `std::println!` is for byte-exact, unbudgeted text.
+macro_rules! render_println {
+    () => { $crate::budget::print("\n") };
+    ($($arg:tt)*) => {
+        $crate::budget::print(&$crate::context::render(&::std::format!(
+            "{}\n",
+            ::std::format_args!($($arg)*)
+        )))
+    };
+}
+
+macro_rules! render_eprintln {
+    () => { ::std::eprintln!() };
+    ($($arg:tt)*) => {
+        ::std::eprintln!("{}", $crate::context::render(&::std::format!($($arg)*)))
+    };
+}
+
+macro_rules! render_print {
+    ($($arg:tt)*) => {
+        $crate::budget::print(&$crate::context::render(&::std::format!($($arg)*)))
+    };
+}
+
+macro_rules! render_eprint {
+    ($($arg:tt)*) => {
+        ::std::eprint!("{}", $crate::context::render(&::std::format!($($arg)*)))
+    };
+}
+
+mod budget;
 mod cargo_cmd;
 mod cc_economics;
 mod ccusage;
 mod config;
 mod container;
+mod context;
 mod curl_cmd;
+mod custom_filter;
+mod delta;
 mod deps;
 mod diff_cmd;
 mod discover;
@@ -13,6 +51,8 @@ mod filter;
 mod find_cmd;
 mod format_cmd;
 mod gain;
+mod gain_html;
+mod gain_metrics;
 mod gh_cmd;
 mod git;
 mod go_cmd;
@@ -25,6 +65,7 @@ mod lint_cmd;
 mod local_llm;
 mod log_cmd;
 mod ls;
+mod mcp;
 mod next_cmd;
 mod npm_cmd;
 mod parser;
@@ -35,9 +76,15 @@ mod prettier_cmd;
 mod prisma_cmd;
 mod pytest_cmd;
 mod read;
+mod read_cache;
+mod redact;
+mod rewrite_cmd;
 mod ruff_cmd;
 mod runner;
+mod stream;
 mod summary;
+mod tee;
+mod timeout;
 mod tracking;
 mod tree;
 mod tsc_cmd;
@@ -46,9 +93,10 @@ mod vitest_cmd;
 mod wget_cmd;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use timeout::TimedCommand;
 
 #[derive(Parser)]
 #[command(
@@ -72,6 +120,26 @@ struct Cli {
     /// Set SKIP_ENV_VALIDATION=1 for child processes (Next.js, tsc, lint, prisma)
     #[arg(long = "skip-env", global = true)]
     skip_env: bool,
+
+    /// Fit output into N tokens, dropping context before summaries and errors (0 = no limit)
+    #[arg(long, global = true)]
+    max_tokens: Option<usize>,
+
+    /// Output format: `json` prints the parsed result (cargo, go, pytest, vitest,
+    /// playwright, tsc, lint, ruff, golangci-lint, pnpm, pip, git); gain,
+    /// cc-economics, discover and learn also take their export formats
+    #[arg(long, global = true, value_name = "FORMAT")]
+    format: Option<String>,
+
+    /// Show only what changed since the previous run of the same test/lint command
+    /// in this directory: new, fixed and still-failing items
+    #[arg(long, global = true)]
+    delta: bool,
+
+    /// Stop the command after this long (30s, 5m, 1h; 0 = no limit), keeping the
+    /// filtered partial output
+    #[arg(long, global = true, value_parser = timeout::parse_duration)]
+    timeout: Option<std::time::Duration>,
 }
 
 #[derive(Subcommand)]
@@ -100,9 +168,15 @@ enum Commands {
         /// Max lines
         #[arg(short, long)]
         max_lines: Option<usize>,
+        /// Only show the last N lines
+        #[arg(long)]
+        tail_lines: Option<usize>,
         /// Show line numbers
         #[arg(short = 'n', long)]
         line_numbers: bool,
+        /// Print the whole file even if it is unchanged since the last read in this session
+        #[arg(long)]
+        force: bool,
     },
 
     /// Generate 2-line technical summary (heuristic-based)
@@ -233,9 +307,9 @@ enum Commands {
         /// Path to search in
         #[arg(default_value = ".")]
         path: String,
-        /// Max line length
-        #[arg(short = 'l', long, default_value = "80")]
-        max_len: usize,
+        /// Max line length [default: 80, capped by display.max_width]
+        #[arg(short = 'l', long)]
+        max_len: Option<usize>,
         /// Max results to show
         #[arg(short, long, default_value = "50")]
         max: usize,
@@ -245,6 +319,12 @@ enum Commands {
         /// Filter by file type (e.g., ts, py, rust)
         #[arg(short = 't', long)]
         file_type: Option<String>,
+        /// Accepted for grep compatibility: rtk grep always recurses
+        #[arg(short = 'r', short_alias = 'R', long, hide = true)]
+        recursive: bool,
+        /// Accepted for grep compatibility: line numbers are always shown
+        #[arg(short = 'n', long, hide = true)]
+        line_number: bool,
         /// Extra ripgrep arguments (e.g., -i, -A 3, -w, --glob)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         extra_args: Vec<String>,
@@ -279,6 +359,16 @@ enum Commands {
         /// Remove all RTK artifacts (hook, RTK.md, CLAUDE.md reference, settings.json entry)
         #[arg(long)]
         uninstall: bool,
+
+        /// Check the installation: rtk on PATH, hook file and version, jq, settings.json
+        /// entries, tracking database, and a sample command through the hook
+        #[arg(long)]
+        doctor: bool,
+
+        /// Agent to set up: claude (rewrite hook), codex, gemini, cursor (instructions +
+        /// rtk MCP server), aider (CONVENTIONS.md)
+        #[arg(long, value_enum, default_value = "claude")]
+        agent: init::Agent,
     },
 
     /// Download with compact output (strips progress bars)
@@ -319,9 +409,47 @@ enum Commands {
         /// Show all time breakdowns (daily + weekly + monthly)
         #[arg(short, long)]
         all: bool,
-        /// Output format: text, json, csv
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json, csv, html, openmetrics (same as --format)
+        #[arg(short = 'f', value_name = "FORMAT")]
+        short_format: Option<String>,
+        /// Write to this file instead of stdout, replacing it atomically (--format html, openmetrics)
+        #[arg(short, long, visible_alias = "write", value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Only count commands run in this project (git root of PATH; default: current)
+        #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = ".")]
+        project: Option<String>,
+        /// Only count commands from this session (RTK_SESSION_ID)
+        #[arg(long)]
+        session: Option<String>,
+        /// Show savings per project
+        #[arg(long)]
+        by_project: bool,
+        /// Only count commands run since: YYYY-MM-DD, an age (12h, 7d, 2w) or RFC3339
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
+        /// Only count commands run before: YYYY-MM-DD (inclusive), an age or RFC3339
+        #[arg(long, value_name = "WHEN")]
+        until: Option<String>,
+        /// Only count matching rtk commands ("git*", "cargo test") and show their
+        /// savings distribution and worst runs
+        #[arg(long, value_name = "GLOB")]
+        command: Option<String>,
+        /// Number of commands (and projects) listed [default: 10]
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+        /// Show the tracking database path, schema version, row count and size
+        #[arg(long)]
+        db_info: bool,
+        /// List commands whose parsers degraded (Degraded/Passthrough) more often,
+        /// with the day it started
+        #[arg(long)]
+        health: bool,
+        /// Read (or, for import, write) this database instead of the tracking
+        /// database, e.g. a team database built with `rtk gain import`
+        #[arg(long, global = true, value_name = "FILE")]
+        db: Option<PathBuf>,
+        #[command(subcommand)]
+        action: Option<GainCommands>,
     },
 
     /// Claude Code economics: spending (ccusage) vs savings (rtk) analysis
@@ -338,11 +466,33 @@ enum Commands {
         /// Show all time breakdowns (daily + weekly + monthly)
         #[arg(short, long)]
         all: bool,
-        /// Output format: text, json, csv
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json, csv (same as --format)
+        #[arg(short = 'f', value_name = "FORMAT")]
+        short_format: Option<String>,
+        /// Read this database instead of the tracking database (e.g. a team
+        /// database built with `rtk gain import`)
+        #[arg(long, value_name = "FILE")]
+        db: Option<PathBuf>,
     },
 
+    /// Show the full raw output saved by a filtered command
+    Show {
+        /// Output id printed in the command footer
+        id: String,
+        /// Only print lines matching this regex (with line numbers)
+        #[arg(short, long)]
+        grep: Option<String>,
+        /// Line range: A-B, A-, -B or A (1-based)
+        #[arg(short, long)]
+        lines: Option<String>,
+    },
+
+    /// Rewrite a hook's Bash command to its rtk equivalent (PreToolUse JSON on stdin)
+    Rewrite,
+
+    /// Serve rtk filters as Model Context Protocol tools over stdio
+    Mcp,
+
     /// Show or create configuration file
     Config {
         /// Create default config file
@@ -445,9 +595,9 @@ enum Commands {
         /// Limit to sessions from last N days
         #[arg(short, long, default_value = "30")]
         since: u64,
-        /// Output format: text, json
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json (same as --format)
+        #[arg(short = 'f', value_name = "FORMAT")]
+        short_format: Option<String>,
     },
 
     /// Learn CLI corrections from Claude Code error history
@@ -461,9 +611,9 @@ enum Commands {
         /// Limit to sessions from last N days
         #[arg(short, long, default_value = "30")]
         since: u64,
-        /// Output format: text, json
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format: text, json (same as --format)
+        #[arg(short = 'f', value_name = "FORMAT")]
+        short_format: Option<String>,
         /// Generate .claude/rules/cli-corrections.md file
         #[arg(short, long)]
         write_rules: bool,
@@ -482,6 +632,13 @@ enum Commands {
         args: Vec<OsString>,
     },
 
+    /// Run a command through its [[filters.custom]] entry from config.toml
+    Run {
+        /// Command and arguments to execute
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// Ruff linter/formatter with compact output
     Ruff {
         /// Ruff arguments (e.g., check, format --check)
@@ -801,9 +958,79 @@ enum GoCommands {
     Other(Vec<OsString>),
 }
 
+#[derive(Subcommand)]
+enum GainCommands {
+    /// Export tracked runs as a JSON lines bundle labelled with host and user
+    Export {
+        /// Bundle file to write
+        #[arg(long, value_name = "FILE")]
+        bundle: PathBuf,
+        /// Host label [default: this machine's hostname]
+        #[arg(long)]
+        host: Option<String>,
+        /// User label [default: $USER]
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// Merge bundles into a team database (default: team.db next to the
+    /// tracking database), skipping runs already imported
+    Import {
+        /// Bundle files written by `rtk gain export`
+        #[arg(required = true)]
+        bundles: Vec<PathBuf>,
+    },
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let family = matches.subcommand_name().unwrap_or_default().to_string();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let format = output_format(&cli).unwrap_or_else(|e| e.exit());
+    context::init(context::Overrides {
+        max_tokens: cli.max_tokens,
+        format,
+        delta: cli.delta,
+        timeout: cli.timeout,
+        family,
+    });
+
+    let result = run(cli);
+    budget::finish();
+    result
+}
+
+/// `--format` for commands printing a parsed result: text or json. The
+/// report commands take their own formats and keep text output here.
+fn output_format(cli: &Cli) -> Result<parser::OutputFormat, clap::Error> {
+    use clap::ValueEnum;
+
+    let reports = matches!(
+        cli.command,
+        Commands::Gain { .. }
+            | Commands::CcEconomics { .. }
+            | Commands::Discover { .. }
+            | Commands::Learn { .. }
+    );
+    match cli.format.as_deref() {
+        None => Ok(parser::OutputFormat::Text),
+        Some(_) if reports => Ok(parser::OutputFormat::Text),
+        Some(value) => parser::OutputFormat::from_str(value, true).map_err(|_| {
+            Cli::command().error(
+                clap::error::ErrorKind::InvalidValue,
+                format!("invalid value '{}' for '--format' (text, json)", value),
+            )
+        }),
+    }
+}
 
+/// Format for gain, cc-economics, discover and learn: `-f` or `--format`
+fn report_format(short: Option<String>, global: &Option<String>) -> String {
+    short
+        .or_else(|| global.clone())
+        .unwrap_or_else(|| "text".to_string())
+}
+
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Ls { args } => {
             ls::run(&args, cli.verbose)?;
@@ -817,12 +1044,22 @@ fn main() -> Result<()> {
             file,
             level,
             max_lines,
+            tail_lines,
             line_numbers,
+            force,
         } => {
             if file == Path::new("-") {
-                read::run_stdin(level, max_lines, line_numbers, cli.verbose)?;
+                read::run_stdin(level, max_lines, tail_lines, line_numbers, cli.verbose)?;
             } else {
-                read::run(&file, level, max_lines, line_numbers, cli.verbose)?;
+                read::run(
+                    &file,
+                    level,
+                    max_lines,
+                    tail_lines,
+                    line_numbers,
+                    force,
+                    cli.verbose,
+                )?;
             }
         }
 
@@ -1023,11 +1260,12 @@ fn main() -> Result<()> {
             context_only,
             file_type,
             extra_args,
+            ..
         } => {
             grep_cmd::run(
                 &pattern,
                 &path,
-                max_len,
+                max_len.unwrap_or_else(|| context::width(80)),
                 max,
                 context_only,
                 file_type.as_deref(),
@@ -1044,11 +1282,15 @@ fn main() -> Result<()> {
             auto_patch,
             no_patch,
             uninstall,
+            doctor,
+            agent,
         } => {
             if show {
                 init::show_config()?;
+            } else if doctor {
+                init::doctor(cli.verbose)?;
             } else if uninstall {
-                init::uninstall(global, cli.verbose)?;
+                init::uninstall(agent, global, cli.verbose)?;
             } else {
                 let patch_mode = if auto_patch {
                     init::PatchMode::Auto
@@ -1057,7 +1299,7 @@ fn main() -> Result<()> {
                 } else {
                     init::PatchMode::Ask
                 };
-                init::run(global, claude_md, hook_only, patch_mode, cli.verbose)?;
+                init::run(agent, global, claude_md, hook_only, patch_mode, cli.verbose)?;
             }
         }
 
@@ -1078,20 +1320,57 @@ fn main() -> Result<()> {
             weekly,
             monthly,
             all,
-            format,
+            short_format,
+            output,
+            project,
+            session,
+            by_project,
+            since,
+            until,
+            command,
+            top,
+            db_info,
+            health,
+            db,
+            action,
         } => {
-            gain::run(
+            let options = gain::GainOptions {
                 graph,
                 history,
                 quota,
-                &tier,
+                tier,
                 daily,
                 weekly,
                 monthly,
                 all,
-                &format,
-                cli.verbose,
-            )?;
+                format: report_format(short_format, &cli.format),
+                filters: gain::Filters {
+                    project,
+                    session,
+                    since,
+                    until,
+                    command,
+                },
+                by_project,
+                top,
+                output,
+                db,
+            };
+            let db = options.db.as_deref();
+            if let Some(action) = action {
+                match action {
+                    GainCommands::Export { bundle, host, user } => {
+                        gain::run_export(&options.filters, db, &bundle, host, user)?
+                    }
+                    GainCommands::Import { bundles } => gain::run_import(&bundles, db)?,
+                }
+            } else if db_info {
+                gain::run_db_info(&options.format, db)?;
+            } else if health {
+                gain::run_health(&options.format, &options.filters, db)?;
+            } else {
+                gain::run(&options, cli.verbose)?;
+            }
         }
 
         Commands::CcEconomics {
@@ -1099,15 +1378,36 @@ fn main() -> Result<()> {
             weekly,
             monthly,
             all,
-            format,
+            short_format,
+            db,
         } => {
-            cc_economics::run(daily, weekly, monthly, all, &format, cli.verbose)?;
+            cc_economics::run(
+                daily,
+                weekly,
+                monthly,
+                all,
+                &report_format(short_format, &cli.format),
+                db.as_deref(),
+                cli.verbose,
+            )?;
+        }
+
+        Commands::Show { id, grep, lines } => {
+            tee::run_show(&id, grep.as_deref(), lines.as_deref(), cli.verbose)?;
+        }
+
+        Commands::Rewrite => {
+            rewrite_cmd::run(cli.verbose)?;
+        }
+
+        Commands::Mcp => {
+            mcp::run(cli.verbose)?;
         }
 
         Commands::Config { create } => {
             if create {
                 let path = config::Config::create_default()?;
-                println!("Created: {}", path.display());
+                render_println!("Created: {}", path.display());
             } else {
                 config::show_config()?;
             }
@@ -1215,8 +1515,9 @@ fn main() -> Result<()> {
             limit,
             all,
             since,
-            format,
+            short_format,
         } => {
+            let format = report_format(short_format, &cli.format);
             discover::run(project.as_deref(), all, since, limit, &format, cli.verbose)?;
         }
 
@@ -1224,7 +1525,7 @@ fn main() -> Result<()> {
             project,
             all,
             since,
-            format,
+            short_format,
             write_rules,
             min_confidence,
             min_occurrences,
@@ -1233,7 +1534,7 @@ fn main() -> Result<()> {
                 project,
                 all,
                 since,
-                format,
+                report_format(short_format, &cli.format),
                 write_rules,
                 min_confidence,
                 min_occurrences,
@@ -1279,14 +1580,15 @@ fn main() -> Result<()> {
                                 for arg in &args {
                                     cmd.arg(arg);
                                 }
-                                let status = cmd.status().context("Failed to run npx prisma")?;
+                                let status =
+                                    cmd.status_timed().context("Failed to run npx prisma")?;
                                 let args_str = args.join(" ");
                                 timer.track_passthrough(
                                     &format!("npx {}", args_str),
                                     &format!("rtk npx {} (passthrough)", args_str),
                                 );
                                 if !status.success() {
-                                    std::process::exit(status.code().unwrap_or(1));
+                                    utils::exit(status.code().unwrap_or(1));
                                 }
                             }
                         }
@@ -1294,11 +1596,11 @@ fn main() -> Result<()> {
                         let timer = tracking::TimedExecution::start();
                         let status = std::process::Command::new("npx")
                             .arg("prisma")
-                            .status()
+                            .status_timed()
                             .context("Failed to run npx prisma")?;
                         timer.track_passthrough("npx prisma", "rtk npx prisma (passthrough)");
                         if !status.success() {
-                            std::process::exit(status.code().unwrap_or(1));
+                            utils::exit(status.code().unwrap_or(1));
                         }
                     }
                 }
@@ -1367,12 +1669,12 @@ fn main() -> Result<()> {
                 .collect();
 
             if cli.verbose > 0 {
-                eprintln!("Proxy mode: {} {}", cmd_name, cmd_args.join(" "));
+                render_eprintln!("Proxy mode: {} {}", cmd_name, cmd_args.join(" "));
             }
 
             let output = Command::new(cmd_name.as_ref())
                 .args(&cmd_args)
-                .output()
+                .output_timed()
                 .context(format!("Failed to execute command: {}", cmd_name))?;
 
             let stdout = String::from_utf8_lossy(&output.stdout);
@@ -1380,8 +1682,8 @@ fn main() -> Result<()> {
             let full_output = format!("{}{}", stdout, stderr);
 
             // Print output
-            print!("{}", stdout);
-            eprint!("{}", stderr);
+            render_print!("{}", stdout);
+            render_eprint!("{}", stderr);
 
             // Track usage (input = output since no filtering)
             timer.track(
@@ -1393,10 +1695,50 @@ fn main() -> Result<()> {
 
             // Exit with same code as child process
             if !output.status.success() {
-                std::process::exit(output.status.code().unwrap_or(1));
+                utils::exit(output.status.code().unwrap_or(1));
             }
         }
+
+        Commands::Run { args } => {
+            custom_filter::run(&args, cli.verbose)?;
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("rtk").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_format_after_subcommand() {
+        let cli = parse(&["git", "status", "--format", "json"]);
+        assert_eq!(output_format(&cli).unwrap(), parser::OutputFormat::Json);
+        let cli = parse(&["--format", "json", "pytest", "-x"]);
+        assert_eq!(output_format(&cli).unwrap(), parser::OutputFormat::Json);
+        assert!(output_format(&parse(&["pytest", "--format", "csv"])).is_err());
+
+        // Report commands take their own formats, with -f kept as a short form
+        let cli = parse(&["gain", "--format", "csv"]);
+        assert_eq!(output_format(&cli).unwrap(), parser::OutputFormat::Text);
+        assert_eq!(cli.format.as_deref(), Some("csv"));
+        let Commands::Gain { short_format, .. } = parse(&["gain", "-f", "json"]).command else {
+            panic!("not gain");
+        };
+        assert_eq!(report_format(short_format, &None), "json");
+    }
+
+    #[test]
+    fn test_timeout_after_subcommand() {
+        let limit = Some(std::time::Duration::from_secs(30));
+        assert_eq!(parse(&["pytest", "--timeout", "30s"]).timeout, limit);
+        assert_eq!(parse(&["--timeout", "30s", "pytest"]).timeout, limit);
+        assert_eq!(parse(&["cargo", "test", "--timeout", "30s"]).timeout, limit);
+        assert!(Cli::try_parse_from(["rtk", "pytest", "--timeout", "soon"]).is_err());
+    }
+}
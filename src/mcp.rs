@@ -0,0 +1,722 @@
+//! `rtk mcp`: Model Context Protocol server over stdio, for agents that cannot
+//! install the Bash hook.
+//!
+//! Speaks newline-delimited JSON-RPC 2.0 (`initialize`, `ping`, `tools/list`,
+//! `tools/call`). Each tool runs the matching rtk command as a child process of
+//! the same binary, so a failing command cannot end the server and its output
+//! still goes through redaction, tracking and the tee store. Tools backed by a
+//! [`crate::parser`] type also return the `--format json` report as
+//! `structuredContent`.
+
+use crate::delta::Diffable;
+use crate::discover::registry::{classify_runner, rewrite_command, Runner};
+use crate::parser::{LintResult, TestResult};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Protocol revision announced when the client does not ask for one
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Failures listed in a summarized test/lint report before "+N more"
+const MAX_ITEMS: usize = 30;
+
+/// Where a tool's structured content comes from
+enum Report {
+    /// Text only
+    None,
+    /// A second, untracked run with these arguments (cheap, read-only commands
+    /// whose json report lacks the text), so a call is tracked once
+    Rerun(Vec<String>),
+    /// A single `--format json` run, the text summarized from its report: tests
+    /// and linters are too slow to run twice
+    Summarize(fn(&Value) -> Option<String>),
+}
+
+/// An rtk command line for one tool call
+struct Invocation {
+    args: Vec<String>,
+    report: Report,
+}
+
+pub fn run(verbose: u8) -> Result<()> {
+    let program = std::env::current_exe().context("Failed to locate the rtk binary")?;
+    let server = Server { program, verbose };
+    server.serve(std::io::stdin().lock(), std::io::stdout().lock())
+}
+
+struct Server {
+    /// Binary the tools run (rtk itself)
+    program: PathBuf,
+    verbose: u8,
+}
+
+impl Server {
+    fn serve(&self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line.context("Failed to read from stdin")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle(&line) {
+                writeln!(output, "{}", response).context("Failed to write to stdout")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Response to one JSON-RPC message, `None` for notifications
+    fn handle(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                return Some(error(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Parse error: {}", e),
+                ))
+            }
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to server requests are never expected; ignore them
+            return match id {
+                Some(id) if message.get("result").is_none() && message.get("error").is_none() => {
+                    Some(error(id, INVALID_REQUEST, "Invalid request".to_string()))
+                }
+                _ => None,
+            };
+        };
+        let id = id?;
+        if self.verbose > 0 {
+            render_eprintln!("rtk mcp: {}", method);
+        }
+
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error(id, code, message),
+        })
+    }
+
+    fn call(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let empty = json!({});
+        let arguments = params.get("arguments").unwrap_or(&empty);
+        let invocation = invocation(name, arguments).map_err(|e| (INVALID_PARAMS, e))?;
+        Ok(self.execute(&invocation))
+    }
+
+    /// Run the invocation and build the `tools/call` result
+    fn execute(&self, invocation: &Invocation) -> Value {
+        let mut args = invocation.args.clone();
+        if matches!(invocation.report, Report::Summarize(_)) {
+            args.splice(0..0, ["--format".to_string(), "json".to_string()]);
+        }
+        let (code, stdout, stderr) = match self.spawn(&args, true) {
+            Ok(output) => output,
+            Err(e) => {
+                return json!({
+                    "content": [{ "type": "text", "text": format!("{:#}", e) }],
+                    "isError": true
+                })
+            }
+        };
+
+        let mut structured = None;
+        let mut text = stdout.trim_end().to_string();
+        match &invocation.report {
+            Report::None => {}
+            Report::Rerun(json_args) => {
+                structured = self
+                    .spawn(json_args, false)
+                    .ok()
+                    .and_then(|(_, json, _)| serde_json::from_str::<Value>(&json).ok());
+            }
+            Report::Summarize(summarize) => {
+                // Commands without a parser print text even in json mode
+                if let Ok(report) = serde_json::from_str::<Value>(&stdout) {
+                    if let Some(summary) = summarize(&report) {
+                        text = summary;
+                    }
+                    structured = Some(report);
+                }
+                if code != 0 {
+                    text.push_str(&format!("\n[exit {}]", code));
+                }
+            }
+        }
+        if !stderr.trim().is_empty() {
+            text = format!("{}\n{}", text, stderr.trim_end())
+                .trim_start()
+                .to_string();
+        }
+
+        let mut result = json!({
+            "content": [{ "type": "text", "text": text }],
+            // Failing tests or an empty grep still print a result; only a
+            // command that printed nothing (bad path, not a repo) is an error
+            "isError": code != 0 && stdout.trim().is_empty(),
+        });
+        if let Some(report) = structured.filter(Value::is_object) {
+            result["structuredContent"] = report;
+        }
+        result
+    }
+
+    /// Run rtk with `args`, stdin closed so the child cannot read the protocol stream
+    fn spawn(&self, args: &[String], tracked: bool) -> Result<(i32, String, String)> {
+        if self.verbose > 1 {
+            render_eprintln!("rtk mcp: running rtk {}", args.join(" "));
+        }
+        let mut cmd = Command::new(&self.program);
+        if !tracked {
+            cmd.env("RTK_TRACKING_ENABLED", "false");
+        }
+        let output = cmd
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to run rtk {}", args.join(" ")))?;
+        Ok((
+            output.status.code().unwrap_or(1),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ))
+    }
+}
+
+fn error(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "rtk", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Prefer these tools over raw shell commands: they return the same \
+                         information in far fewer tokens."
+    })
+}
+
+/// Tool definitions for `tools/list`
+fn tools() -> Value {
+    let command = json!({
+        "type": "array",
+        "items": { "type": "string" },
+        "description": "Command and arguments, one per item"
+    });
+    let timeout = json!({
+        "type": "string",
+        "description": "Stop the command after this long (30s, 5m, 1h)"
+    });
+    json!([
+        {
+            "name": "read_file",
+            "description": "Read a file with comments and blank lines filtered out. A reread of an unchanged file returns a one-line notice, a changed one a diff.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "level": { "type": "string", "enum": ["none", "minimal", "aggressive"] },
+                    "max_lines": { "type": "integer", "minimum": 1 },
+                    "tail_lines": { "type": "integer", "minimum": 1 },
+                    "force": { "type": "boolean", "description": "Return the whole file even if unchanged" }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "grep",
+            "description": "Search file contents with ripgrep, grouped by file.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "path": { "type": "string", "default": "." },
+                    "file_type": { "type": "string", "description": "e.g. rust, ts, py" },
+                    "max": { "type": "integer", "minimum": 1, "default": 50 }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "git_status",
+            "description": "Compact git status of the working directory.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "git_diff",
+            "description": "Condensed git diff.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "args": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "git diff arguments, e.g. [\"--cached\"] or paths"
+                    }
+                }
+            }
+        },
+        {
+            "name": "run_tests",
+            "description": "Run a test suite and return only the failures. Accepts cargo test, pytest, go test, vitest run and playwright test; other commands are rejected.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "command": command, "timeout": timeout },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "lint",
+            "description": "Run a linter or type checker and return issues grouped by rule. Accepts cargo clippy, eslint, biome, tsc, ruff check, go vet and golangci-lint run; other commands are rejected.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "command": command, "timeout": timeout },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "gain_summary",
+            "description": "Token savings recorded by rtk.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+/// Map a tool call to rtk arguments
+fn invocation(name: &str, arguments: &Value) -> Result<Invocation, String> {
+    let text = |args: Vec<String>| Invocation {
+        args,
+        report: Report::None,
+    };
+    let rerun = |args: Vec<String>, json_args: Vec<String>| Invocation {
+        args,
+        report: Report::Rerun(json_args),
+    };
+
+    match name {
+        "read_file" => {
+            let mut args = vec!["read".to_string()];
+            if let Some(level) = string_arg(arguments, "level")? {
+                args.extend(["--level".to_string(), level]);
+            }
+            if let Some(lines) = integer_arg(arguments, "max_lines")? {
+                args.extend(["--max-lines".to_string(), lines.to_string()]);
+            }
+            if let Some(lines) = integer_arg(arguments, "tail_lines")? {
+                args.extend(["--tail-lines".to_string(), lines.to_string()]);
+            }
+            if bool_arg(arguments, "force")? {
+                args.push("--force".to_string());
+            }
+            args.extend(["--".to_string(), required(arguments, "path")?]);
+            Ok(text(args))
+        }
+        "grep" => {
+            let mut args = vec!["grep".to_string()];
+            if let Some(max) = integer_arg(arguments, "max")? {
+                args.extend(["--max".to_string(), max.to_string()]);
+            }
+            if let Some(file_type) = string_arg(arguments, "file_type")? {
+                args.extend(["--file-type".to_string(), file_type]);
+            }
+            args.extend([
+                "--".to_string(),
+                required(arguments, "pattern")?,
+                string_arg(arguments, "path")?.unwrap_or_else(|| ".".to_string()),
+            ]);
+            Ok(text(args))
+        }
+        "git_status" => Ok(rerun(
+            strings(&["git", "status"]),
+            strings(&["--format", "json", "git", "status"]),
+        )),
+        "git_diff" => {
+            let extra = string_list(arguments, "args")?;
+            let mut args = strings(&["git", "diff"]);
+            args.extend(extra.iter().cloned());
+            let mut json_args = strings(&["--format", "json", "git", "diff"]);
+            json_args.extend(extra);
+            Ok(rerun(args, json_args))
+        }
+        "run_tests" | "lint" => {
+            let command = string_list(arguments, "command")?;
+            if command.is_empty() {
+                return Err("command must not be empty".to_string());
+            }
+            let mut args = Vec::new();
+            if let Some(timeout) = string_arg(arguments, "timeout")? {
+                args.extend(["--timeout".to_string(), timeout]);
+            }
+            let (runner, summarize): (_, fn(&Value) -> Option<String>) = if name == "lint" {
+                (Runner::Lint, summarize::<LintResult>)
+            } else {
+                (Runner::Tests, summarize::<TestResult>)
+            };
+            let rtk = (classify_runner(&command.join(" ")) == Some(runner))
+                .then(|| rtk_args(&command))
+                .flatten()
+                .ok_or_else(|| {
+                    format!(
+                        "'{}' is not a {} rtk supports",
+                        command.join(" "),
+                        if name == "lint" {
+                            "linter"
+                        } else {
+                            "test runner"
+                        }
+                    )
+                })?;
+            args.extend(rtk);
+            Ok(Invocation {
+                args,
+                report: Report::Summarize(summarize),
+            })
+        }
+        "gain_summary" => Ok(rerun(
+            strings(&["gain"]),
+            strings(&["gain", "--format", "json"]),
+        )),
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+/// rtk arguments for a command given as argv: its rewrite from the discover
+/// registry (`cargo test` → `cargo test`, `npx vitest run` → `vitest run`), or
+/// `None` when rtk has no filter for it
+fn rtk_args(command: &[String]) -> Option<Vec<String>> {
+    let rewritten = rewrite_command(&command.join(" "))?;
+    let rest = rewritten.strip_prefix("rtk ")?;
+    // The registry keeps the tail of the command verbatim: map the rewritten
+    // head back onto the original items so quoting is never needed
+    for start in 0..=command.len() {
+        let tail = command[start..].join(" ");
+        let Some(head) = rest.strip_suffix(tail.as_str()) else {
+            continue;
+        };
+        if head.is_empty() || head.ends_with(' ') {
+            let mut args: Vec<String> = head.split_whitespace().map(String::from).collect();
+            args.extend(command[start..].iter().cloned());
+            return Some(args);
+        }
+    }
+    None
+}
+
+/// Compact text for a test/lint `--format json` report
+fn summarize<T: Diffable>(report: &Value) -> Option<String> {
+    if let Some(raw) = report.get("raw").and_then(Value::as_str) {
+        return Some(raw.to_string());
+    }
+    let tool = report.get("tool")?.as_str()?;
+    let data: T = serde_json::from_value(report.get("data")?.clone()).ok()?;
+    let items = data.items();
+    let mut out = format!("{}: {}", tool, data.totals());
+    for (_, name, detail) in items.iter().take(MAX_ITEMS) {
+        out.push_str(&format!("\n  ✗ {}", name));
+        if !detail.is_empty() {
+            out.push_str(&format!("\n    {}", detail));
+        }
+    }
+    if items.len() > MAX_ITEMS {
+        out.push_str(&format!("\n  ... +{} more", items.len() - MAX_ITEMS));
+    }
+    if report.get("timed_out").and_then(Value::as_bool) == Some(true) {
+        out.push_str(&format!("\n{}", crate::timeout::label()));
+    }
+    Some(out)
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+fn required(arguments: &Value, key: &str) -> Result<String, String> {
+    string_arg(arguments, key)?.ok_or_else(|| format!("Missing argument: {}", key))
+}
+
+fn string_arg(arguments: &Value, key: &str) -> Result<Option<String>, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(format!("{} must be a string", key)),
+    }
+}
+
+fn integer_arg(arguments: &Value, key: &str) -> Result<Option<u64>, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("{} must be a non-negative integer", key)),
+    }
+}
+
+fn bool_arg(arguments: &Value, key: &str) -> Result<bool, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(false),
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| format!("{} must be a boolean", key)),
+    }
+}
+
+fn string_list(arguments: &Value, key: &str) -> Result<Vec<String>, String> {
+    match arguments.get(key) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(String::from)
+                    .ok_or_else(|| format!("{} must be a list of strings", key))
+            })
+            .collect(),
+        Some(_) => Err(format!("{} must be a list of strings", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed a scripted client session through the server and collect the replies
+    fn session(program: &str, requests: &[Value]) -> Vec<Value> {
+        let server = Server {
+            program: PathBuf::from(program),
+            verbose: 0,
+        };
+        let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    fn call(id: u64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments }
+        })
+    }
+
+    #[test]
+    fn test_handshake_and_tool_list() {
+        let replies = session(
+            "rtk",
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                       "params": {"protocolVersion": "2025-03-26", "capabilities": {}}}),
+                json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "resources/list"}),
+            ],
+        );
+        // The notification gets no reply
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(replies[0]["result"]["serverInfo"]["name"], "rtk");
+
+        let names: Vec<&str> = replies[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "read_file",
+                "grep",
+                "git_status",
+                "git_diff",
+                "run_tests",
+                "lint",
+                "gain_summary"
+            ]
+        );
+        assert_eq!(replies[2]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[2]["id"], 3);
+    }
+
+    #[test]
+    fn test_bad_messages() {
+        let server = Server {
+            program: PathBuf::from("rtk"),
+            verbose: 0,
+        };
+        let reply = server.handle("{not json").unwrap();
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+        assert_eq!(reply["id"], Value::Null);
+
+        let reply = server
+            .handle(&call(4, "rm_rf", json!({})).to_string())
+            .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        let reply = server
+            .handle(&call(5, "read_file", json!({"max_lines": 10})).to_string())
+            .unwrap();
+        assert_eq!(reply["error"]["message"], "Missing argument: path");
+    }
+
+    #[test]
+    fn test_tool_arguments() {
+        let args = |name: &str, arguments: Value| invocation(name, &arguments).unwrap().args;
+        assert_eq!(
+            args(
+                "read_file",
+                json!({"path": "-x.rs", "max_lines": 20, "force": true})
+            ),
+            ["read", "--max-lines", "20", "--force", "--", "-x.rs"]
+        );
+        assert_eq!(
+            args("grep", json!({"pattern": "fn main", "file_type": "rust"})),
+            ["grep", "--file-type", "rust", "--", "fn main", "."]
+        );
+        assert_eq!(
+            args("git_diff", json!({"args": ["--cached"]})),
+            ["git", "diff", "--cached"]
+        );
+        assert!(invocation("lint", &json!({"command": "eslint ."})).is_err());
+    }
+
+    #[test]
+    fn test_commands_map_to_rtk_filters() {
+        let to_rtk = |command: &[&str]| rtk_args(&strings(command)).unwrap();
+        assert_eq!(
+            to_rtk(&["cargo", "test", "--lib"]),
+            ["cargo", "test", "--lib"]
+        );
+        assert_eq!(
+            to_rtk(&["pytest", "-k", "login and not slow"]),
+            ["pytest", "-k", "login and not slow"]
+        );
+        assert_eq!(to_rtk(&["npx", "vitest", "run"]), ["vitest", "run"]);
+        assert_eq!(to_rtk(&["eslint", "src"]), ["lint", "src"]);
+        assert_eq!(rtk_args(&strings(&["./run-tests.sh"])), None);
+    }
+
+    #[test]
+    fn test_only_test_runners_and_linters_run() {
+        let command = |name: &str, argv: &[&str]| invocation(name, &json!({ "command": argv }));
+        assert!(command("run_tests", &["cargo", "test"]).is_ok());
+        assert!(command("lint", &["ruff", "check", "."]).is_ok());
+        // Scripts, other tools and the wrong kind of runner are refused
+        assert!(command("run_tests", &["./run-tests.sh"]).is_err());
+        assert!(command("run_tests", &["sh", "-c", "cargo test"]).is_err());
+        assert!(command("lint", &["rm", "-rf", "target"]).is_err());
+        assert!(command("lint", &["cargo", "test"]).is_err());
+        assert!(command("run_tests", &["cargo", "clippy"]).is_err());
+
+        let replies = session(
+            "echo",
+            &[call(8, "run_tests", json!({"command": ["./run-tests.sh"]}))],
+        );
+        assert_eq!(replies[0]["error"]["code"], INVALID_PARAMS);
+        assert!(replies[0].get("result").is_none());
+    }
+
+    #[test]
+    fn test_summarize_test_report() {
+        let report = json!({
+            "tool": "cargo test",
+            "tier": 1,
+            "warnings": [],
+            "exit_code": 101,
+            "data": {
+                "total": 3, "passed": 2, "failed": 1, "skipped": 0, "duration_ms": null,
+                "failures": [{
+                    "test_name": "api::login",
+                    "file_path": "",
+                    "error_message": "assertion `left == right` failed\n  left: 1",
+                    "stack_trace": null
+                }]
+            }
+        });
+        assert_eq!(
+            summarize::<TestResult>(&report).unwrap(),
+            "cargo test: 1 failed, 2 passed\n  ✗ api::login\n    assertion `left == right` failed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_call_runs_child_process() {
+        // `echo` stands in for rtk: the reply carries the arguments it was given
+        let replies = session(
+            "echo",
+            &[call(
+                7,
+                "run_tests",
+                json!({"command": ["go", "test", "./..."], "timeout": "5m"}),
+            )],
+        );
+        let result = &replies[0]["result"];
+        assert_eq!(
+            result["content"][0]["text"],
+            "--format json --timeout 5m go test ./..."
+        );
+        assert_eq!(result["isError"], false);
+        assert!(result.get("structuredContent").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rerun_is_not_tracked() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in rtk reporting whether tracking was turned off for it
+        let dir = tempfile::TempDir::new().unwrap();
+        let program = dir.path().join("rtk");
+        std::fs::write(
+            &program,
+            "#!/bin/sh\nif [ \"$1\" = --format ]; then echo \"{\\\"off\\\": \\\"$RTK_TRACKING_ENABLED\\\"}\"; else echo \"text$RTK_TRACKING_ENABLED\"; fi\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let replies = session(
+            program.to_str().unwrap(),
+            &[call(1, "git_status", json!({}))],
+        );
+        let result = &replies[0]["result"];
+        assert_eq!(result["content"][0]["text"], "text");
+        assert_eq!(result["structuredContent"]["off"], "false");
+    }
+}
@@ -1,3 +1,6 @@
+use crate::budget::{Priority, Sections};
+use crate::stream::{self, StderrMode, StreamFilter};
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::{strip_ansi, truncate};
 use anyhow::{Context, Result};
@@ -10,7 +13,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     // Try next directly first, fallback to npx if not found
     let next_exists = Command::new("which")
         .arg("next")
-        .output()
+        .output_timed()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -30,69 +33,83 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     if verbose > 0 {
         let tool = if next_exists { "next" } else { "npx next" };
-        eprintln!("Running: {} build", tool);
+        render_eprintln!("Running: {} build", tool);
     }
 
-    let output = cmd
-        .output()
+    let mut filter = NextBuildFilter::default();
+    let mut outcome = stream::run(&mut cmd, "next build", &mut filter, StderrMode::Filter)
         .context("Failed to run next build (try: npm install -g next)")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
+    outcome.print_tee_hint();
 
-    let filtered = filter_next_build(&raw);
-
-    println!("{}", filtered);
-
-    timer.track("next build", "rtk next build", &raw, &filtered);
+    timer.track_streamed(
+        "next build",
+        "rtk next build",
+        outcome.raw_bytes,
+        &outcome.filtered,
+    );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    outcome.exit_on_failure();
 
     Ok(())
 }
 
+/// Bundles listed in the summary (largest first)
+const MAX_BUNDLES: usize = 10;
+
 /// Filter Next.js build output - extract routes, bundles, warnings
+#[cfg(test)]
 fn filter_next_build(output: &str) -> String {
-    lazy_static::lazy_static! {
-        // Route line pattern: ○ /dashboard    1.2 kB  132 kB
-        static ref ROUTE_PATTERN: Regex = Regex::new(
-            r"^[○●◐λ✓]\s+(/[^\s]*)\s+(\d+(?:\.\d+)?)\s*(kB|B)"
-        ).unwrap();
-
-        // Bundle size pattern
-        static ref BUNDLE_PATTERN: Regex = Regex::new(
-            r"^[○●◐λ✓]\s+([\w/\-\.]+)\s+(\d+(?:\.\d+)?)\s*(kB|B)\s+(\d+(?:\.\d+)?)\s*(kB|B)"
-        ).unwrap();
-    }
+    stream::filter_all(NextBuildFilter::default(), output)
+}
+
+/// Streaming Next.js build filter: everything is summarized at the end, only
+/// counters and the largest bundles are kept while the build runs.
+#[derive(Default)]
+struct NextBuildFilter {
+    routes_static: usize,
+    routes_dynamic: usize,
+    routes_total: usize,
+    /// Largest bundles seen so far (route, total size, % over page size)
+    bundles: Vec<(String, f64, Option<f64>)>,
+    bundle_count: usize,
+    warnings: usize,
+    errors: usize,
+    build_time: String,
+    saw_cache_hint: bool,
+    saw_ready: bool,
+}
 
-    let mut routes_static = 0;
-    let mut routes_dynamic = 0;
-    let mut routes_total = 0;
-    let mut bundles: Vec<(String, f64, Option<f64>)> = Vec::new();
-    let mut warnings = 0;
-    let mut errors = 0;
-    let mut build_time = String::new();
+impl StreamFilter for NextBuildFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
+        lazy_static::lazy_static! {
+            // Route line pattern: ○ /dashboard    1.2 kB  132 kB
+            static ref ROUTE_PATTERN: Regex = Regex::new(
+                r"^[○●◐λ✓]\s+(/[^\s]*)\s+(\d+(?:\.\d+)?)\s*(kB|B)"
+            ).unwrap();
+
+            // Bundle size pattern
+            static ref BUNDLE_PATTERN: Regex = Regex::new(
+                r"^[○●◐λ✓]\s+([\w/\-\.]+)\s+(\d+(?:\.\d+)?)\s*(kB|B)\s+(\d+(?:\.\d+)?)\s*(kB|B)"
+            ).unwrap();
+        }
 
-    // Strip ANSI codes
-    let clean_output = strip_ansi(output);
+        // Strip ANSI codes
+        let line = strip_ansi(line);
 
-    for line in clean_output.lines() {
         // Count route types by symbol
         if line.starts_with("○") {
-            routes_static += 1;
-            routes_total += 1;
+            self.routes_static += 1;
+            self.routes_total += 1;
         } else if line.starts_with("●") || line.starts_with("◐") {
-            routes_dynamic += 1;
-            routes_total += 1;
+            self.routes_dynamic += 1;
+            self.routes_total += 1;
         } else if line.starts_with("λ") {
-            routes_total += 1;
+            self.routes_total += 1;
         }
 
         // Extract bundle information (route + size + total size)
-        if let Some(caps) = BUNDLE_PATTERN.captures(line) {
+        if let Some(caps) = BUNDLE_PATTERN.captures(&line) {
             let route = caps[1].to_string();
             let size: f64 = caps[2].parse().unwrap_or(0.0);
             let total: f64 = caps[4].parse().unwrap_or(0.0);
@@ -104,84 +121,123 @@ fn filter_next_build(output: &str) -> String {
                 None
             };
 
-            bundles.push((route, total, pct_change));
+            self.bundle_count += 1;
+            self.bundles.push((route, total, pct_change));
+            if self.bundles.len() > MAX_BUNDLES {
+                self.bundles
+                    .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+                self.bundles.truncate(MAX_BUNDLES);
+            }
         }
 
         // Count warnings and errors
         if line.to_lowercase().contains("warning") {
-            warnings += 1;
+            self.warnings += 1;
         }
         if line.to_lowercase().contains("error") && !line.contains("0 error") {
-            errors += 1;
+            self.errors += 1;
         }
 
         // Extract build time
         if line.contains("Compiled") || line.contains("in") {
-            if let Some(time_match) = extract_time(line) {
-                build_time = time_match;
+            if let Some(time_match) = extract_time(&line) {
+                self.build_time = time_match;
             }
         }
-    }
-
-    // Detect if build was skipped (already built)
-    let already_built = clean_output.contains("already optimized")
-        || clean_output.contains("Cache")
-        || (routes_total == 0 && clean_output.contains("Ready"));
 
-    // Build filtered output
-    let mut result = String::new();
-    result.push_str("⚡ Next.js Build\n");
-    result.push_str("═══════════════════════════════════════\n");
+        if line.contains("already optimized") || line.contains("Cache") {
+            self.saw_cache_hint = true;
+        }
+        if line.contains("Ready") {
+            self.saw_ready = true;
+        }
 
-    if already_built && routes_total == 0 {
-        result.push_str("✓ Already built (using cache)\n\n");
-    } else if routes_total > 0 {
-        result.push_str(&format!(
-            "✓ {} routes ({} static, {} dynamic)\n\n",
-            routes_total, routes_static, routes_dynamic
-        ));
+        None
     }
 
-    if !bundles.is_empty() {
-        result.push_str("Bundles:\n");
+    fn progress(&self) -> Option<String> {
+        Some(format!(
+            "{} routes, {} errors",
+            self.routes_total, self.errors
+        ))
+    }
 
-        // Sort by size (descending) and show top 10
-        bundles.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    fn finish(&mut self) -> String {
+        self.finish_sections().text().trim().to_string()
+    }
 
-        for (route, size, pct_change) in bundles.iter().take(10) {
-            let warning_marker = if let Some(pct) = pct_change {
-                if *pct > 10.0 {
-                    format!(" ⚠️ (+{:.0}%)", pct)
+    /// Route counts and totals are the summary, the bundle table is detail
+    fn finish_sections(&mut self) -> Sections {
+        let mut sections = Sections::new();
+        // Detect if build was skipped (already built)
+        let already_built =
+            self.saw_cache_hint || (self.routes_total == 0 && self.saw_ready);
+
+        // Build filtered output
+        let mut result = String::new();
+        result.push_str("⚡ Next.js Build\n");
+        result.push_str("═══════════════════════════════════════\n");
+
+        if already_built && self.routes_total == 0 {
+            result.push_str("✓ Already built (using cache)\n\n");
+        } else if self.routes_total > 0 {
+            result.push_str(&format!(
+                "✓ {} routes ({} static, {} dynamic)\n\n",
+                self.routes_total, self.routes_static, self.routes_dynamic
+            ));
+        }
+        sections.push(Priority::Summary, std::mem::take(&mut result));
+
+        if !self.bundles.is_empty() {
+            result.push_str("Bundles:\n");
+
+            // Sort by size (descending)
+            self.bundles
+                .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+            for (route, size, pct_change) in &self.bundles {
+                let warning_marker = if let Some(pct) = pct_change {
+                    if *pct > 10.0 {
+                        format!(" ⚠️ (+{:.0}%)", pct)
+                    } else {
+                        String::new()
+                    }
                 } else {
                     String::new()
-                }
-            } else {
-                String::new()
-            };
+                };
+
+                result.push_str(&format!(
+                    "  {:<30} {:>6.0} kB{}\n",
+                    truncate(route, 30),
+                    size,
+                    warning_marker
+                ));
+            }
 
-            result.push_str(&format!(
-                "  {:<30} {:>6.0} kB{}\n",
-                truncate(route, 30),
-                size,
-                warning_marker
-            ));
+            if self.bundle_count > MAX_BUNDLES {
+                result.push_str(&format!(
+                    "\n  ... +{} more routes\n",
+                    self.bundle_count - MAX_BUNDLES
+                ));
+            }
+
+            result.push('\n');
+            sections.push(Priority::Context, std::mem::take(&mut result));
         }
 
-        if bundles.len() > 10 {
-            result.push_str(&format!("\n  ... +{} more routes\n", bundles.len() - 10));
+        // Show build time and status
+        if !self.build_time.is_empty() {
+            result.push_str(&format!("Time: {} | ", self.build_time));
         }
 
-        result.push('\n');
-    }
+        result.push_str(&format!(
+            "Errors: {} | Warnings: {}\n",
+            self.errors, self.warnings
+        ));
+        sections.push(Priority::Summary, result);
 
-    // Show build time and status
-    if !build_time.is_empty() {
-        result.push_str(&format!("Time: {} | ", build_time));
+        sections
     }
-
-    result.push_str(&format!("Errors: {} | Warnings: {}\n", errors, warnings));
-
-    result.trim().to_string()
 }
 
 /// Extract time from build output (e.g., "Compiled in 34.2s")
@@ -1,3 +1,5 @@
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -17,16 +19,17 @@ pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: npm run {}", args.join(" "));
+        render_eprintln!("Running: npm run {}", args.join(" "));
     }
 
-    let output = cmd.output().context("Failed to run npm run")?;
+    let output = cmd.output_timed().context("Failed to run npm run")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_npm_output(&raw);
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("npm run {}", args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("npm run {}", args.join(" ")),
@@ -35,9 +38,7 @@ pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
         &filtered,
     );
 
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
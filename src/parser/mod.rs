@@ -14,6 +14,12 @@ pub mod types;
 pub use formatter::{FormatMode, TokenFormatter};
 pub use types::*;
 
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Worst tier parsed during this run, with its warnings (see [`noted`])
+static NOTED: Mutex<Option<(u8, Vec<String>)>> = Mutex::new(None);
+
 /// Parse result with degradation tier
 #[derive(Debug)]
 pub enum ParseResult<T> {
@@ -29,6 +35,7 @@ pub enum ParseResult<T> {
 
 impl<T> ParseResult<T> {
     /// Unwrap the parsed data, panicking on Passthrough
+    #[allow(dead_code)]
     pub fn unwrap(self) -> T {
         match self {
             ParseResult::Full(data) => data,
@@ -47,11 +54,13 @@ impl<T> ParseResult<T> {
     }
 
     /// Check if parsing succeeded (Full or Degraded)
+    #[allow(dead_code)]
     pub fn is_ok(&self) -> bool {
         !matches!(self, ParseResult::Passthrough(_))
     }
 
     /// Map the parsed data while preserving tier
+    #[allow(dead_code)]
     pub fn map<U, F>(self, f: F) -> ParseResult<U>
     where
         F: FnOnce(T) -> U,
@@ -70,6 +79,109 @@ impl<T> ParseResult<T> {
             _ => vec![],
         }
     }
+
+    /// Remember the tier and warnings so the tracked run records them
+    pub fn note(&self) {
+        let mut noted = NOTED.lock().unwrap_or_else(|e| e.into_inner());
+        merge_noted(&mut noted, self.tier(), self.warnings());
+    }
+}
+
+/// Keep the worst tier; warnings of equal tiers accumulate
+fn merge_noted(noted: &mut Option<(u8, Vec<String>)>, tier: u8, warnings: Vec<String>) {
+    match noted {
+        Some((worst, _)) if *worst > tier => {}
+        Some((worst, list)) if *worst == tier => {
+            for warning in warnings {
+                if !list.contains(&warning) {
+                    list.push(warning);
+                }
+            }
+        }
+        _ => *noted = Some((tier, warnings)),
+    }
+}
+
+/// Tier and warnings noted by [`ParseResult::note`] during this run, stored
+/// with the tracked command (`rtk gain --health`). `None` when no output was parsed.
+pub fn noted() -> Option<(u8, Vec<String>)> {
+    NOTED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+impl<T: Serialize> ParseResult<T> {
+    /// Wrap for `--format json`: the parsed data (or raw output on passthrough)
+    /// plus the tier and warnings, so callers can tell how far to trust it
+    pub fn into_report(self, tool: &str, exit_code: i32) -> JsonReport<T> {
+        let tier = self.tier();
+        let warnings = self.warnings();
+        let (data, raw) = match self {
+            ParseResult::Full(data) | ParseResult::Degraded(data, _) => (Some(data), None),
+            ParseResult::Passthrough(raw) => (None, Some(raw)),
+        };
+        JsonReport {
+            tool: tool.to_string(),
+            tier,
+            warnings,
+            exit_code,
+            timed_out: false,
+            data,
+            raw,
+        }
+    }
+}
+
+/// Output format selected with the top-level `--format` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Filtered, human-oriented text
+    #[default]
+    Text,
+    /// The canonical struct with parse tier and warnings
+    Json,
+}
+
+/// Document printed by `--format json`
+#[derive(Debug, Serialize)]
+pub struct JsonReport<T> {
+    pub tool: String,
+    pub tier: u8,
+    pub warnings: Vec<String>,
+    pub exit_code: i32,
+    /// The command was stopped by `--timeout`; `data` covers the partial output
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    /// Raw output, only when nothing could be parsed (tier 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+}
+
+/// `--format json` was requested
+pub fn json_requested() -> bool {
+    crate::context::get().format == OutputFormat::Json
+}
+
+/// Print the `--format json` document for `result` and return it (for tracking).
+/// Bypasses the display settings and `--max-tokens`, which would corrupt the JSON
+/// (secrets are still redacted).
+pub fn print_json<T: Serialize>(tool: &str, result: ParseResult<T>, exit_code: i32) -> String {
+    result.note();
+    let mut report = result.into_report(tool, exit_code);
+    if crate::timeout::expired() {
+        report.timed_out = true;
+        report.exit_code = crate::timeout::EXIT_CODE;
+    }
+    let json = serde_json::to_string_pretty(&report).unwrap_or_else(|e| {
+        format!(
+            "{{\"tool\": {:?}, \"tier\": 3, \"warnings\": [{:?}], \"exit_code\": {}}}",
+            tool,
+            e.to_string(),
+            exit_code
+        )
+    });
+    std::println!("{}", crate::redact::text(&json));
+    json
 }
 
 /// Unified parser trait for tool outputs
@@ -85,6 +197,7 @@ pub trait OutputParser: Sized {
     fn parse(input: &str) -> ParseResult<Self::Output>;
 
     /// Parse with explicit tier preference (for testing/debugging)
+    #[allow(dead_code)]
     fn parse_with_tier(input: &str, max_tier: u8) -> ParseResult<Self::Output> {
         let result = Self::parse(input);
         if result.tier() > max_tier {
@@ -113,12 +226,12 @@ pub fn truncate_output(output: &str, max_chars: usize) -> String {
 
 /// Helper to emit degradation warning
 pub fn emit_degradation_warning(tool: &str, reason: &str) {
-    eprintln!("[RTK:DEGRADED] {} parser: {}", tool, reason);
+    render_eprintln!("[RTK:DEGRADED] {} parser: {}", tool, reason);
 }
 
 /// Helper to emit passthrough warning
 pub fn emit_passthrough_warning(tool: &str, reason: &str) {
-    eprintln!("[RTK:PASSTHROUGH] {} parser: {}", tool, reason);
+    render_eprintln!("[RTK:PASSTHROUGH] {} parser: {}", tool, reason);
 }
 
 /// Extract a complete JSON object from input that may have non-JSON prefix (pnpm banner, dotenv messages, etc.)
@@ -191,6 +304,18 @@ pub fn extract_json_object(input: &str) -> Option<&str> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_merge_noted_keeps_worst_tier() {
+        let mut noted = None;
+        merge_noted(&mut noted, 1, vec![]);
+        merge_noted(&mut noted, 2, vec!["a".to_string()]);
+        merge_noted(&mut noted, 2, vec!["a".to_string(), "b".to_string()]);
+        merge_noted(&mut noted, 1, vec![]);
+        assert_eq!(noted, Some((2, vec!["a".to_string(), "b".to_string()])));
+        merge_noted(&mut noted, 3, vec![]);
+        assert_eq!(noted, Some((3, vec![])));
+    }
+
     #[test]
     fn test_parse_result_tier() {
         let full: ParseResult<i32> = ParseResult::Full(42);
@@ -221,6 +346,24 @@ mod tests {
         assert_eq!(mapped.unwrap(), 84);
     }
 
+    #[test]
+    fn test_json_report() {
+        let degraded: ParseResult<i32> = ParseResult::Degraded(42, vec!["partial".to_string()]);
+        let json = serde_json::to_value(degraded.into_report("cargo test", 101)).unwrap();
+        assert_eq!(json["tool"], "cargo test");
+        assert_eq!(json["tier"], 2);
+        assert_eq!(json["warnings"][0], "partial");
+        assert_eq!(json["exit_code"], 101);
+        assert_eq!(json["data"], 42);
+        assert!(json.get("raw").is_none());
+
+        let passthrough: ParseResult<i32> = ParseResult::Passthrough("raw".to_string());
+        let json = serde_json::to_value(passthrough.into_report("tsc", 2)).unwrap();
+        assert_eq!(json["tier"], 3);
+        assert_eq!(json["raw"], "raw");
+        assert!(json.get("data").is_none());
+    }
+
     #[test]
     fn test_truncate_output() {
         let short = "hello";
@@ -32,6 +32,24 @@ pub struct LintResult {
     pub issues: Vec<LintIssue>,
 }
 
+impl LintResult {
+    /// Totals derived from the issues; `total_files` only counts files with issues
+    pub fn from_issues(issues: Vec<LintIssue>) -> Self {
+        let files: std::collections::HashSet<&str> =
+            issues.iter().map(|i| i.file_path.as_str()).collect();
+        let count =
+            |severity: LintSeverity| issues.iter().filter(|i| i.severity == severity).count();
+        Self {
+            total_files: files.len(),
+            files_with_issues: files.len(),
+            total_issues: issues.len(),
+            errors: count(LintSeverity::Error),
+            warnings: count(LintSeverity::Warning),
+            issues,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LintIssue {
     pub file_path: String,
@@ -111,6 +129,7 @@ pub struct GitCommit {
 
 /// Generic command output (for tools without specific types)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct GenericOutput {
     pub exit_code: i32,
     pub stdout: String,
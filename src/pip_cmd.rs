@@ -1,3 +1,6 @@
+use crate::parser::{self, truncate_output, Dependency, DependencyState, ParseResult};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -19,7 +22,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let base_cmd = if use_uv { "uv" } else { "pip" };
 
     if verbose > 0 && use_uv {
-        eprintln!("Using uv (pip-compatible)");
+        render_eprintln!("Using uv (pip-compatible)");
     }
 
     // Detect subcommand
@@ -40,6 +43,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         }
     };
 
+    if !parser::json_requested() {
+        tee::print_hint(
+            &format!("{} {}", base_cmd, args.join(" ")),
+            &cmd_str,
+            &filtered,
+        );
+    }
+
     timer.track(
         &format!("{} {}", base_cmd, args.join(" ")),
         &format!("rtk {} {}", base_cmd, args.join(" ")),
@@ -64,22 +75,28 @@ fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, Str
     }
 
     if verbose > 0 {
-        eprintln!("Running: {} pip list --format=json", base_cmd);
+        render_eprintln!("Running: {} pip list --format=json", base_cmd);
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .with_context(|| format!("Failed to run {} pip list", base_cmd))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pip_list(&stdout);
-    println!("{}", filtered);
+    let filtered = if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        parser::print_json("pip list", parse_pip_json(&stdout), exit_code)
+    } else {
+        let filtered = filter_pip_list(&stdout);
+        render_println!("{}", filtered);
+        filtered
+    };
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok((raw, filtered))
@@ -99,22 +116,28 @@ fn run_outdated(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String,
     }
 
     if verbose > 0 {
-        eprintln!("Running: {} pip list --outdated --format=json", base_cmd);
+        render_eprintln!("Running: {} pip list --outdated --format=json", base_cmd);
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .with_context(|| format!("Failed to run {} pip list --outdated", base_cmd))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pip_outdated(&stdout);
-    println!("{}", filtered);
+    let filtered = if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        parser::print_json("pip outdated", parse_pip_json(&stdout), exit_code)
+    } else {
+        let filtered = filter_pip_outdated(&stdout);
+        render_println!("{}", filtered);
+        filtered
+    };
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok((raw, filtered))
@@ -132,22 +155,22 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
     }
 
     if verbose > 0 {
-        eprintln!("Running: {} pip {}", base_cmd, args.join(" "));
+        render_eprintln!("Running: {} pip {}", base_cmd, args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .with_context(|| format!("Failed to run {} pip {}", base_cmd, args.join(" ")))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    print!("{}", stdout);
-    eprint!("{}", stderr);
+    render_print!("{}", stdout);
+    render_eprint!("{}", stderr);
 
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok((raw.clone(), raw))
@@ -157,7 +180,7 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
 fn which_command(cmd: &str) -> Option<String> {
     Command::new("which")
         .arg(cmd)
-        .output()
+        .output_timed()
         .ok()
         .filter(|o| o.status.success())
         .and_then(|o| String::from_utf8(o.stdout).ok())
@@ -166,6 +189,32 @@ fn which_command(cmd: &str) -> Option<String> {
 }
 
 /// Filter pip list JSON output
+/// Canonical result of `pip list [--outdated] --format=json` for `--format json`
+fn parse_pip_json(output: &str) -> ParseResult<DependencyState> {
+    let packages: Vec<Package> = match serde_json::from_str(output) {
+        Ok(p) => p,
+        Err(_) => return ParseResult::Passthrough(truncate_output(output.trim(), 2000)),
+    };
+    let dependencies: Vec<Dependency> = packages
+        .into_iter()
+        .map(|p| Dependency {
+            name: p.name,
+            current_version: p.version,
+            latest_version: p.latest_version,
+            wanted_version: None,
+            dev_dependency: false,
+        })
+        .collect();
+    ParseResult::Full(DependencyState {
+        total_packages: dependencies.len(),
+        outdated_count: dependencies
+            .iter()
+            .filter(|d| d.latest_version.is_some())
+            .count(),
+        dependencies,
+    })
+}
+
 fn filter_pip_list(output: &str) -> String {
     let packages: Vec<Package> = match serde_json::from_str(output) {
         Ok(p) => p,
@@ -299,4 +348,24 @@ mod tests {
         assert!(result.contains("pytest"));
         assert!(result.contains("7.4.0 → 8.0.0"));
     }
+
+    #[test]
+    fn test_parse_pip_json() {
+        let output = r#"[
+  {"name": "requests", "version": "2.31.0", "latest_version": "2.32.0"},
+  {"name": "six", "version": "1.16.0"}
+]"#;
+        let result = parse_pip_json(output);
+        assert_eq!(result.tier(), 1);
+        let deps = result.unwrap();
+        assert_eq!(deps.total_packages, 2);
+        assert_eq!(deps.outdated_count, 1);
+        assert_eq!(deps.dependencies[0].name, "requests");
+        assert_eq!(
+            deps.dependencies[0].latest_version.as_deref(),
+            Some("2.32.0")
+        );
+
+        assert_eq!(parse_pip_json("ERROR: unknown option").tier(), 3);
+    }
 }
@@ -1,3 +1,6 @@
+use crate::delta;
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::{package_manager_exec, strip_ansi};
 use anyhow::{Context, Result};
@@ -5,8 +8,8 @@ use regex::Regex;
 use serde::Deserialize;
 
 use crate::parser::{
-    emit_degradation_warning, emit_passthrough_warning, truncate_output, FormatMode, OutputParser,
-    ParseResult, TestFailure, TestResult, TokenFormatter,
+    self, emit_degradation_warning, emit_passthrough_warning, truncate_output, FormatMode,
+    OutputParser, ParseResult, TestFailure, TestResult, TokenFormatter,
 };
 
 /// Playwright JSON output structures (tool-specific format)
@@ -49,6 +52,7 @@ struct PlaywrightTest {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct PlaywrightTestResult {
     #[serde(rename = "status")]
     status: String,
@@ -229,11 +233,11 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: playwright {}", args.join(" "));
+        render_eprintln!("Running: playwright {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run playwright (try: npm install -g playwright)")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -242,12 +246,29 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     // Parse output using PlaywrightParser
     let parse_result = PlaywrightParser::parse(&stdout);
+    parse_result.note();
+
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("playwright", parse_result, exit_code);
+        timer.track(
+            &format!("playwright {}", args.join(" ")),
+            &format!("rtk playwright {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
+
     let mode = FormatMode::from_verbosity(verbose);
 
-    let filtered = match parse_result {
+    let filtered = delta::report(&format!("playwright {}", args.join(" ")), || {
+        PlaywrightParser::parse(&stdout)
+    })
+    .unwrap_or_else(|| match parse_result {
         ParseResult::Full(data) => {
             if verbose > 0 {
-                eprintln!("playwright test (Tier 1: Full JSON parse)");
+                render_eprintln!("playwright test (Tier 1: Full JSON parse)");
             }
             data.format(mode)
         }
@@ -261,9 +282,10 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
             emit_passthrough_warning("playwright", "All parsing tiers failed");
             raw
         }
-    };
+    });
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("playwright {}", args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("playwright {}", args.join(" ")),
@@ -273,9 +295,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
@@ -1,3 +1,5 @@
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -6,7 +8,7 @@ use std::ffi::OsString;
 use std::process::Command;
 
 use crate::parser::{
-    emit_degradation_warning, emit_passthrough_warning, truncate_output, Dependency,
+    self, emit_degradation_warning, emit_passthrough_warning, truncate_output, Dependency,
     DependencyState, FormatMode, OutputParser, ParseResult, TokenFormatter,
 };
 
@@ -303,23 +305,37 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run pnpm list")?;
+    let output = cmd.output_timed().context("Failed to run pnpm list")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("pnpm list failed: {}", stderr);
+        render_eprintln!("pnpm list failed: {}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     // Parse output using PnpmListParser
     let parse_result = PnpmListParser::parse(&stdout);
+    parse_result.note();
+
+    if parser::json_requested() {
+        let json = parser::print_json("pnpm list", parse_result, 0);
+        timer.track(
+            &format!("pnpm list --depth={}", depth),
+            &format!("rtk pnpm list --depth={}", depth),
+            &stdout,
+            &json,
+        );
+        return Ok(());
+    }
+
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
         ParseResult::Full(data) => {
             if verbose > 0 {
-                eprintln!("pnpm list (Tier 1: Full JSON parse)");
+                render_eprintln!("pnpm list (Tier 1: Full JSON parse)");
             }
             data.format(mode)
         }
@@ -335,7 +351,8 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
         }
     };
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("pnpm list --depth={}", depth), &stdout, &filtered);
 
     timer.track(
         &format!("pnpm list --depth={}", depth),
@@ -359,19 +376,28 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run pnpm outdated")?;
+    let output = cmd.output_timed().context("Failed to run pnpm outdated")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
+    let exit_code = output.status.code().unwrap_or(1);
 
     // Parse output using PnpmOutdatedParser
     let parse_result = PnpmOutdatedParser::parse(&stdout);
+    parse_result.note();
+
+    if parser::json_requested() {
+        let json = parser::print_json("pnpm outdated", parse_result, exit_code);
+        timer.track("pnpm outdated", "rtk pnpm outdated", &combined, &json);
+        crate::utils::exit(exit_code);
+    }
+
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
         ParseResult::Full(data) => {
             if verbose > 0 {
-                eprintln!("pnpm outdated (Tier 1: Full JSON parse)");
+                render_eprintln!("pnpm outdated (Tier 1: Full JSON parse)");
             }
             data.format(mode)
         }
@@ -388,13 +414,23 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
     };
 
     if filtered.trim().is_empty() {
-        println!("All packages up-to-date ✓");
+        if exit_code == 0 {
+            render_println!("All packages up-to-date ✓");
+        }
     } else {
-        println!("{}", filtered);
+        render_println!("{}", filtered);
+        tee::print_hint("pnpm outdated", &combined, &filtered);
     }
 
     timer.track("pnpm outdated", "rtk pnpm outdated", &combined, &filtered);
 
+    // pnpm exits 1 when packages are outdated: the list already says why
+    if filtered.trim().is_empty() {
+        crate::utils::exit_on_failure(exit_code, &combined, &filtered);
+    } else if exit_code != 0 {
+        crate::utils::exit(exit_code);
+    }
+
     Ok(())
 }
 
@@ -423,21 +459,23 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
     }
 
     if verbose > 0 {
-        eprintln!("pnpm install running...");
+        render_eprintln!("pnpm install running...");
     }
 
-    let output = cmd.output().context("Failed to run pnpm install")?;
+    let output = cmd.output_timed().context("Failed to run pnpm install")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
     if !output.status.success() {
-        anyhow::bail!("pnpm install failed: {}", stderr);
+        render_eprintln!("pnpm install failed: {}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let combined = format!("{}{}", stdout, stderr);
     let filtered = filter_pnpm_install(&combined);
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("pnpm install {}", packages.join(" ")), &combined, &filtered);
 
     timer.track(
         &format!("pnpm install {}", packages.join(" ")),
@@ -493,11 +531,11 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("pnpm passthrough: {:?}", args);
+        render_eprintln!("pnpm passthrough: {:?}", args);
     }
     let status = Command::new("pnpm")
         .args(args)
-        .status()
+        .status_timed()
         .context("Failed to run pnpm")?;
 
     let args_str = tracking::args_display(args);
@@ -507,7 +545,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
+        crate::utils::exit(status.code().unwrap_or(1));
     }
     Ok(())
 }
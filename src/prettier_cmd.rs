@@ -1,3 +1,5 @@
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::package_manager_exec;
 use anyhow::{Context, Result};
@@ -13,11 +15,11 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: prettier {}", args.join(" "));
+        render_eprintln!("Running: prettier {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run prettier (try: npm install -g prettier)")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -26,7 +28,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     let filtered = filter_prettier_output(&raw);
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("prettier {}", args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("prettier {}", args.join(" ")),
@@ -36,9 +39,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
@@ -1,3 +1,5 @@
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -28,7 +30,7 @@ pub fn run(cmd: PrismaCommand, args: &[String], verbose: u8) -> Result<()> {
 fn create_prisma_command() -> Command {
     let prisma_exists = Command::new("which")
         .arg("prisma")
-        .output()
+        .output_timed()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -52,16 +54,17 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: prisma generate");
+        render_eprintln!("Running: prisma generate");
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run prisma generate (try: npm install -g prisma)")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma generate failed: {}", stderr);
+        render_eprintln!("prisma generate failed: {}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -69,7 +72,8 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
     let raw = format!("{}\n{}", stdout, stderr);
     let filtered = filter_prisma_generate(&raw);
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint("prisma generate", &raw, &filtered);
 
     timer.track("prisma generate", "rtk prisma generate", &raw, &filtered);
 
@@ -105,14 +109,15 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
     }
 
     if verbose > 0 {
-        eprintln!("Running: {}", cmd_name);
+        render_eprintln!("Running: {}", cmd_name);
     }
 
-    let output = cmd.output().context("Failed to run prisma migrate")?;
+    let output = cmd.output_timed().context("Failed to run prisma migrate")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma migrate failed: {}", stderr);
+        render_eprintln!("prisma migrate failed: {}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -125,7 +130,8 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
         MigrateSubcommand::Deploy => filter_migrate_deploy(&raw),
     };
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(cmd_name, &raw, &filtered);
 
     timer.track(cmd_name, &format!("rtk {}", cmd_name), &raw, &filtered);
 
@@ -143,14 +149,15 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: prisma db push");
+        render_eprintln!("Running: prisma db push");
     }
 
-    let output = cmd.output().context("Failed to run prisma db push")?;
+    let output = cmd.output_timed().context("Failed to run prisma db push")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma db push failed: {}", stderr);
+        render_eprintln!("prisma db push failed: {}", stderr.trim());
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -158,7 +165,8 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
     let raw = format!("{}\n{}", stdout, stderr);
     let filtered = filter_db_push(&raw);
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint("prisma db push", &raw, &filtered);
 
     timer.track("prisma db push", "rtk prisma db push", &raw, &filtered);
 
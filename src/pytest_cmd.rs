@@ -1,6 +1,14 @@
+use crate::budget::{Priority, Sections};
+use crate::context;
+use crate::delta;
+use crate::parser::{self, truncate_output, ParseResult, TestFailure, TestResult};
+use crate::stream::{self, StderrMode, StreamFilter};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
+use regex::Regex;
 use std::process::Command;
 
 #[derive(Debug, PartialEq)]
@@ -40,37 +48,68 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: pytest --tb=short -q {}", args.join(" "));
+        render_eprintln!("Running: pytest --tb=short -q {}", args.join(" "));
     }
 
-    let output = cmd
-        .output()
-        .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
-
-    let filtered = filter_pytest_output(&stdout);
+    let label = format!("pytest {}", args.join(" "));
 
-    println!("{}", filtered);
+    if parser::json_requested() {
+        let output = cmd
+            .output_timed()
+            .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
+        let raw = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("pytest", parse_pytest_output(&raw), exit_code);
+        timer.track(
+            &label,
+            &format!("rtk pytest {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
 
-    // Include stderr if present (import errors, etc.)
-    if !stderr.trim().is_empty() {
-        eprintln!("{}", stderr.trim());
+    if delta::requested() {
+        let output = cmd
+            .output_timed()
+            .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
+        let raw = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let filtered = delta::report(&label, || parse_pytest_output(&raw))
+            .unwrap_or_else(|| stream::filter_all(PytestFilter::default(), &raw));
+        render_println!("{}", filtered);
+        tee::print_hint(&label, &raw, &filtered);
+        timer.track(
+            &label,
+            &format!("rtk pytest {}", args.join(" ")),
+            &raw,
+            &filtered,
+        );
+        crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
+        return Ok(());
     }
 
-    timer.track(
-        &format!("pytest {}", args.join(" ")),
+    let mut filter = PytestFilter::default();
+    let mut outcome = stream::run(&mut cmd, &label, &mut filter, StderrMode::Echo)
+        .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
+    outcome.print_tee_hint();
+
+    timer.track_streamed(
+        &label,
         &format!("rtk pytest {}", args.join(" ")),
-        &raw,
-        &filtered,
+        outcome.raw_bytes,
+        &outcome.filtered,
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    outcome.exit_on_failure();
 
     Ok(())
 }
@@ -79,7 +118,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 fn which_command(cmd: &str) -> Option<String> {
     Command::new("which")
         .arg(cmd)
-        .output()
+        .output_timed()
         .ok()
         .filter(|o| o.status.success())
         .and_then(|o| String::from_utf8(o.stdout).ok())
@@ -87,163 +126,271 @@ fn which_command(cmd: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+/// Max failures printed
+const MAX_FAILURES: usize = 5;
+
 /// Parse pytest output using state machine
+#[cfg(test)]
 fn filter_pytest_output(output: &str) -> String {
-    let mut state = ParseState::Header;
-    let mut test_files: Vec<String> = Vec::new();
-    let mut failures: Vec<String> = Vec::new();
-    let mut current_failure: Vec<String> = Vec::new();
-    let mut summary_line = String::new();
+    stream::filter_all(PytestFilter::default(), output)
+}
+
+/// Streaming pytest filter: each failure is printed once its section ends,
+/// the pass/fail summary comes last.
+struct PytestFilter {
+    state: ParseState,
+    current_failure: Vec<String>,
+    failures: usize,
+    summary_line: String,
+}
+
+impl Default for PytestFilter {
+    fn default() -> Self {
+        Self {
+            state: ParseState::Header,
+            current_failure: Vec::new(),
+            failures: 0,
+            summary_line: String::new(),
+        }
+    }
+}
+
+impl PytestFilter {
+    fn take_failure(&mut self) -> Option<String> {
+        if self.current_failure.is_empty() {
+            return None;
+        }
+        let lines = std::mem::take(&mut self.current_failure);
+        self.push_failure(&lines)
+    }
+
+    fn push_failure(&mut self, lines: &[String]) -> Option<String> {
+        self.failures += 1;
+        if self.failures > MAX_FAILURES {
+            return None;
+        }
+        let entry = format_failure(self.failures, lines);
+        if self.failures == 1 {
+            Some(format!("Failures:\n{}", entry))
+        } else {
+            Some(format!("\n{}", entry))
+        }
+    }
+}
 
-    for line in output.lines() {
+impl StreamFilter for PytestFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
         let trimmed = line.trim();
 
         // State transitions
         if trimmed.starts_with("===") && trimmed.contains("test session starts") {
-            state = ParseState::Header;
-            continue;
+            self.state = ParseState::Header;
+            return None;
         } else if trimmed.starts_with("===") && trimmed.contains("FAILURES") {
-            state = ParseState::Failures;
-            continue;
+            self.state = ParseState::Failures;
+            return None;
         } else if trimmed.starts_with("===") && trimmed.contains("short test summary") {
-            state = ParseState::Summary;
+            self.state = ParseState::Summary;
             // Save current failure if any
-            if !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
-                current_failure.clear();
-            }
-            continue;
+            return self.take_failure();
         } else if trimmed.starts_with("===")
             && (trimmed.contains("passed") || trimmed.contains("failed"))
         {
-            summary_line = trimmed.to_string();
-            continue;
+            self.summary_line = trimmed.to_string();
+            return None;
         }
 
         // Process based on state
-        match state {
+        match self.state {
             ParseState::Header => {
                 if trimmed.starts_with("collected") {
-                    state = ParseState::TestProgress;
-                }
-            }
-            ParseState::TestProgress => {
-                // Lines like "tests/test_foo.py ....  [ 40%]"
-                if !trimmed.is_empty()
-                    && !trimmed.starts_with("===")
-                    && (trimmed.contains(".py") || trimmed.contains("%]"))
-                {
-                    test_files.push(trimmed.to_string());
+                    self.state = ParseState::TestProgress;
                 }
+                None
             }
+            // Lines like "tests/test_foo.py ....  [ 40%]" carry nothing we print
+            ParseState::TestProgress => None,
             ParseState::Failures => {
                 // Collect failure details
                 if trimmed.starts_with("___") {
                     // New failure section
-                    if !current_failure.is_empty() {
-                        failures.push(current_failure.join("\n"));
-                        current_failure.clear();
+                    let done = self.take_failure();
+                    self.current_failure.push(trimmed.to_string());
+                    done
+                } else {
+                    if !trimmed.is_empty()
+                        && !trimmed.starts_with("===")
+                        && self.current_failure.len() < stream::MAX_BLOCK_LINES
+                    {
+                        self.current_failure.push(trimmed.to_string());
                     }
-                    current_failure.push(trimmed.to_string());
-                } else if !trimmed.is_empty() && !trimmed.starts_with("===") {
-                    current_failure.push(trimmed.to_string());
+                    None
                 }
             }
             ParseState::Summary => {
                 // FAILED test lines
                 if trimmed.starts_with("FAILED") || trimmed.starts_with("ERROR") {
-                    failures.push(trimmed.to_string());
+                    self.push_failure(&[trimmed.to_string()])
+                } else {
+                    None
                 }
             }
         }
     }
 
-    // Save last failure if any
-    if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
+    fn progress(&self) -> Option<String> {
+        Some(format!("{} failures", self.failures))
     }
 
-    // Build compact output
-    build_pytest_summary(&summary_line, &test_files, &failures)
-}
-
-fn build_pytest_summary(summary: &str, _test_files: &[String], failures: &[String]) -> String {
-    // Parse summary line
-    let (passed, failed, skipped) = parse_summary_line(summary);
-
-    if failed == 0 && passed > 0 {
-        return format!("✓ Pytest: {} passed", passed);
+    fn finish(&mut self) -> String {
+        self.finish_sections().text().trim_end().to_string()
     }
 
-    if passed == 0 && failed == 0 {
-        return "Pytest: No tests collected".to_string();
+    fn section(&self) -> Priority {
+        Priority::Failure
     }
 
-    let mut result = String::new();
-    result.push_str(&format!("Pytest: {} passed, {} failed", passed, failed));
-    if skipped > 0 {
-        result.push_str(&format!(", {} skipped", skipped));
-    }
-    result.push('\n');
-    result.push_str("═══════════════════════════════════════\n");
+    fn finish_sections(&mut self) -> Sections {
+        let last = self.take_failure();
+        let (passed, failed, skipped) = parse_summary_line(&self.summary_line);
+        let mut sections = Sections::new();
 
-    if failures.is_empty() {
-        return result.trim().to_string();
-    }
+        if failed == 0 && passed > 0 {
+            sections.push(Priority::Summary, format!("✓ Pytest: {} passed\n", passed));
+            return sections;
+        }
 
-    // Show failures (limit to key information)
-    result.push_str("\nFailures:\n");
-
-    for (i, failure) in failures.iter().take(5).enumerate() {
-        // Extract test name and key error info
-        let lines: Vec<&str> = failure.lines().collect();
-
-        // First line is usually test name (after ___)
-        if let Some(first_line) = lines.first() {
-            if first_line.starts_with("___") {
-                // Extract test name between ___
-                let test_name = first_line.trim_matches('_').trim();
-                result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
-            } else if first_line.starts_with("FAILED") {
-                // Summary format: "FAILED tests/test_foo.py::test_bar - AssertionError"
-                let parts: Vec<&str> = first_line.split(" - ").collect();
-                if let Some(test_path) = parts.first() {
-                    let test_name = test_path.trim_start_matches("FAILED ");
-                    result.push_str(&format!("{}. ❌ {}\n", i + 1, test_name));
-                }
-                if parts.len() > 1 {
-                    result.push_str(&format!("     {}\n", truncate(parts[1], 100)));
-                }
-                continue;
-            }
+        if passed == 0 && failed == 0 {
+            sections.push(Priority::Summary, "Pytest: No tests collected\n");
+            return sections;
+        }
+
+        if let Some(entry) = last {
+            sections.push(Priority::Failure, format!("{}\n", entry));
         }
+        if self.failures > MAX_FAILURES {
+            sections.push(
+                Priority::Context,
+                format!("\n... +{} more failures\n", self.failures - MAX_FAILURES),
+            );
+        }
+
+        let mut summary = String::from("═══════════════════════════════════════\n");
+        summary.push_str(&format!("Pytest: {} passed, {} failed", passed, failed));
+        if skipped > 0 {
+            summary.push_str(&format!(", {} skipped", skipped));
+        }
+        summary.push('\n');
+        sections.push(Priority::Summary, summary);
+        sections
+    }
+}
 
-        // Show relevant error lines (assertions, errors, file locations)
-        let mut relevant_lines = 0;
-        for line in &lines[1..] {
-            let line_lower = line.to_lowercase();
-            let is_relevant = line.trim().starts_with('>')
-                || line.trim().starts_with('E')
-                || line_lower.contains("assert")
-                || line_lower.contains("error")
-                || line.contains(".py:");
-
-            if is_relevant && relevant_lines < 3 {
-                result.push_str(&format!("     {}\n", truncate(line, 100)));
-                relevant_lines += 1;
+/// Render one failure: test name plus up to 3 relevant error lines
+fn format_failure(index: usize, lines: &[String]) -> String {
+    let mut result = String::new();
+
+    // First line is usually test name (after ___)
+    if let Some(first_line) = lines.first() {
+        if first_line.starts_with("___") {
+            // Extract test name between ___
+            let test_name = first_line.trim_matches('_').trim();
+            result.push_str(&format!("{}. ❌ {}\n", index, test_name));
+        } else if first_line.starts_with("FAILED") {
+            // Summary format: "FAILED tests/test_foo.py::test_bar - AssertionError"
+            let parts: Vec<&str> = first_line.split(" - ").collect();
+            if let Some(test_path) = parts.first() {
+                let test_name = test_path.trim_start_matches("FAILED ");
+                result.push_str(&format!("{}. ❌ {}\n", index, test_name));
+            }
+            if parts.len() > 1 {
+                result.push_str(&format!(
+                    "     {}\n",
+                    truncate(parts[1], context::width(100))
+                ));
             }
+            return result.trim_end().to_string();
         }
+    }
 
-        if i < failures.len() - 1 {
-            result.push('\n');
+    // Show relevant error lines (assertions, errors, file locations)
+    let mut relevant_lines = 0;
+    for line in lines.iter().skip(1) {
+        let line_lower = line.to_lowercase();
+        let is_relevant = line.trim().starts_with('>')
+            || line.trim().starts_with('E')
+            || line_lower.contains("assert")
+            || line_lower.contains("error")
+            || line.contains(".py:");
+
+        if is_relevant && relevant_lines < 3 {
+            result.push_str(&format!("     {}\n", truncate(line, context::width(100))));
+            relevant_lines += 1;
         }
     }
 
-    if failures.len() > 5 {
-        result.push_str(&format!("\n... +{} more failures\n", failures.len() - 5));
+    result.trim_end().to_string()
+}
+
+/// Canonical result for `--format json`, from the final summary line and the
+/// `FAILED` / `ERROR` entries of the short test summary
+fn parse_pytest_output(output: &str) -> ParseResult<TestResult> {
+    lazy_static::lazy_static! {
+        static ref SUMMARY: Regex = Regex::new(
+            r"^=*\s*((?:\d+ \w+(?:, )?)+) in ([\d.]+)s"
+        ).unwrap();
+        static ref ERRORS: Regex = Regex::new(r"(\d+) errors?\b").unwrap();
     }
 
-    result.trim().to_string()
+    let Some((summary, duration)) = output.lines().rev().find_map(|line| {
+        let caps = SUMMARY.captures(line.trim())?;
+        Some((caps[1].to_string(), caps[2].parse::<f64>().ok()))
+    }) else {
+        return ParseResult::Passthrough(truncate_output(output.trim(), 2000));
+    };
+
+    let (passed, failed, skipped) = parse_summary_line(&summary);
+    let errors: usize = ERRORS
+        .captures(&summary)
+        .and_then(|caps| caps[1].parse().ok())
+        .unwrap_or(0);
+
+    let failures: Vec<TestFailure> = output
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            line.strip_prefix("FAILED ")
+                .or_else(|| line.strip_prefix("ERROR "))
+        })
+        .map(|entry| {
+            let (test, message) = entry.split_once(" - ").unwrap_or((entry, ""));
+            let (file, name) = test.split_once("::").unwrap_or((test, test));
+            TestFailure {
+                test_name: name.to_string(),
+                file_path: file.to_string(),
+                error_message: message.to_string(),
+                stack_trace: None,
+            }
+        })
+        .collect();
+
+    let result = TestResult {
+        total: passed + failed + skipped + errors,
+        passed,
+        failed: failed + errors,
+        skipped,
+        duration_ms: duration.map(|secs| (secs * 1000.0) as u64),
+        failures,
+    };
+    if result.failures.len() < result.failed {
+        let warning = format!(
+            "{} of {} failures have no FAILED/ERROR entry (short test summary disabled?)",
+            result.failed - result.failures.len(),
+            result.failed
+        );
+        return ParseResult::Degraded(result, vec![warning]);
+    }
+    ParseResult::Full(result)
 }
 
 fn parse_summary_line(summary: &str) -> (usize, usize, usize) {
@@ -350,6 +497,22 @@ FAILED tests/test_foo.py::test_three - KeyError
         assert!(result.contains("expected 5"));
     }
 
+    #[test]
+    fn test_pytest_sections_put_failures_before_summary() {
+        let output = r#"=== FAILURES ===
+___ test_one ___
+E   AssertionError: expected 5
+
+=== short test summary info ===
+=== 1 failed in 0.20s ==="#;
+
+        let sections = crate::stream::sections_all(PytestFilter::default(), output);
+        let full = sections.render_within(None);
+        let failure = full.find("expected 5").unwrap();
+        let summary = full.find("Pytest: 0 passed, 1 failed").unwrap();
+        assert!(failure < summary, "got: {}", full);
+    }
+
     #[test]
     fn test_filter_pytest_no_tests() {
         let output = r#"=== test session starts ===
@@ -361,6 +524,45 @@ collected 0 items
         assert!(result.contains("No tests collected"));
     }
 
+    #[test]
+    fn test_parse_pytest_output_canonical() {
+        let output = r#"..F.F
+=========================== short test summary info ============================
+FAILED tests/test_foo.py::test_bar - AssertionError: assert 1 == 2
+FAILED tests/test_foo.py::test_baz - ValueError: bad
+2 failed, 3 passed, 1 skipped in 0.50s"#;
+
+        let result = parse_pytest_output(output);
+        assert_eq!(result.tier(), 1);
+        let tests = result.unwrap();
+        assert_eq!(tests.total, 6);
+        assert_eq!(tests.passed, 3);
+        assert_eq!(tests.failed, 2);
+        assert_eq!(tests.skipped, 1);
+        assert_eq!(tests.duration_ms, Some(500));
+        assert_eq!(tests.failures[0].file_path, "tests/test_foo.py");
+        assert_eq!(tests.failures[0].test_name, "test_bar");
+        assert_eq!(
+            tests.failures[0].error_message,
+            "AssertionError: assert 1 == 2"
+        );
+    }
+
+    #[test]
+    fn test_parse_pytest_output_tiers() {
+        let errors = "ERROR tests/test_db.py - ImportError: no module\n=== 1 error in 0.10s ===";
+        let result = parse_pytest_output(errors);
+        assert_eq!(result.tier(), 1);
+        assert_eq!(result.unwrap().failed, 1);
+
+        let no_details = "=== 1 failed, 2 passed in 0.10s ===";
+        let result = parse_pytest_output(no_details);
+        assert_eq!(result.tier(), 2);
+        assert_eq!(result.warnings().len(), 1);
+
+        assert_eq!(parse_pytest_output("usage: pytest [options]").tier(), 3);
+    }
+
     #[test]
     fn test_parse_summary_line() {
         assert_eq!(parse_summary_line("=== 5 passed in 0.50s ==="), (5, 0, 0));
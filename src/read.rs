@@ -1,4 +1,7 @@
+use crate::context;
 use crate::filter::{self, FilterLevel, Language};
+use crate::read_cache;
+use crate::redact;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::fs;
@@ -8,13 +11,15 @@ pub fn run(
     file: &Path,
     level: FilterLevel,
     max_lines: Option<usize>,
+    tail_lines: Option<usize>,
     line_numbers: bool,
+    force: bool,
     verbose: u8,
 ) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Reading: {} (filter: {})", file.display(), level);
+        render_eprintln!("Reading: {} (filter: {})", file.display(), level);
     }
 
     // Read file content
@@ -29,7 +34,7 @@ pub fn run(
         .unwrap_or(Language::Unknown);
 
     if verbose > 1 {
-        eprintln!("Detected language: {:?}", lang);
+        render_eprintln!("Detected language: {:?}", lang);
     }
 
     // Apply filter
@@ -44,9 +49,11 @@ pub fn run(
         } else {
             0.0
         };
-        eprintln!(
+        render_eprintln!(
             "Lines: {} -> {} ({:.1}% reduction)",
-            original_lines, filtered_lines, reduction
+            original_lines,
+            filtered_lines,
+            reduction
         );
     }
 
@@ -55,12 +62,28 @@ pub fn run(
         filtered = filter::smart_truncate(&filtered, max, &lang);
     }
 
-    let rtk_output = if line_numbers {
-        format_with_line_numbers(&filtered)
-    } else {
-        filtered.clone()
+    if let Some(n) = tail_lines {
+        filtered = keep_tail(&filtered, n);
+    }
+
+    // Rereads in the same session print a notice or a diff instead
+    let view = view_key(level, max_lines, tail_lines, line_numbers);
+    let rtk_output = match read_cache::check(file, &view, &filtered, force) {
+        Some(notice) => {
+            render_println!("{}", notice);
+            notice
+        }
+        None => {
+            let rtk_output = if line_numbers {
+                format_with_line_numbers(&filtered)
+            } else {
+                filtered.clone()
+            };
+            // File content is printed as-is (no emoji/color rewriting), minus secrets
+            context::raw(redact::text(&format!("{}\n", rtk_output)).as_bytes());
+            rtk_output
+        }
     };
-    println!("{}", rtk_output);
     timer.track(
         &format!("cat {}", file.display()),
         "rtk read",
@@ -73,6 +96,7 @@ pub fn run(
 pub fn run_stdin(
     level: FilterLevel,
     max_lines: Option<usize>,
+    tail_lines: Option<usize>,
     line_numbers: bool,
     verbose: u8,
 ) -> Result<()> {
@@ -81,7 +105,7 @@ pub fn run_stdin(
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Reading from stdin (filter: {})", level);
+        render_eprintln!("Reading from stdin (filter: {})", level);
     }
 
     // Read from stdin
@@ -95,7 +119,7 @@ pub fn run_stdin(
     let lang = Language::Unknown;
 
     if verbose > 1 {
-        eprintln!("Language: {:?} (stdin has no extension)", lang);
+        render_eprintln!("Language: {:?} (stdin has no extension)", lang);
     }
 
     // Apply filter
@@ -110,9 +134,11 @@ pub fn run_stdin(
         } else {
             0.0
         };
-        eprintln!(
+        render_eprintln!(
             "Lines: {} -> {} ({:.1}% reduction)",
-            original_lines, filtered_lines, reduction
+            original_lines,
+            filtered_lines,
+            reduction
         );
     }
 
@@ -121,17 +147,51 @@ pub fn run_stdin(
         filtered = filter::smart_truncate(&filtered, max, &lang);
     }
 
+    if let Some(n) = tail_lines {
+        filtered = keep_tail(&filtered, n);
+    }
+
     let rtk_output = if line_numbers {
         format_with_line_numbers(&filtered)
     } else {
         filtered.clone()
     };
-    println!("{}", rtk_output);
+    context::raw(redact::text(&format!("{}\n", rtk_output)).as_bytes());
 
     timer.track("cat - (stdin)", "rtk read -", &content, &rtk_output);
     Ok(())
 }
 
+/// Keep the last `n` lines, noting how many were dropped (like `tail -n`)
+fn keep_tail(content: &str, n: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() <= n {
+        return content.to_string();
+    }
+    let skipped = lines.len() - n;
+    format!(
+        "... {} lines omitted\n{}",
+        skipped,
+        lines[skipped..].join("\n")
+    )
+}
+
+/// Read cache key covering every option that changes the printed output
+fn view_key(
+    level: FilterLevel,
+    max_lines: Option<usize>,
+    tail_lines: Option<usize>,
+    line_numbers: bool,
+) -> String {
+    format!(
+        "{} {:?} {:?} {}",
+        level,
+        max_lines,
+        tail_lines,
+        if line_numbers { "numbered" } else { "plain" }
+    )
+}
+
 fn format_with_line_numbers(content: &str) -> String {
     let lines: Vec<&str> = content.lines().collect();
     let width = lines.len().to_string().len();
@@ -155,15 +215,42 @@ mod tests {
             file,
             r#"// Comment
 fn main() {{
-    println!("Hello");
+    render_println!("Hello");
 }}"#
         )?;
 
         // Just verify it doesn't panic
-        run(file.path(), FilterLevel::Minimal, None, false, 0)?;
+        run(
+            file.path(),
+            FilterLevel::Minimal,
+            None,
+            None,
+            false,
+            true,
+            0,
+        )?;
         Ok(())
     }
 
+    #[test]
+    fn test_keep_tail() {
+        assert_eq!(keep_tail("a\nb\nc\nd", 2), "... 2 lines omitted\nc\nd");
+        assert_eq!(keep_tail("a\nb", 5), "a\nb");
+    }
+
+    #[test]
+    fn test_view_key_covers_output_options() {
+        let keys = [
+            view_key(FilterLevel::Minimal, None, None, false),
+            view_key(FilterLevel::Minimal, None, None, true),
+            view_key(FilterLevel::Aggressive, None, None, false),
+            view_key(FilterLevel::Minimal, Some(10), None, false),
+            view_key(FilterLevel::Minimal, None, Some(10), false),
+        ];
+        let unique: std::collections::HashSet<&String> = keys.iter().collect();
+        assert_eq!(unique.len(), keys.len());
+    }
+
     #[test]
     fn test_stdin_support_signature() {
         // Test that run_stdin has correct signature and compiles
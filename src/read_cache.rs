@@ -0,0 +1,272 @@
+//! Per-session cache for `rtk read`.
+//!
+//! Agents reread the same files many times in one session. The filtered view of
+//! each file is saved under `~/.local/share/rtk/reads/`, keyed by session (see
+//! [`crate::utils::session_id`]), path and view options. An identical reread
+//! prints a one-line notice, a changed file prints a diff against the cached
+//! copy; `rtk read --force` always prints the whole file. Cached copies are
+//! redacted (see [`crate::redact`]) like the tee store.
+
+use crate::redact;
+use crate::utils::{session_id, stable_hash};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Entries older than this belong to finished sessions
+const MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 2;
+
+/// Upper bound on the diff table (old lines x new lines in the changed region)
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    session: String,
+    path: String,
+    view: String,
+    /// Unix seconds
+    read_at: i64,
+    output: String,
+}
+
+/// Compare `output` (the filtered view of `file`) with the last read in this
+/// session and save it for the next one.
+///
+/// Returns what to print instead of `output`: an "unchanged" notice, or a diff
+/// when it is shorter than the file. `None` means print the file (first read,
+/// `force`, no known session, or a diff that would not save anything).
+pub fn check(file: &Path, view: &str, output: &str, force: bool) -> Option<String> {
+    // Cached copies are stored redacted, so compare redacted against redacted
+    let output = redact::text(output);
+    let output: &str = &output;
+    let path = fs::canonicalize(file)
+        .unwrap_or_else(|_| file.to_path_buf())
+        .display()
+        .to_string();
+    let dir = cache_dir();
+    let session = session_id()?;
+    let previous = if force {
+        None
+    } else {
+        load_in(&dir, &session, &path, view)
+    };
+    if let Err(e) = save_in(&dir, &session, &path, view, output) {
+        render_eprintln!("rtk: read cache not saved: {}", e);
+    }
+    let previous = previous?;
+
+    let since = DateTime::from_timestamp(previous.read_at, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+        .format("%H:%M");
+    let lines = output.lines().count();
+    if previous.output == output {
+        return Some(format!(
+            "{}: unchanged ({}, {} lines) since {}; rtk read --force to print it again",
+            file.display(),
+            short_hash(output),
+            lines,
+            since
+        ));
+    }
+
+    let diff = diff(&previous.output, output)?;
+    let report = format!(
+        "{}: changed since {} ({} → {}, {} lines); diff against last read:\n{}",
+        file.display(),
+        since,
+        short_hash(&previous.output),
+        short_hash(output),
+        lines,
+        diff
+    );
+    (report.len() < output.len()).then_some(report)
+}
+
+/// Directory holding the cached reads (`RTK_READ_CACHE_DIR` overrides the default)
+fn cache_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("RTK_READ_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rtk")
+        .join("reads")
+}
+
+fn short_hash(text: &str) -> String {
+    format!("{:016x}", stable_hash(&[text]))[..8].to_string()
+}
+
+fn entry_path(dir: &Path, session: &str, path: &str, view: &str) -> PathBuf {
+    dir.join(format!("{:016x}.json", stable_hash(&[session, path, view])))
+}
+
+fn load_in(dir: &Path, session: &str, path: &str, view: &str) -> Option<Entry> {
+    let content = fs::read_to_string(entry_path(dir, session, path, view)).ok()?;
+    let entry: Entry = serde_json::from_str(&content).ok()?;
+    (entry.session == session && entry.path == path && entry.view == view).then_some(entry)
+}
+
+fn save_in(dir: &Path, session: &str, path: &str, view: &str, output: &str) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create read cache: {}", dir.display()))?;
+    let entry = Entry {
+        session: session.to_string(),
+        path: path.to_string(),
+        view: view.to_string(),
+        read_at: Utc::now().timestamp(),
+        output: output.to_string(),
+    };
+    let file = entry_path(dir, session, path, view);
+    fs::write(&file, serde_json::to_string(&entry)?)
+        .with_context(|| format!("Failed to write {}", file.display()))?;
+    prune(dir);
+    Ok(())
+}
+
+fn prune(dir: &Path) {
+    let Some(cutoff) = SystemTime::now().checked_sub(MAX_AGE) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let modified = entry.metadata().and_then(|m| m.modified());
+        if modified.is_ok_and(|m| m < cutoff) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Unified-style hunks between two texts, or `None` if the changed region is
+/// too large to diff cheaply
+fn diff(old: &str, new: &str) -> Option<String> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Only the region between the common prefix and suffix needs the LCS table
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let (n, m) = (a_mid.len(), b_mid.len());
+    if n * m > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[at(i, j)] = if a_mid[i] == b_mid[j] {
+                lcs[at(i + 1, j + 1)] + 1
+            } else {
+                lcs[at(i + 1, j)].max(lcs[at(i, j + 1)])
+            };
+        }
+    }
+
+    // (tag, old line index, new line index) over the whole file
+    let mut ops: Vec<(char, usize, usize)> = (0..prefix).map(|k| (' ', k, k)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a_mid[i] == b_mid[j] {
+            ops.push((' ', prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[at(i + 1, j)] >= lcs[at(i, j + 1)]) {
+            ops.push(('-', prefix + i, prefix + j));
+            i += 1;
+        } else {
+            ops.push(('+', prefix + i, prefix + j));
+            j += 1;
+        }
+    }
+    ops.extend((0..suffix).map(|k| (' ', prefix + n + k, prefix + m + k)));
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    let mut out = String::new();
+    let mut k = 0;
+    while k < changed.len() {
+        // Extend the hunk while the next change is within the shared context
+        let mut end = k;
+        while end + 1 < changed.len() && changed[end + 1] - changed[end] <= 2 * CONTEXT_LINES + 1 {
+            end += 1;
+        }
+        let start = changed[k].saturating_sub(CONTEXT_LINES);
+        let stop = (changed[end] + CONTEXT_LINES + 1).min(ops.len());
+        let hunk = &ops[start..stop];
+        let old_count = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_count = hunk.iter().filter(|op| op.0 != '-').count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk[0].1 + 1,
+            old_count,
+            hunk[0].2 + 1,
+            new_count
+        ));
+        for &(tag, old_line, new_line) in hunk {
+            let text = if tag == '-' { a[old_line] } else { b[new_line] };
+            out.push_str(&format!("{}{}\n", tag, text));
+        }
+        k = end + 1;
+    }
+    Some(out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_insert_keeps_alignment() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh";
+        let new = "a\nb\nc\nX\nd\ne\nf\ng\nh";
+        assert_eq!(
+            diff(old, new).unwrap(),
+            "@@ -2,4 +2,5 @@\n b\n c\n+X\n d\n e"
+        );
+    }
+
+    #[test]
+    fn test_diff_separate_hunks() {
+        let lines = |edit: bool| {
+            (1..=20)
+                .map(|n| match n {
+                    3 if edit => "three".to_string(),
+                    18 if edit => "eighteen".to_string(),
+                    _ => n.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let (old, new) = (lines(false), lines(true));
+        let out = diff(&old, &new).unwrap();
+        assert_eq!(out.matches("@@ -").count(), 2);
+        assert!(out.contains("-3\n+three"));
+        assert!(out.contains("-18\n+eighteen"));
+    }
+
+    #[test]
+    fn test_cache_per_session_and_view() {
+        let dir = tempfile::tempdir().unwrap();
+        save_in(dir.path(), "s1", "/repo/main.rs", "minimal", "fn main() {}").unwrap();
+
+        let entry = load_in(dir.path(), "s1", "/repo/main.rs", "minimal").unwrap();
+        assert_eq!(entry.output, "fn main() {}");
+        assert!(load_in(dir.path(), "s2", "/repo/main.rs", "minimal").is_none());
+        assert!(load_in(dir.path(), "s1", "/repo/main.rs", "aggressive").is_none());
+    }
+}
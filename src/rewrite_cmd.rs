@@ -0,0 +1,101 @@
+//! `rtk rewrite`: Claude Code PreToolUse hook backend.
+//!
+//! Reads the hook payload on stdin and, when the Bash command has an rtk
+//! equivalent in the discover registry, prints a `hookSpecificOutput` with the
+//! rewritten `updatedInput`. Prints nothing (approve as-is) otherwise.
+
+use crate::discover::registry::rewrite_command;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::Read;
+
+pub fn run(verbose: u8) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read hook input from stdin")?;
+
+    if let Some(output) = rewrite_hook_input(&input) {
+        if verbose > 0 {
+            render_eprintln!(
+                "rtk rewrite: {}",
+                output["hookSpecificOutput"]["updatedInput"]["command"]
+            );
+        }
+        std::println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
+    Ok(())
+}
+
+/// Build the hook response for a PreToolUse payload, or `None` to leave the command alone.
+///
+/// Malformed payloads are never an error: the hook must not block the tool call.
+fn rewrite_hook_input(input: &str) -> Option<Value> {
+    let payload: Value = serde_json::from_str(input).ok()?;
+    let tool_input = payload.get("tool_input")?.as_object()?;
+    let command = tool_input.get("command")?.as_str()?;
+
+    let rewritten = rewrite_command(command)?;
+
+    // Keep every original field, only the command changes
+    let mut updated = tool_input.clone();
+    updated.insert("command".to_string(), Value::String(rewritten));
+
+    Some(json!({
+        "hookSpecificOutput": {
+            "hookEventName": "PreToolUse",
+            "permissionDecision": "allow",
+            "permissionDecisionReason": "RTK auto-rewrite",
+            "updatedInput": updated
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewritten(command: &str) -> Option<String> {
+        let input = json!({
+            "tool_name": "Bash",
+            "tool_input": { "command": command, "description": "desc" }
+        });
+        rewrite_hook_input(&input.to_string()).map(|out| {
+            let updated = &out["hookSpecificOutput"]["updatedInput"];
+            assert_eq!(updated["description"], "desc");
+            updated["command"].as_str().unwrap().to_string()
+        })
+    }
+
+    #[test]
+    fn test_rewrite_hook_output_shape() {
+        let input = r#"{"tool_input":{"command":"git status"}}"#;
+        let out = rewrite_hook_input(input).unwrap();
+        assert_eq!(out["hookSpecificOutput"]["hookEventName"], "PreToolUse");
+        assert_eq!(out["hookSpecificOutput"]["permissionDecision"], "allow");
+        assert_eq!(
+            out["hookSpecificOutput"]["updatedInput"]["command"],
+            "rtk git status"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_preserves_other_fields() {
+        assert_eq!(rewritten("cargo test"), Some("rtk cargo test".to_string()));
+    }
+
+    #[test]
+    fn test_rewrite_no_match() {
+        assert_eq!(rewritten("terraform plan"), None);
+        assert_eq!(rewritten("rtk git status"), None);
+        assert_eq!(rewritten("echo hi"), None);
+    }
+
+    #[test]
+    fn test_rewrite_malformed_input() {
+        assert_eq!(rewrite_hook_input(""), None);
+        assert_eq!(rewrite_hook_input("not json"), None);
+        assert_eq!(rewrite_hook_input(r#"{"tool_input":{}}"#), None);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::parser::{self, truncate_output, LintIssue, LintResult, LintSeverity, ParseResult};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -74,16 +77,33 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     if verbose > 0 {
-        eprintln!("Running: ruff {}", args.join(" "));
+        render_eprintln!("Running: ruff {}", args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run ruff. Is it installed? Try: pip install ruff")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
+    let exit_code = output.status.code().unwrap_or(1);
+
+    if parser::json_requested() && (is_check || is_format) {
+        let result = if is_check {
+            parse_ruff_check_json(&stdout)
+        } else {
+            parse_ruff_format(&raw)
+        };
+        let json = parser::print_json("ruff", result, exit_code);
+        timer.track(
+            &format!("ruff {}", args.join(" ")),
+            &format!("rtk ruff {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
 
     let filtered = if is_check && !stdout.trim().is_empty() {
         filter_ruff_check_json(&stdout)
@@ -94,7 +114,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         raw.trim().to_string()
     };
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("ruff {}", args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("ruff {}", args.join(" ")),
@@ -104,13 +125,59 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &filtered);
 
     Ok(())
 }
 
+/// Canonical result of `ruff check --output-format=json` for `--format json`
+pub fn parse_ruff_check_json(output: &str) -> ParseResult<LintResult> {
+    if output.trim().is_empty() {
+        return ParseResult::Full(LintResult::from_issues(Vec::new()));
+    }
+    match serde_json::from_str::<Vec<RuffDiagnostic>>(output) {
+        Ok(diagnostics) => ParseResult::Full(LintResult::from_issues(
+            diagnostics
+                .into_iter()
+                .map(|d| LintIssue {
+                    file_path: d.filename,
+                    line: d.location.row,
+                    column: d.location.column,
+                    severity: LintSeverity::Error,
+                    rule_id: d.code,
+                    message: d.message,
+                })
+                .collect(),
+        )),
+        Err(_) => ParseResult::Passthrough(truncate_output(output.trim(), 2000)),
+    }
+}
+
+/// Canonical result of `ruff format` for `--format json`: one issue per file
+/// that would be reformatted
+fn parse_ruff_format(output: &str) -> ParseResult<LintResult> {
+    let issues = output
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line.trim();
+            let lower = trimmed.to_lowercase();
+            if !lower.starts_with("would reformat:") {
+                return None;
+            }
+            let file = trimmed.split_once(':').map(|(_, f)| f.trim())?;
+            Some(LintIssue {
+                file_path: file.to_string(),
+                line: 0,
+                column: 0,
+                severity: LintSeverity::Error,
+                rule_id: "format".to_string(),
+                message: "Would reformat".to_string(),
+            })
+        })
+        .collect();
+    ParseResult::Full(LintResult::from_issues(issues))
+}
+
 /// Filter ruff check JSON output - group by rule and file
 pub fn filter_ruff_check_json(output: &str) -> String {
     let diagnostics: Result<Vec<RuffDiagnostic>, _> = serde_json::from_str(output);
@@ -386,6 +453,31 @@ Would reformat: tests/test_utils.py
         assert!(result.contains("3 files already formatted"));
     }
 
+    #[test]
+    fn test_parse_ruff_check_json() {
+        let output = r#"[{"code": "F401", "message": "`os` imported but unused",
+            "location": {"row": 1, "column": 8}, "end_location": null,
+            "filename": "src/main.py", "fix": null}]"#;
+        let result = parse_ruff_check_json(output);
+        assert_eq!(result.tier(), 1);
+        let lint = result.unwrap();
+        assert_eq!(lint.total_issues, 1);
+        assert_eq!(lint.issues[0].rule_id, "F401");
+        assert_eq!(lint.issues[0].line, 1);
+
+        assert_eq!(parse_ruff_check_json("not json").tier(), 3);
+    }
+
+    #[test]
+    fn test_parse_ruff_format() {
+        let output =
+            "Would reformat: src/main.py\n1 file would be reformatted, 3 files left unchanged";
+        let lint = parse_ruff_format(output).unwrap();
+        assert_eq!(lint.total_issues, 1);
+        assert_eq!(lint.issues[0].file_path, "src/main.py");
+        assert_eq!(lint.issues[0].rule_id, "format");
+    }
+
     #[test]
     fn test_compact_path() {
         assert_eq!(
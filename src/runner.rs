@@ -1,3 +1,7 @@
+use crate::budget::{Priority, Sections};
+use crate::stream::{self, StderrMode, StreamFilter};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
@@ -8,49 +12,30 @@ pub fn run_err(command: &str, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Running: {}", command);
+        render_eprintln!("Running: {}", command);
     }
 
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
     } else {
-        Command::new("sh")
-            .args(["-c", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-    }
-    .context("Failed to execute command")?;
+        let mut c = Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
-    let filtered = filter_errors(&raw);
-    let mut rtk = String::new();
+    let mut filter = ErrorFilter::default();
+    let mut outcome = stream::run(&mut cmd, command, &mut filter, StderrMode::Filter)
+        .context("Failed to execute command")?;
 
-    if filtered.is_empty() {
-        if output.status.success() {
-            rtk.push_str("✅ Command completed successfully (no errors)");
-        } else {
-            rtk.push_str(&format!(
-                "❌ Command failed (exit code: {:?})\n",
-                output.status.code()
-            ));
-            let lines: Vec<&str> = raw.lines().collect();
-            for line in lines.iter().rev().take(10).rev() {
-                rtk.push_str(&format!("  {}\n", line));
-            }
-        }
-    } else {
-        rtk.push_str(&filtered);
+    if filter.kept == 0 && outcome.status.success() {
+        outcome.print("✅ Command completed successfully (no errors)");
     }
 
-    println!("{}", rtk);
-    timer.track(command, "rtk run-err", &raw, &rtk);
+    outcome.print_tee_hint();
+    timer.track_streamed(command, "rtk run-err", outcome.raw_bytes, &outcome.filtered);
+    outcome.exit_on_failure();
     Ok(())
 }
 
@@ -59,7 +44,7 @@ pub fn run_test(command: &str, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Running tests: {}", command);
+        render_eprintln!("Running tests: {}", command);
     }
 
     let output = if cfg!(target_os = "windows") {
@@ -67,13 +52,13 @@ pub fn run_test(command: &str, verbose: u8) -> Result<()> {
             .args(["/C", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_timed()
     } else {
         Command::new("sh")
             .args(["-c", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_timed()
     }
     .context("Failed to execute test command")?;
 
@@ -81,70 +66,103 @@ pub fn run_test(command: &str, verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let summary = extract_test_summary(&raw, command);
-    println!("{}", summary);
+    let summary = extract_test_summary(&raw, command).render();
+    render_println!("{}", summary);
+    tee::print_hint(command, &raw, &summary);
     timer.track(command, "rtk run-test", &raw, &summary);
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &summary);
     Ok(())
 }
 
+#[cfg(test)]
 fn filter_errors(output: &str) -> String {
-    lazy_static::lazy_static! {
-        static ref ERROR_PATTERNS: Vec<Regex> = vec![
-            // Generic errors
-            Regex::new(r"(?i)^.*error[\s:\[].*$").unwrap(),
-            Regex::new(r"(?i)^.*\berr\b.*$").unwrap(),
-            Regex::new(r"(?i)^.*warning[\s:\[].*$").unwrap(),
-            Regex::new(r"(?i)^.*\bwarn\b.*$").unwrap(),
-            Regex::new(r"(?i)^.*failed.*$").unwrap(),
-            Regex::new(r"(?i)^.*failure.*$").unwrap(),
-            Regex::new(r"(?i)^.*exception.*$").unwrap(),
-            Regex::new(r"(?i)^.*panic.*$").unwrap(),
-            // Rust specific
-            Regex::new(r"^error\[E\d+\]:.*$").unwrap(),
-            Regex::new(r"^\s*--> .*:\d+:\d+$").unwrap(),
-            // Python
-            Regex::new(r"^Traceback.*$").unwrap(),
-            Regex::new(r#"^\s*File ".*", line \d+.*$"#).unwrap(),
-            // JavaScript/TypeScript
-            Regex::new(r"^\s*at .*:\d+:\d+.*$").unwrap(),
-            // Go
-            Regex::new(r"^.*\.go:\d+:.*$").unwrap(),
-        ];
+    stream::filter_all(ErrorFilter::default(), output)
+}
+
+/// Streaming error filter: error lines and their indented continuations are
+/// printed as they arrive.
+#[derive(Default)]
+struct ErrorFilter {
+    in_error_block: bool,
+    blank_count: usize,
+    kept: usize,
+}
+
+impl ErrorFilter {
+    fn keep(&mut self, line: &str) -> Option<String> {
+        self.kept += 1;
+        Some(line.to_string())
     }
+}
 
-    let mut result = Vec::new();
-    let mut in_error_block = false;
-    let mut blank_count = 0;
+impl StreamFilter for ErrorFilter {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
+        lazy_static::lazy_static! {
+            static ref ERROR_PATTERNS: Vec<Regex> = vec![
+                // Generic errors
+                Regex::new(r"(?i)^.*error[\s:\[].*$").unwrap(),
+                Regex::new(r"(?i)^.*\berr\b.*$").unwrap(),
+                Regex::new(r"(?i)^.*warning[\s:\[].*$").unwrap(),
+                Regex::new(r"(?i)^.*\bwarn\b.*$").unwrap(),
+                Regex::new(r"(?i)^.*failed.*$").unwrap(),
+                Regex::new(r"(?i)^.*failure.*$").unwrap(),
+                Regex::new(r"(?i)^.*exception.*$").unwrap(),
+                Regex::new(r"(?i)^.*panic.*$").unwrap(),
+                // Rust specific
+                Regex::new(r"^error\[E\d+\]:.*$").unwrap(),
+                Regex::new(r"^\s*--> .*:\d+:\d+$").unwrap(),
+                // Python
+                Regex::new(r"^Traceback.*$").unwrap(),
+                Regex::new(r#"^\s*File ".*", line \d+.*$"#).unwrap(),
+                // JavaScript/TypeScript
+                Regex::new(r"^\s*at .*:\d+:\d+.*$").unwrap(),
+                // Go
+                Regex::new(r"^.*\.go:\d+:.*$").unwrap(),
+            ];
+        }
 
-    for line in output.lines() {
         let is_error_line = ERROR_PATTERNS.iter().any(|p| p.is_match(line));
 
         if is_error_line {
-            in_error_block = true;
-            blank_count = 0;
-            result.push(line.to_string());
-        } else if in_error_block {
+            self.in_error_block = true;
+            self.blank_count = 0;
+            self.keep(line)
+        } else if self.in_error_block {
             if line.trim().is_empty() {
-                blank_count += 1;
-                if blank_count >= 2 {
-                    in_error_block = false;
+                self.blank_count += 1;
+                if self.blank_count >= 2 {
+                    self.in_error_block = false;
+                    None
                 } else {
-                    result.push(line.to_string());
+                    self.keep(line)
                 }
             } else if line.starts_with(' ') || line.starts_with('\t') {
                 // Continuation of error
-                result.push(line.to_string());
-                blank_count = 0;
+                self.blank_count = 0;
+                self.keep(line)
             } else {
-                in_error_block = false;
+                self.in_error_block = false;
+                None
             }
+        } else {
+            None
         }
     }
 
-    result.join("\n")
+    fn progress(&self) -> Option<String> {
+        Some(format!("{} error lines", self.kept))
+    }
+
+    fn finish(&mut self) -> String {
+        String::new()
+    }
+
+    fn section(&self) -> Priority {
+        Priority::Error
+    }
 }
 
-fn extract_test_summary(output: &str, command: &str) -> String {
+fn extract_test_summary(output: &str, command: &str) -> Sections {
     let mut result = Vec::new();
     let lines: Vec<&str> = output.lines().collect();
 
@@ -209,6 +227,7 @@ fn extract_test_summary(output: &str, command: &str) -> String {
     }
 
     // Build output
+    let mut sections = Sections::new();
     let mut output = String::new();
 
     if !failures.is_empty() {
@@ -220,6 +239,7 @@ fn extract_test_summary(output: &str, command: &str) -> String {
             output.push_str(&format!("  ... +{} more failures\n", failures.len() - 10));
         }
         output.push('\n');
+        sections.push(Priority::Failure, std::mem::take(&mut output));
     }
 
     if !result.is_empty() {
@@ -227,6 +247,7 @@ fn extract_test_summary(output: &str, command: &str) -> String {
         for r in &result {
             output.push_str(&format!("  {}\n", r));
         }
+        sections.push(Priority::Summary, output);
     } else {
         // Fallback: show last few lines
         output.push_str("📊 OUTPUT (last 5 lines):\n");
@@ -236,9 +257,10 @@ fn extract_test_summary(output: &str, command: &str) -> String {
                 output.push_str(&format!("  {}\n", line));
             }
         }
+        sections.push(Priority::Context, output);
     }
 
-    output
+    sections
 }
 
 #[cfg(test)]
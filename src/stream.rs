@@ -0,0 +1,396 @@
+//! Line-incremental filtering for long-running commands.
+//!
+//! [`run`] spawns the child and feeds each stdout/stderr line to a [`StreamFilter`]
+//! as it arrives. Kept lines are printed right away, raw output goes straight to the
+//! tee store, and only the filter's own (bounded) state stays in memory.
+//!
+//! One-shot `filter_*` functions wrap the same state machines via [`filter_all`].
+//!
+//! Under a `--max-tokens` budget kept lines are held back instead, tagged with
+//! the section the filter says they belong to ([`StreamFilter::section`]), and
+//! printed with the final output by priority, so the budget cuts detail before
+//! the summary and errors.
+//!
+//! With `--timeout` the child runs in its own process group, which is killed at
+//! the deadline; the filter then finishes on whatever was read.
+
+use crate::budget::{self, Priority, Sections};
+use crate::tee::{self, TeeWriter};
+use crate::timeout::{self, Watchdog};
+use crate::utils::RAW_TAIL_LINES;
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Quiet period before a heartbeat is shown on stderr (interactive terminals only)
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Lines buffered between the reader threads and the filter
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Upper bound on lines kept for a single error/failure block
+pub const MAX_BLOCK_LINES: usize = 100;
+
+/// A filter driven one line at a time.
+pub trait StreamFilter {
+    /// Consume one line (without its newline). Returns text to print now.
+    fn feed_line(&mut self, line: &str) -> Option<String>;
+
+    /// Short status for heartbeats while the child is quiet
+    fn progress(&self) -> Option<String> {
+        None
+    }
+
+    /// Remaining output once the input is exhausted (summary, counts)
+    fn finish(&mut self) -> String;
+
+    /// Budget section of the text last returned by [`feed_line`](Self::feed_line)
+    fn section(&self) -> Priority {
+        Priority::Context
+    }
+
+    /// [`finish`](Self::finish) split into budget sections; all summary unless
+    /// the filter says otherwise
+    fn finish_sections(&mut self) -> Sections {
+        let mut sections = Sections::new();
+        sections.push(Priority::Summary, format!("{}\n", self.finish()));
+        sections
+    }
+}
+
+/// Run a filter over complete output, as the one-shot `filter_*` functions do.
+pub fn filter_all<F: StreamFilter>(mut filter: F, output: &str) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for line in output.lines() {
+        if let Some(kept) = filter.feed_line(line) {
+            parts.push(kept);
+        }
+    }
+    let rest = filter.finish();
+    if !rest.is_empty() {
+        parts.push(rest);
+    }
+    parts.join("\n").trim().to_string()
+}
+
+/// Run a filter over complete output and collect what it keeps as budget
+/// sections, as [`run`] does under `--max-tokens`
+#[cfg(test)]
+pub fn sections_all<F: StreamFilter>(mut filter: F, output: &str) -> Sections {
+    let mut sections = Sections::new();
+    for line in output.lines() {
+        if let Some(kept) = filter.feed_line(line) {
+            sections.push(filter.section(), format!("{}\n", kept));
+        }
+    }
+    sections.append(filter.finish_sections());
+    sections
+}
+
+/// Adapter for filters that still need the whole output at once
+pub struct Buffered<F: Fn(&str) -> String> {
+    filter_fn: F,
+    output: String,
+}
+
+impl<F: Fn(&str) -> String> Buffered<F> {
+    pub fn new(filter_fn: F) -> Self {
+        Self {
+            filter_fn,
+            output: String::new(),
+        }
+    }
+}
+
+impl<F: Fn(&str) -> String> StreamFilter for Buffered<F> {
+    fn feed_line(&mut self, line: &str) -> Option<String> {
+        self.output.push_str(line);
+        self.output.push('\n');
+        None
+    }
+
+    fn finish(&mut self) -> String {
+        (self.filter_fn)(&self.output)
+    }
+}
+
+/// What to do with the child's stderr
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StderrMode {
+    /// Feed stderr lines to the filter along with stdout
+    Filter,
+    /// Pass stderr through unfiltered (still saved to the tee store)
+    Echo,
+}
+
+/// Result of a streamed run
+pub struct StreamOutcome {
+    pub status: ExitStatus,
+    /// Total bytes of raw stdout/stderr (for token estimation)
+    pub raw_bytes: usize,
+    /// Everything printed to stdout so far
+    pub filtered: String,
+    /// Last raw lines, for the failure safety net
+    tail: VecDeque<String>,
+    tee: Option<TeeWriter>,
+}
+
+impl StreamOutcome {
+    /// Print the output that went to stdout after the stream ended
+    pub fn print(&mut self, text: &str) {
+        render_println!("{}", text);
+        push_output(&mut self.filtered, text);
+    }
+
+    /// Close the tee file and print its `rtk show` footer if anything was dropped
+    pub fn print_tee_hint(&mut self) {
+        if let Some(writer) = self.tee.take() {
+            if let Some(hint) = writer.finish(self.filtered.len()) {
+                budget::print_footer(&hint);
+            }
+        }
+    }
+
+    /// Exit with the child's code if it failed (see [`crate::utils::exit_on_failure`])
+    pub fn exit_on_failure(&self) {
+        let raw_tail: Vec<&str> = self.tail.iter().map(|l| l.as_str()).collect();
+        crate::utils::exit_on_failure(
+            self.status.code().unwrap_or(1),
+            &raw_tail.join("\n"),
+            &self.filtered,
+        );
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    Stdout,
+    Stderr,
+}
+
+/// Spawn `cmd`, stream its output through `filter` and print what it keeps.
+///
+/// `label` names the run in heartbeats and the tee store. The caller prints any
+/// trailing text via [`StreamOutcome::print`], then calls
+/// [`StreamOutcome::print_tee_hint`].
+pub fn run(
+    cmd: &mut Command,
+    label: &str,
+    filter: &mut dyn StreamFilter,
+    stderr_mode: StderrMode,
+) -> Result<StreamOutcome> {
+    run_in(cmd, label, filter, stderr_mode, &tee::tee_dir())
+}
+
+/// [`run`] with the tee store in `tee_dir`
+fn run_in(
+    cmd: &mut Command,
+    label: &str,
+    filter: &mut dyn StreamFilter,
+    stderr_mode: StderrMode,
+    tee_dir: &Path,
+) -> Result<StreamOutcome> {
+    timeout::prepare(cmd);
+    let mut child = cmd
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", label))?;
+
+    let (tx, rx) = mpsc::sync_channel::<(Source, String)>(CHANNEL_CAPACITY);
+    let stdout = child.stdout.take().context("Failed to capture stdout")?;
+    let stderr = child.stderr.take().context("Failed to capture stderr")?;
+    let stdout_tx = tx.clone();
+    let stdout_reader = thread::spawn(move || read_lines(stdout, Source::Stdout, stdout_tx));
+    let stderr_reader = thread::spawn(move || read_lines(stderr, Source::Stderr, tx));
+
+    let mut watchdog = Watchdog::new(&child);
+    let mut gave_up = false;
+    let mut tee = TeeWriter::create(tee_dir, label);
+    let mut filtered = String::new();
+    let budgeted = budget::limit().is_some();
+    let mut sections = Sections::new();
+    let mut raw_bytes = 0;
+    let mut tail = VecDeque::with_capacity(RAW_TAIL_LINES);
+    let heartbeats = io::stderr().is_terminal();
+    let started = Instant::now();
+    let mut last_output = Instant::now();
+
+    loop {
+        let wait = watchdog
+            .next_wait()
+            .map_or(HEARTBEAT_INTERVAL, |w| w.min(HEARTBEAT_INTERVAL));
+        match rx.recv_timeout(wait) {
+            Ok((source, line)) => {
+                raw_bytes += line.len() + 1;
+                if let Some(writer) = tee.as_mut() {
+                    writer.write_line(&line);
+                }
+                if !line.trim().is_empty() {
+                    if tail.len() == RAW_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line.clone());
+                }
+
+                if matches!(source, Source::Stderr) && stderr_mode == StderrMode::Echo {
+                    render_eprintln!("{}", line);
+                    last_output = Instant::now();
+                    continue;
+                }
+
+                if let Some(kept) = filter.feed_line(&line) {
+                    if budgeted {
+                        sections.push(filter.section(), format!("{}\n", kept));
+                    } else {
+                        render_println!("{}", kept);
+                        let _ = io::stdout().flush();
+                        push_output(&mut filtered, &kept);
+                    }
+                    last_output = Instant::now();
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Give up on pipes held open by processes that left the group
+                if watchdog.check() {
+                    gave_up = true;
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if heartbeats && last_output.elapsed() >= HEARTBEAT_INTERVAL {
+            let status = filter.progress().unwrap_or_else(|| "running".to_string());
+            render_eprintln!(
+                "⏳ {} ({}, {}s)",
+                label,
+                status,
+                started.elapsed().as_secs()
+            );
+            last_output = Instant::now();
+        }
+    }
+
+    if !gave_up {
+        let _ = stdout_reader.join();
+        let _ = stderr_reader.join();
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for {}", label))?;
+
+    if budgeted {
+        sections.append(filter.finish_sections());
+        filtered = sections.render();
+        render_print!("{}", filtered);
+    } else {
+        let rest = filter.finish();
+        if !rest.is_empty() {
+            render_println!("{}", rest);
+            push_output(&mut filtered, &rest);
+        }
+    }
+
+    Ok(StreamOutcome {
+        status,
+        raw_bytes,
+        filtered,
+        tail,
+        tee,
+    })
+}
+
+fn push_output(filtered: &mut String, text: &str) {
+    filtered.push_str(text);
+    filtered.push('\n');
+}
+
+/// Forward lines from a pipe; invalid UTF-8 is replaced rather than dropped
+fn read_lines<R: Read>(pipe: R, source: Source, tx: mpsc::SyncSender<(Source, String)>) {
+    let mut reader = BufReader::new(pipe);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                while matches!(buf.last(), Some(b'\n') | Some(b'\r')) {
+                    buf.pop();
+                }
+                let line = String::from_utf8_lossy(&buf).into_owned();
+                if tx.send((source, line)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps lines containing "keep", summarizes the rest
+    struct KeepFilter {
+        dropped: usize,
+    }
+
+    impl StreamFilter for KeepFilter {
+        fn feed_line(&mut self, line: &str) -> Option<String> {
+            if line.contains("keep") {
+                Some(line.to_string())
+            } else {
+                self.dropped += 1;
+                None
+            }
+        }
+
+        fn finish(&mut self) -> String {
+            format!("{} dropped", self.dropped)
+        }
+    }
+
+    #[test]
+    fn test_filter_all() {
+        let result = filter_all(KeepFilter { dropped: 0 }, "keep a\nnoise\nkeep b\nnoise\n");
+        assert_eq!(result, "keep a\nkeep b\n2 dropped");
+    }
+
+    #[test]
+    fn test_buffered_sees_whole_output() {
+        let result = filter_all(Buffered::new(|s: &str| s.lines().count().to_string()), "a\nb\nc");
+        assert_eq!(result, "3");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_streams_both_pipes() {
+        // Keep the raw output out of the real tee store
+        let tee_dir = tempfile::TempDir::new().unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo keep out; echo noise; echo keep err 1>&2; exit 3"]);
+        let mut filter = KeepFilter { dropped: 0 };
+        let outcome = run_in(
+            &mut cmd,
+            "sh test",
+            &mut filter,
+            StderrMode::Filter,
+            tee_dir.path(),
+        )
+        .unwrap();
+
+        assert_eq!(outcome.status.code(), Some(3));
+        assert!(outcome.filtered.contains("keep out"));
+        assert!(outcome.filtered.contains("keep err"));
+        assert!(outcome.filtered.contains("1 dropped"));
+        assert_eq!(outcome.raw_bytes, "keep out\nnoise\nkeep err\n".len());
+        assert_eq!(outcome.tail.len(), 3);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -9,7 +10,7 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("Running and summarizing: {}", command);
+        render_eprintln!("Running and summarizing: {}", command);
     }
 
     let output = if cfg!(target_os = "windows") {
@@ -17,13 +18,13 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
             .args(["/C", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_timed()
     } else {
         Command::new("sh")
             .args(["-c", command])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .output_timed()
     }
     .context("Failed to execute command")?;
 
@@ -32,8 +33,9 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
     let raw = format!("{}\n{}", stdout, stderr);
 
     let summary = summarize_output(&raw, command, output.status.success());
-    println!("{}", summary);
+    render_println!("{}", summary);
     timer.track(command, "rtk summary", &raw, &summary);
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &raw, &summary);
     Ok(())
 }
 
@@ -0,0 +1,594 @@
+//! Raw output store for recovering lines dropped by filters.
+//!
+//! Filtered wrappers save the unfiltered stdout/stderr of each run under
+//! `~/.local/share/rtk/tee/<id>.log` and print a one-line footer with the id.
+//! `rtk show <id>` reads it back, optionally narrowed with `--grep`/`--lines`.
+//!
+//! Retention follows `tracking.history_days`; size is capped by the `[tee]`
+//! section of config.toml.
+
+use crate::budget;
+use crate::config::{Config, TeeConfig};
+use crate::context;
+use crate::redact;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// First line of every stored file: `# rtk-tee <rfc3339> <command>`
+const HEADER_PREFIX: &str = "# rtk-tee ";
+
+/// Directory holding the raw outputs (`RTK_TEE_DIR` overrides the default)
+pub fn tee_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("RTK_TEE_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rtk")
+        .join("tee")
+}
+
+/// Save raw output and print a `rtk show <id>` footer when the filter dropped content.
+///
+/// Never fails: the store is best-effort and must not break the wrapped command.
+pub fn print_hint(command: &str, raw: &str, filtered: &str) {
+    if let Some(hint) = hint(command, raw, filtered) {
+        budget::print_footer(&hint);
+    }
+}
+
+/// Same as [`print_hint`] but returns the footer instead of printing it.
+pub fn hint(command: &str, raw: &str, filtered: &str) -> Option<String> {
+    if raw.trim().is_empty() || raw.len() <= filtered.len() {
+        return None;
+    }
+    save(command, raw).map(|id| format_hint(&id))
+}
+
+fn format_hint(id: &str) -> String {
+    format!("[full output: rtk show {}]", id)
+}
+
+/// Store raw output and return its id, or `None` if the store is disabled or unwritable.
+pub fn save(command: &str, raw: &str) -> Option<String> {
+    let config = Config::load().unwrap_or_default();
+    if !config.tee.enabled {
+        return None;
+    }
+    let history_days = u64::from(config.tracking.history_days);
+    save_in(
+        &tee_dir(),
+        &redact::text(command),
+        &redact::text(raw),
+        &config.tee,
+        history_days,
+    )
+    .ok()
+}
+
+fn save_in(
+    dir: &Path,
+    command: &str,
+    raw: &str,
+    limits: &TeeConfig,
+    history_days: u64,
+) -> Result<String> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create tee directory: {}", dir.display()))?;
+
+    let mut id = generate_id(command, 0);
+    let mut attempt = 1;
+    while dir.join(format!("{}.log", id)).exists() {
+        id = generate_id(command, attempt);
+        attempt += 1;
+    }
+
+    let body = cap_size(raw, (limits.max_file_kb * 1024) as usize);
+    let content = format!(
+        "{}{} {}\n{}",
+        HEADER_PREFIX,
+        chrono::Utc::now().to_rfc3339(),
+        command.replace('\n', " "),
+        body
+    );
+    let path = dir.join(format!("{}.log", id));
+    fs::write(&path, content)
+        .with_context(|| format!("Failed to write tee file: {}", path.display()))?;
+
+    prune(dir, history_days, limits.max_total_mb * 1024 * 1024)?;
+    Ok(id)
+}
+
+/// Incremental variant of [`save`] for streamed commands.
+///
+/// Lines go to disk as they arrive; once the head budget (half of `max_file_kb`)
+/// is spent, only a bounded tail is kept in memory and written on [`finish`](Self::finish).
+pub struct TeeWriter {
+    id: String,
+    dir: PathBuf,
+    file: Option<BufWriter<File>>,
+    history_days: u64,
+    max_total_bytes: u64,
+    head_budget: usize,
+    head_bytes: usize,
+    tail: VecDeque<String>,
+    tail_bytes: usize,
+    dropped_bytes: usize,
+    total_bytes: usize,
+    /// Private key block being held back (see [`redact::stream`])
+    key_block: redact::KeyBlock,
+}
+
+impl TeeWriter {
+    /// Open a new store file in `dir` (normally [`tee_dir`]), or `None` if the
+    /// store is disabled or unwritable
+    pub fn create(dir: &Path, command: &str) -> Option<Self> {
+        let config = Config::load().unwrap_or_default();
+        if !config.tee.enabled {
+            return None;
+        }
+        let history_days = u64::from(config.tracking.history_days);
+        Self::create_in(dir, &redact::text(command), &config.tee, history_days).ok()
+    }
+
+    fn create_in(
+        dir: &Path,
+        command: &str,
+        limits: &TeeConfig,
+        history_days: u64,
+    ) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create tee directory: {}", dir.display()))?;
+
+        let mut id = generate_id(command, 0);
+        let mut attempt = 1;
+        while dir.join(format!("{}.log", id)).exists() {
+            id = generate_id(command, attempt);
+            attempt += 1;
+        }
+
+        let path = dir.join(format!("{}.log", id));
+        let mut file = BufWriter::new(
+            File::create(&path)
+                .with_context(|| format!("Failed to create tee file: {}", path.display()))?,
+        );
+        writeln!(
+            file,
+            "{}{} {}",
+            HEADER_PREFIX,
+            chrono::Utc::now().to_rfc3339(),
+            command.replace('\n', " ")
+        )?;
+
+        let max_file_bytes = (limits.max_file_kb * 1024) as usize;
+        Ok(Self {
+            id,
+            dir: dir.to_path_buf(),
+            file: Some(file),
+            history_days,
+            max_total_bytes: limits.max_total_mb * 1024 * 1024,
+            head_budget: if max_file_bytes == 0 {
+                usize::MAX
+            } else {
+                max_file_bytes / 2
+            },
+            head_bytes: 0,
+            tail: VecDeque::new(),
+            tail_bytes: 0,
+            dropped_bytes: 0,
+            total_bytes: 0,
+            key_block: redact::KeyBlock::new(),
+        })
+    }
+
+    /// Append one line of raw output (without its newline)
+    pub fn write_line(&mut self, line: &str) {
+        let text = redact::stream(&format!("{}\n", line), &mut self.key_block).into_owned();
+        self.store(text);
+    }
+
+    /// Store redacted text: zero or more complete lines
+    fn store(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        let len = text.len();
+        self.total_bytes += len;
+
+        if self.head_bytes + len <= self.head_budget {
+            self.head_bytes += len;
+            if let Some(file) = self.file.as_mut() {
+                let _ = write!(file, "{}", text);
+            }
+            return;
+        }
+
+        self.tail.push_back(text);
+        self.tail_bytes += len;
+        while self.tail_bytes > self.head_budget {
+            match self.tail.pop_front() {
+                Some(old) => {
+                    self.tail_bytes -= old.len();
+                    self.dropped_bytes += old.len();
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Close the file and return the footer, or `None` (file removed) when the
+    /// filter kept everything anyway.
+    pub fn finish(mut self, filtered_len: usize) -> Option<String> {
+        let held = redact::finish(&mut self.key_block);
+        self.store(held);
+        if self.total_bytes == 0 || self.total_bytes <= filtered_len {
+            return None;
+        }
+
+        let mut file = self.file.take()?;
+        if self.dropped_bytes > 0 {
+            let _ = writeln!(
+                file,
+                "... [rtk: {} bytes truncated] ...",
+                self.dropped_bytes
+            );
+        }
+        for text in &self.tail {
+            let _ = write!(file, "{}", text);
+        }
+        file.flush().ok()?;
+        drop(file);
+
+        let _ = prune(&self.dir, self.history_days, self.max_total_bytes);
+        Some(format_hint(&self.id))
+    }
+}
+
+impl Drop for TeeWriter {
+    /// Unfinished or unneeded outputs are not worth keeping
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            drop(file);
+            let _ = fs::remove_file(self.dir.join(format!("{}.log", self.id)));
+        }
+    }
+}
+
+/// Short, mostly-unique id: 8 hex chars from command + time + pid
+fn generate_id(command: &str, salt: u32) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let mut hasher = DefaultHasher::new();
+    command.hash(&mut hasher);
+    nanos.hash(&mut hasher);
+    std::process::id().hash(&mut hasher);
+    salt.hash(&mut hasher);
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Keep head and tail of oversized output (errors usually sit at the end)
+fn cap_size(raw: &str, max_bytes: usize) -> String {
+    if max_bytes == 0 || raw.len() <= max_bytes {
+        return raw.to_string();
+    }
+
+    let half = max_bytes / 2;
+    let mut head_end = half;
+    while !raw.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = raw.len() - half;
+    while !raw.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+
+    format!(
+        "{}\n... [rtk: {} bytes truncated] ...\n{}",
+        &raw[..head_end],
+        tail_start - head_end,
+        &raw[tail_start..]
+    )
+}
+
+/// Drop files past the retention window, then oldest files until under the size cap
+fn prune(dir: &Path, history_days: u64, max_total_bytes: u64) -> Result<()> {
+    // history_days = 0 disables age-based pruning (the size cap still applies)
+    let cutoff = match history_days {
+        0 => SystemTime::UNIX_EPOCH,
+        days => SystemTime::now()
+            .checked_sub(Duration::from_secs(days * 24 * 3600))
+            .unwrap_or(SystemTime::UNIX_EPOCH),
+    };
+
+    let mut files: Vec<(PathBuf, SystemTime, u64)> = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("log") {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        if modified < cutoff {
+            let _ = fs::remove_file(&path);
+        } else {
+            files.push((path, modified, meta.len()));
+        }
+    }
+
+    let mut total: u64 = files.iter().map(|(_, _, len)| len).sum();
+    if max_total_bytes == 0 || total <= max_total_bytes {
+        return Ok(());
+    }
+
+    files.sort_by_key(|(_, modified, _)| *modified);
+    for (path, _, len) in files {
+        if total <= max_total_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+        }
+    }
+
+    Ok(())
+}
+
+/// `rtk show <id>`: print a stored raw output
+pub fn run_show(id: &str, grep: Option<&str>, lines: Option<&str>, verbose: u8) -> Result<()> {
+    let out = show_in(&tee_dir(), id, grep, lines, verbose)?;
+    context::raw(out.as_bytes());
+    Ok(())
+}
+
+fn show_in(
+    dir: &Path,
+    id: &str,
+    grep: Option<&str>,
+    lines: Option<&str>,
+    verbose: u8,
+) -> Result<String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid output id: {}", id);
+    }
+
+    let path = dir.join(format!("{}.log", id));
+    let content = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "No stored output for id {} (expired or pruned?): {}",
+            id,
+            path.display()
+        )
+    })?;
+
+    let (header, body) = content.split_once('\n').unwrap_or((&content, ""));
+    if verbose > 0 {
+        if let Some(meta) = header.strip_prefix(HEADER_PREFIX) {
+            render_eprintln!("{}", meta);
+        }
+    }
+    // Lines keep their own terminators, so the output is the stored bytes
+    let body: Vec<&str> = body.split_inclusive('\n').collect();
+
+    let (start, end) = match lines {
+        Some(spec) => parse_line_range(spec, body.len())?,
+        None => (1, body.len()),
+    };
+
+    let pattern = grep
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid --grep pattern: {}", p)))
+        .transpose()?;
+
+    let mut out = String::new();
+    for (idx, line) in body.iter().enumerate() {
+        let line_no = idx + 1;
+        if line_no < start || line_no > end {
+            continue;
+        }
+        match &pattern {
+            Some(re) => {
+                let text = line.trim_end_matches('\n').trim_end_matches('\r');
+                if re.is_match(text) {
+                    out.push_str(&format!("{}:{}\n", line_no, text));
+                }
+            }
+            None => out.push_str(line),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Parse `A-B`, `A-`, `-B` or `A` into an inclusive 1-based range
+fn parse_line_range(spec: &str, total: usize) -> Result<(usize, usize)> {
+    let parse = |s: &str| -> Result<usize> {
+        s.trim()
+            .parse::<usize>()
+            .with_context(|| format!("Invalid line number in --lines {}", spec))
+    };
+
+    let (start, end) = match spec.split_once('-') {
+        Some((a, b)) => {
+            let start = if a.trim().is_empty() { 1 } else { parse(a)? };
+            let end = if b.trim().is_empty() { total } else { parse(b)? };
+            (start, end)
+        }
+        None => {
+            let n = parse(spec)?;
+            (n, n)
+        }
+    };
+
+    if start == 0 || start > end {
+        anyhow::bail!("Invalid --lines range: {} (expected A-B, 1-based)", spec);
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn limits() -> TeeConfig {
+        TeeConfig::default()
+    }
+
+    #[test]
+    fn test_save_and_show_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let raw = "Compiling foo\nerror: boom\nwarning: meh\n";
+        let id = save_in(dir.path(), "cargo build", raw, &limits(), 90).unwrap();
+
+        assert_eq!(id.len(), 8);
+        let shown = show_in(dir.path(), &id, None, None, 0).unwrap();
+        assert_eq!(shown, raw);
+    }
+
+    #[test]
+    fn test_show_grep_and_lines() {
+        let dir = TempDir::new().unwrap();
+        let raw = "a ok\nb error\nc ok\nd error\n";
+        let id = save_in(dir.path(), "cmd", raw, &limits(), 90).unwrap();
+
+        let grepped = show_in(dir.path(), &id, Some("error"), None, 0).unwrap();
+        assert_eq!(grepped, "2:b error\n4:d error\n");
+
+        let ranged = show_in(dir.path(), &id, None, Some("2-3"), 0).unwrap();
+        assert_eq!(ranged, "b error\nc ok\n");
+
+        let both = show_in(dir.path(), &id, Some("error"), Some("3-"), 0).unwrap();
+        assert_eq!(both, "4:d error\n");
+    }
+
+    #[test]
+    fn test_show_is_byte_exact() {
+        let dir = TempDir::new().unwrap();
+        let raw = "✅ done \x1b[31mred\x1b[0m\r\n\n  indented\tline\nno final newline";
+        let id = save_in(dir.path(), "cmd", raw, &limits(), 90).unwrap();
+        assert_eq!(show_in(dir.path(), &id, None, None, 0).unwrap(), raw);
+        assert_eq!(
+            show_in(dir.path(), &id, None, Some("2-"), 0).unwrap(),
+            "\n  indented\tline\nno final newline"
+        );
+        assert_eq!(
+            show_in(dir.path(), &id, Some("red"), None, 0).unwrap(),
+            "1:✅ done \x1b[31mred\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_show_rejects_bad_id() {
+        let dir = TempDir::new().unwrap();
+        assert!(show_in(dir.path(), "../etc/passwd", None, None, 0).is_err());
+        assert!(show_in(dir.path(), "deadbeef", None, None, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("3-7", 10).unwrap(), (3, 7));
+        assert_eq!(parse_line_range("5-", 10).unwrap(), (5, 10));
+        assert_eq!(parse_line_range("-4", 10).unwrap(), (1, 4));
+        assert_eq!(parse_line_range("6", 10).unwrap(), (6, 6));
+        assert!(parse_line_range("0-3", 10).is_err());
+        assert!(parse_line_range("7-3", 10).is_err());
+        assert!(parse_line_range("x-3", 10).is_err());
+    }
+
+    #[test]
+    fn test_cap_size_keeps_head_and_tail() {
+        let raw = format!("{}{}", "a".repeat(1000), "z".repeat(1000));
+        let capped = cap_size(&raw, 200);
+        assert!(capped.starts_with(&"a".repeat(100)));
+        assert!(capped.ends_with(&"z".repeat(100)));
+        assert!(capped.contains("1800 bytes truncated"));
+    }
+
+    #[test]
+    fn test_cap_size_multibyte() {
+        let raw = "é".repeat(500);
+        let capped = cap_size(&raw, 101);
+        assert!(capped.contains("bytes truncated"));
+    }
+
+    #[test]
+    fn test_prune_enforces_total_size() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("{:08x}.log", i)), "x".repeat(1000)).unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        prune(dir.path(), 90, 2500).unwrap();
+
+        let remaining: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().collect();
+        assert_eq!(remaining.len(), 2);
+        // Newest files survive
+        assert!(dir.path().join("00000004.log").exists());
+        assert!(dir.path().join("00000003.log").exists());
+    }
+
+    #[test]
+    fn test_writer_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = TeeWriter::create_in(dir.path(), "cargo test", &limits(), 90).unwrap();
+        writer.write_line("running 3 tests");
+        writer.write_line("test a ... ok");
+        let hint = writer.finish(3).unwrap();
+
+        let id = hint
+            .trim_start_matches("[full output: rtk show ")
+            .trim_end_matches(']');
+        let shown = show_in(dir.path(), id, None, None, 0).unwrap();
+        assert_eq!(shown, "running 3 tests\ntest a ... ok\n");
+    }
+
+    #[test]
+    fn test_writer_keeps_head_and_tail() {
+        let dir = TempDir::new().unwrap();
+        let limits = TeeConfig {
+            enabled: true,
+            max_file_kb: 1,
+            max_total_mb: 50,
+        };
+        let mut writer = TeeWriter::create_in(dir.path(), "cmd", &limits, 90).unwrap();
+        for i in 0..1000 {
+            writer.write_line(&format!("line {:04}", i));
+        }
+        let hint = writer.finish(0).unwrap();
+        let id = hint
+            .trim_start_matches("[full output: rtk show ")
+            .trim_end_matches(']');
+
+        let shown = show_in(dir.path(), id, None, None, 0).unwrap();
+        assert!(shown.starts_with("line 0000\n"));
+        assert!(shown.ends_with("line 0999\n"));
+        assert!(shown.contains("bytes truncated"));
+        assert!(shown.len() < 1200);
+    }
+
+    #[test]
+    fn test_writer_discarded_when_nothing_dropped() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = TeeWriter::create_in(dir.path(), "cmd", &limits(), 90).unwrap();
+        writer.write_line("ok");
+        assert_eq!(writer.finish(100), None);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_hint_skipped_when_nothing_dropped() {
+        assert_eq!(hint("cmd", "short", "short but longer"), None);
+        assert_eq!(hint("cmd", "  \n", ""), None);
+    }
+}
@@ -0,0 +1,407 @@
+//! Per-invocation time limit (`--timeout`, `[timeouts]` in config).
+//!
+//! With a limit set, every child is started in its own process group. When the
+//! deadline passes the group gets SIGTERM, then SIGKILL after [`GRACE`], so test
+//! runners and build tools cannot leave workers behind. Output read up to that
+//! point is returned as usual: filters run on the partial output, and
+//! [`crate::utils::exit`] labels the result and exits with [`EXIT_CODE`].
+//!
+//! A child in its own group no longer gets the terminal's Ctrl-C, so SIGINT and
+//! SIGTERM sent to rtk are forwarded to the group of the running child.
+
+use crate::context;
+use std::io::{self, Read};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Exit code after a timeout (same as coreutils `timeout`)
+pub const EXIT_CODE: i32 = 124;
+
+/// Time between SIGTERM and SIGKILL, and between SIGKILL and giving up on the pipes
+pub const GRACE: Duration = Duration::from_secs(2);
+
+static DEADLINE: OnceLock<Option<Instant>> = OnceLock::new();
+static EXPIRED: AtomicBool = AtomicBool::new(false);
+/// Process group of the running child, which gets rtk's SIGINT/SIGTERM (0 = none)
+static FORWARD_GROUP: AtomicI32 = AtomicI32::new(0);
+
+/// Parse "90", "90s", "500ms", "5m" or "1h" (a bare number is seconds)
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (e.g. 30s, 5m, 1h)", text))?;
+    let seconds = match unit.trim() {
+        "" | "s" | "sec" => value,
+        "ms" => value / 1000.0,
+        "m" | "min" => value * 60.0,
+        "h" => value * 3600.0,
+        other => {
+            return Err(format!(
+                "unknown duration unit '{}' (use ms, s, m or h)",
+                other
+            ))
+        }
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration '{}' is out of range", text))
+}
+
+/// "90s", "5m" or "1h30m" for messages
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let mut out = String::new();
+    if h > 0 {
+        out.push_str(&format!("{}h", h));
+    }
+    if m > 0 {
+        out.push_str(&format!("{}m", m));
+    }
+    if s > 0 || out.is_empty() {
+        out.push_str(&format!("{}s", s));
+    }
+    out
+}
+
+/// When the current run must stop, counted from the first child started
+pub fn deadline() -> Option<Instant> {
+    *DEADLINE.get_or_init(|| context::get().timeout.map(|limit| Instant::now() + limit))
+}
+
+/// Whether a child was killed because the deadline passed
+pub fn expired() -> bool {
+    EXPIRED.load(Ordering::Relaxed)
+}
+
+/// Label printed at the end of a timed-out run
+pub fn label() -> String {
+    let limit = context::get().timeout.unwrap_or_default();
+    format!(
+        "[rtk: timed out after {}; output above is partial]",
+        format_duration(limit)
+    )
+}
+
+/// Start the child in its own process group when a deadline applies
+pub fn prepare(cmd: &mut Command) {
+    #[cfg(unix)]
+    if deadline().is_some() {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+        forward_signals();
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Install the handler that passes SIGINT/SIGTERM on to [`FORWARD_GROUP`]
+#[cfg(unix)]
+fn forward_signals() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let handler = forward_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        // SAFETY: the handler only makes async-signal-safe calls
+        unsafe {
+            libc::signal(libc::SIGINT, handler);
+            libc::signal(libc::SIGTERM, handler);
+        }
+    });
+}
+
+#[cfg(unix)]
+extern "C" fn forward_signal(signal: libc::c_int) {
+    let group = FORWARD_GROUP.load(Ordering::Relaxed);
+    // SAFETY: killpg, signal and raise are async-signal-safe
+    unsafe {
+        if group > 0 {
+            libc::killpg(group, signal);
+        } else {
+            // No child running: die of the signal as without the handler
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+}
+
+/// Escalates from SIGTERM to SIGKILL once the deadline has passed
+pub struct Watchdog {
+    pid: u32,
+    deadline: Option<Instant>,
+    terminated_at: Option<Instant>,
+    killed: bool,
+}
+
+impl Watchdog {
+    /// Watch a child started after [`prepare`]
+    pub fn new(child: &Child) -> Self {
+        let deadline = deadline();
+        if deadline.is_some() {
+            FORWARD_GROUP.store(child.id() as i32, Ordering::Relaxed);
+        }
+        Self {
+            pid: child.id(),
+            deadline,
+            terminated_at: None,
+            killed: false,
+        }
+    }
+
+    /// Time until [`check`](Self::check) has something to do (`None` = never)
+    pub fn next_wait(&self) -> Option<Duration> {
+        let next = match self.terminated_at {
+            None => self.deadline?,
+            Some(at) if !self.killed => at + GRACE,
+            Some(at) => at + GRACE * 2,
+        };
+        Some(next.saturating_duration_since(Instant::now()))
+    }
+
+    /// Signal the process group if its time is up. Returns true once the
+    /// child had its full grace period after SIGKILL and should be given up on.
+    pub fn check(&mut self) -> bool {
+        let now = Instant::now();
+        match self.terminated_at {
+            None => {
+                if self.deadline.is_some_and(|d| now >= d) {
+                    EXPIRED.store(true, Ordering::Relaxed);
+                    signal_group(self.pid, false);
+                    self.terminated_at = Some(now);
+                }
+                false
+            }
+            Some(at) if !self.killed => {
+                if now >= at + GRACE {
+                    signal_group(self.pid, true);
+                    self.killed = true;
+                }
+                false
+            }
+            Some(at) => now >= at + GRACE * 2,
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    /// Stop forwarding signals to a group that is gone
+    fn drop(&mut self) {
+        let _ = FORWARD_GROUP.compare_exchange(
+            self.pid as i32,
+            0,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, kill: bool) {
+    let signal = if kill { libc::SIGKILL } else { libc::SIGTERM };
+    // SAFETY: plain syscall; the group was created by `prepare` for this child
+    unsafe {
+        libc::killpg(pid as libc::pid_t, signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(pid: u32, _kill: bool) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+/// `output()` / `status()` that honor the deadline
+pub trait TimedCommand {
+    /// Like [`Command::output`]; on timeout returns what was read so far
+    fn output_timed(&mut self) -> io::Result<Output>;
+    /// Like [`Command::status`]
+    fn status_timed(&mut self) -> io::Result<ExitStatus>;
+}
+
+impl TimedCommand for Command {
+    fn output_timed(&mut self) -> io::Result<Output> {
+        if deadline().is_none() {
+            return self.output();
+        }
+        prepare(self);
+        let mut child = self
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let (tx, rx) = mpsc::channel();
+        let stdout = child.stdout.take().map(|pipe| read_pipe(pipe, tx.clone()));
+        let stderr = child.stderr.take().map(|pipe| read_pipe(pipe, tx.clone()));
+        let status = wait_all(child, tx, rx, 2)?;
+
+        let take = |buf: Option<Arc<Mutex<Vec<u8>>>>| {
+            buf.and_then(|b| b.lock().ok().map(|mut v| std::mem::take(&mut *v)))
+                .unwrap_or_default()
+        };
+        Ok(Output {
+            status,
+            stdout: take(stdout),
+            stderr: take(stderr),
+        })
+    }
+
+    fn status_timed(&mut self) -> io::Result<ExitStatus> {
+        if deadline().is_none() {
+            return self.status();
+        }
+        prepare(self);
+        let child = self.spawn()?;
+        let (tx, rx) = mpsc::channel();
+        wait_all(child, tx, rx, 0)
+    }
+}
+
+enum Event {
+    Exited(io::Result<ExitStatus>),
+    Closed,
+}
+
+/// Copy a pipe into a shared buffer, so a partial read survives a give-up
+fn read_pipe<R: Read + Send + 'static>(
+    mut pipe: R,
+    tx: mpsc::Sender<Event>,
+) -> Arc<Mutex<Vec<u8>>> {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let shared = Arc::clone(&buf);
+    thread::spawn(move || {
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if let Ok(mut out) = shared.lock() {
+                        out.extend_from_slice(&chunk[..n]);
+                    }
+                }
+            }
+        }
+        let _ = tx.send(Event::Closed);
+    });
+    buf
+}
+
+/// Wait for the child and `pipes` reader threads, enforcing the deadline
+fn wait_all(
+    mut child: Child,
+    tx: mpsc::Sender<Event>,
+    rx: mpsc::Receiver<Event>,
+    pipes: usize,
+) -> io::Result<ExitStatus> {
+    let mut watchdog = Watchdog::new(&child);
+    thread::spawn(move || {
+        let _ = tx.send(Event::Exited(child.wait()));
+    });
+
+    let mut status = None;
+    let mut open = pipes;
+    let mut gave_up = false;
+    while status.is_none() || open > 0 {
+        let event = match watchdog.next_wait().filter(|_| !gave_up) {
+            Some(wait) => rx.recv_timeout(wait),
+            None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok(Event::Exited(result)) => status = Some(result?),
+            Ok(Event::Closed) => open -= 1,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Grandchildren that left the group may keep the pipes open
+                gave_up = watchdog.check();
+                if gave_up && status.is_some() {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    status.ok_or_else(|| io::Error::other("child exited without a status"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(5400)));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(90)), "1m30s");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h");
+        assert_eq!(format_duration(Duration::from_millis(250)), "250ms");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_watchdog_kills_group() {
+        use std::os::unix::process::CommandExt;
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo started; sleep 30 & sleep 30; echo never"]);
+        cmd.process_group(0);
+        let mut child = cmd.stdout(Stdio::piped()).spawn().unwrap();
+        let mut stdout = io::BufReader::new(child.stdout.take().unwrap());
+        let mut out = String::new();
+        io::BufRead::read_line(&mut stdout, &mut out).unwrap();
+
+        let mut watchdog = Watchdog {
+            pid: child.id(),
+            deadline: Some(Instant::now()),
+            terminated_at: None,
+            killed: false,
+        };
+        let started = Instant::now();
+        assert!(!watchdog.check());
+        let status = child.wait().unwrap();
+        stdout.read_to_string(&mut out).unwrap();
+
+        // The background sleep shares the group, so the pipe closes too
+        assert!(!status.success());
+        assert_eq!(out, "started\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sigint_forwarded_to_group() {
+        use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        forward_signals();
+        FORWARD_GROUP.store(child.id() as i32, Ordering::Relaxed);
+        // SAFETY: the handler forwards to the child's group instead of exiting
+        unsafe {
+            libc::raise(libc::SIGINT);
+        }
+        let status = child.wait().unwrap();
+        FORWARD_GROUP.store(0, Ordering::Relaxed);
+        assert_eq!(status.signal(), Some(libc::SIGINT));
+    }
+}
@@ -9,6 +9,8 @@
 //! - Storage: SQLite database (~/.local/share/rtk/tracking.db)
 //! - Retention: 90-day automatic cleanup
 //! - Metrics: Input/output tokens, savings %, execution time
+//! - Token counts: chars/4 estimate or an exact bundled BPE (`tracking.tokenizer`),
+//!   recorded per row
 //!
 //! # Quick Start
 //!
@@ -29,17 +31,16 @@
 //!
 //! See [docs/tracking.md](../docs/tracking.md) for full documentation.
 
-use anyhow::Result;
+use crate::config::Tokenizer;
+use crate::context;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
-use serde::Serialize;
+use rusqlite::{params, Connection, ToSql, TransactionBehavior};
+use serde::{Deserialize, Serialize};
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-/// Number of days to retain tracking history before automatic cleanup.
-const HISTORY_DAYS: i64 = 90;
-
 /// Main tracking interface for recording and querying command history.
 ///
 /// Manages SQLite database connection and provides methods for:
@@ -56,10 +57,11 @@ const HISTORY_DAYS: i64 = 90;
 /// # Examples
 ///
 /// ```no_run
+/// use rtk::config::Tokenizer;
 /// use rtk::tracking::Tracker;
 ///
 /// let tracker = Tracker::new()?;
-/// tracker.record("ls -la", "rtk ls", 1000, 200, 50)?;
+/// tracker.record("ls -la", "rtk ls", 1000, 200, 50, Tokenizer::Estimate)?;
 ///
 /// let summary = tracker.get_summary()?;
 /// println!("Total saved: {} tokens", summary.total_saved);
@@ -67,8 +69,193 @@ const HISTORY_DAYS: i64 = 90;
 /// ```
 pub struct Tracker {
     conn: Connection,
+    path: PathBuf,
+    scope: Scope,
+}
+
+/// Restricts every query of a [`Tracker`] to a project, session, time range
+/// or set of commands (`rtk gain --project`, `--session`, `--since`,
+/// `--until`, `--command`). The default covers all rows.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Scope {
+    /// Project root as recorded (git toplevel, else working directory)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Session id from `RTK_SESSION_ID`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// Inclusive lower bound (RFC3339 UTC)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Exclusive upper bound (RFC3339 UTC)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// SQLite GLOB pattern matched against the rtk command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl Scope {
+    pub fn is_all(&self) -> bool {
+        self.project.is_none()
+            && self.session.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.command.is_none()
+    }
+
+    fn params(&self) -> Vec<(&str, &dyn ToSql)> {
+        vec![
+            (":project", &self.project),
+            (":session", &self.session),
+            (":since", &self.since),
+            (":until", &self.until),
+            (":command", &self.command),
+        ]
+    }
 }
 
+/// Distribution of savings over the runs in scope, for the `rtk gain
+/// --command` drill-down.
+#[derive(Debug, Serialize)]
+pub struct CommandDetail {
+    /// Runs matched
+    pub runs: usize,
+    /// Distinct rtk commands among them
+    pub commands: usize,
+    pub saved_tokens: usize,
+    /// Median and 90th percentile savings percentage
+    pub savings_p50: f64,
+    pub savings_p90: f64,
+    /// Median and 90th percentile execution time (milliseconds)
+    pub time_p50_ms: u64,
+    pub time_p90_ms: u64,
+    pub total_time_ms: u64,
+    /// Runs with the lowest savings, worst first
+    pub worst: Vec<RunRecord>,
+}
+
+/// One tracked run, as listed by the drill-down.
+#[derive(Debug, Serialize)]
+pub struct RunRecord {
+    /// RFC3339 UTC timestamp
+    pub timestamp: String,
+    /// Command as typed (e.g., "git log -20")
+    pub original_cmd: String,
+    /// rtk command that ran it (e.g., "rtk git log -20")
+    pub rtk_cmd: String,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub saved_tokens: usize,
+    pub savings_pct: f64,
+    pub exec_time_ms: u64,
+}
+
+/// One line of a team bundle (`rtk gain export --bundle`): a tracked run
+/// labelled with the machine and user it came from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleRow {
+    pub host: String,
+    pub user: String,
+    /// RFC3339 UTC timestamp
+    pub timestamp: String,
+    pub original_cmd: String,
+    pub rtk_cmd: String,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub saved_tokens: usize,
+    pub savings_pct: f64,
+    pub exec_time_ms: u64,
+    pub tokenizer: String,
+    pub timed_out: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_root: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse_tier: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_warnings: Vec<String>,
+}
+
+/// Parser outcome of one tracked run, for `rtk gain --health`.
+#[derive(Debug)]
+pub struct ParseRun {
+    /// RFC3339 UTC timestamp
+    pub timestamp: String,
+    pub rtk_cmd: String,
+    /// 1 full, 2 degraded, 3 passthrough
+    pub tier: u8,
+    pub warnings: Vec<String>,
+}
+
+/// Savings of one project, for `rtk gain --by-project`.
+#[derive(Debug, Serialize)]
+pub struct ProjectStats {
+    /// Project root, or "(unknown)" for rows recorded before attribution
+    pub project: String,
+    pub commands: usize,
+    pub saved_tokens: usize,
+    pub savings_pct: f64,
+    pub sessions: usize,
+}
+
+/// Storage details shown by `rtk gain --db-info`.
+#[derive(Debug, Serialize)]
+pub struct DbInfo {
+    /// Database file
+    pub path: PathBuf,
+    /// Schema version stored in the database
+    pub schema_version: u32,
+    /// Latest schema version this binary knows
+    pub latest_version: u32,
+    /// Rows in the `commands` table
+    pub rows: usize,
+    /// Database file size in bytes
+    pub size_bytes: u64,
+    /// Oldest and newest recorded timestamps (RFC3339)
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+}
+
+/// Schema migrations, applied in order inside one transaction: running
+/// `MIGRATIONS[n]` takes the database to version `n + 1`. Append new
+/// migrations at the end; never edit one that has shipped.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE IF NOT EXISTS commands (
+        id INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        original_cmd TEXT NOT NULL,
+        rtk_cmd TEXT NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        saved_tokens INTEGER NOT NULL,
+        savings_pct REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_timestamp ON commands(timestamp);",
+    // 2: execution time
+    "ALTER TABLE commands ADD COLUMN exec_time_ms INTEGER DEFAULT 0;",
+    // 3: rows recorded before tokenizer selection were estimated
+    "ALTER TABLE commands ADD COLUMN tokenizer TEXT DEFAULT 'estimate';",
+    // 4: runs stopped by --timeout
+    "ALTER TABLE commands ADD COLUMN timed_out INTEGER DEFAULT 0;",
+    // 5: per-project and per-session attribution
+    "ALTER TABLE commands ADD COLUMN project_root TEXT;
+    ALTER TABLE commands ADD COLUMN session_id TEXT;
+    CREATE INDEX IF NOT EXISTS idx_project ON commands(project_root);",
+    // 6: rows imported from team bundles; local rows leave both NULL
+    "ALTER TABLE commands ADD COLUMN host TEXT;
+    ALTER TABLE commands ADD COLUMN user TEXT;
+    CREATE UNIQUE INDEX IF NOT EXISTS idx_origin ON commands(host, timestamp, rtk_cmd);",
+    // 7: parser tier (1 full, 2 degraded, 3 passthrough) and warnings as a JSON array
+    "ALTER TABLE commands ADD COLUMN parse_tier INTEGER;
+    ALTER TABLE commands ADD COLUMN parse_warnings TEXT;",
+];
+
+/// Latest schema version known to this binary
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
 /// Individual command record from tracking history.
 ///
 /// Contains timestamp, command name, and savings metrics for a single execution.
@@ -108,6 +295,10 @@ pub struct GainSummary {
     pub by_command: Vec<(String, usize, usize, f64, u64)>,
     /// Last 30 days of activity: (date, saved_tokens)
     pub by_day: Vec<(String, usize)>,
+    /// Commands per token counter: (tokenizer, count), most used first
+    pub by_tokenizer: Vec<(String, usize)>,
+    /// Commands stopped by `--timeout`
+    pub timed_out: usize,
 }
 
 /// Daily statistics for token savings and execution metrics.
@@ -200,9 +391,8 @@ pub struct MonthStats {
 impl Tracker {
     /// Create a new tracker instance.
     ///
-    /// Opens or creates the SQLite database at the platform-specific location.
-    /// Automatically creates the `commands` table if it doesn't exist and runs
-    /// any necessary schema migrations.
+    /// Opens or creates the SQLite database at the platform-specific location
+    /// and applies any pending schema migrations (see `schema_version`).
     ///
     /// # Errors
     ///
@@ -211,6 +401,7 @@ impl Tracker {
     /// - Cannot create parent directories
     /// - Cannot open/create SQLite database
     /// - Schema creation/migration fails
+    /// - The database was written by a newer rtk (schema version too recent)
     ///
     /// # Examples
     ///
@@ -225,56 +416,70 @@ impl Tracker {
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
+        Self::open(&db_path)
+    }
 
-        let conn = Connection::open(&db_path)?;
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS commands (
-                id INTEGER PRIMARY KEY,
-                timestamp TEXT NOT NULL,
-                original_cmd TEXT NOT NULL,
-                rtk_cmd TEXT NOT NULL,
-                input_tokens INTEGER NOT NULL,
-                output_tokens INTEGER NOT NULL,
-                saved_tokens INTEGER NOT NULL,
-                savings_pct REAL NOT NULL
-            )",
-            [],
-        )?;
+    /// Open the database given with `--db`, or the default one.
+    /// Unlike [`open`](Self::open), a missing `--db` file is an error.
+    pub fn open_or_default(db: Option<&Path>) -> Result<Self> {
+        match db {
+            Some(path) if !path.exists() => {
+                anyhow::bail!("Database not found: {}", path.display())
+            }
+            Some(path) => Self::open(path),
+            None => Self::new(),
+        }
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp ON commands(timestamp)",
-            [],
-        )?;
+    /// Open the database at `path`, creating it if needed, and bring its
+    /// schema up to date.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut conn = Connection::open(path)?;
+        migrate(&mut conn).with_context(|| format!("Tracking database {}", path.display()))?;
+        Ok(Self {
+            conn,
+            path: path.to_path_buf(),
+            scope: Scope::default(),
+        })
+    }
 
-        // Migration: add exec_time_ms column if it doesn't exist
-        let _ = conn.execute(
-            "ALTER TABLE commands ADD COLUMN exec_time_ms INTEGER DEFAULT 0",
-            [],
-        );
+    /// Restrict all following queries to `scope`.
+    pub fn with_scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
 
-        Ok(Self { conn })
+    /// Filters applied to queries (see [`with_scope`](Self::with_scope)).
+    pub fn scope(&self) -> &Scope {
+        &self.scope
     }
 
     /// Record a command execution with token counts and timing.
     ///
     /// Calculates savings metrics and stores the record in the database.
+    /// Runs stopped by `--timeout` are flagged as timed out. Command lines are
+    /// stored with secrets redacted. Each row is attributed to the project root
+    /// (git toplevel, else cwd) and to `RTK_SESSION_ID` when set, and carries
+    /// the parser tier and warnings noted during the run, if any.
     /// Automatically cleans up records older than 90 days after insertion.
     ///
     /// # Arguments
     ///
     /// - `original_cmd`: The standard command (e.g., "ls -la")
     /// - `rtk_cmd`: The RTK command used (e.g., "rtk ls")
-    /// - `input_tokens`: Estimated tokens from standard command output
-    /// - `output_tokens`: Actual tokens from RTK output
+    /// - `input_tokens`: Tokens in the standard command output
+    /// - `output_tokens`: Tokens in the RTK output
     /// - `exec_time_ms`: Execution time in milliseconds
+    /// - `tokenizer`: Counter that produced both token counts
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// use rtk::config::Tokenizer;
     /// use rtk::tracking::Tracker;
     ///
     /// let tracker = Tracker::new()?;
-    /// tracker.record("ls -la", "rtk ls", 1000, 200, 50)?;
+    /// tracker.record("ls -la", "rtk ls", 1000, 200, 50, Tokenizer::Estimate)?;
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn record(
@@ -284,6 +489,7 @@ impl Tracker {
         input_tokens: usize,
         output_tokens: usize,
         exec_time_ms: u64,
+        tokenizer: Tokenizer,
     ) -> Result<()> {
         let saved = input_tokens.saturating_sub(output_tokens);
         let pct = if input_tokens > 0 {
@@ -292,18 +498,33 @@ impl Tracker {
             0.0
         };
 
+        let (parse_tier, parse_warnings) = match crate::parser::noted() {
+            Some((tier, warnings)) if warnings.is_empty() => (Some(tier), None),
+            Some((tier, warnings)) => {
+                let warnings: Vec<_> = warnings.iter().map(|w| crate::redact::text(w)).collect();
+                (Some(tier), Some(serde_json::to_string(&warnings)?))
+            }
+            None => (None, None),
+        };
+
         self.conn.execute(
-            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, tokenizer, timed_out, project_root, session_id, parse_tier, parse_warnings)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
             params![
                 Utc::now().to_rfc3339(),
-                original_cmd,
-                rtk_cmd,
+                crate::redact::text(original_cmd),
+                crate::redact::text(rtk_cmd),
                 input_tokens as i64,
                 output_tokens as i64,
                 saved as i64,
                 pct,
-                exec_time_ms as i64
+                exec_time_ms as i64,
+                tokenizer.name(),
+                crate::timeout::expired(),
+                crate::utils::project_root().display().to_string(),
+                crate::utils::env_session_id(),
+                parse_tier,
+                parse_warnings
             ],
         )?;
 
@@ -311,8 +532,43 @@ impl Tracker {
         Ok(())
     }
 
+    /// Insert a row and roll it back: fails if the database is read-only or locked.
+    /// Used by `rtk init --doctor`.
+    pub fn check_writable(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "BEGIN IMMEDIATE;
+             INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct)
+             VALUES ('', 'rtk doctor', 'rtk doctor', 0, 0, 0, 0);
+             ROLLBACK;",
+        )?;
+        Ok(())
+    }
+
+    /// Schema version, size and row count of the database.
+    pub fn db_info(&self) -> Result<DbInfo> {
+        let (rows, oldest, newest) = self.conn.query_row(
+            "SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM commands",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(DbInfo {
+            path: self.path.clone(),
+            schema_version: schema_version(&self.conn)?,
+            latest_version: SCHEMA_VERSION,
+            rows: rows as usize,
+            size_bytes: std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0),
+            oldest,
+            newest,
+        })
+    }
+
+    /// Drop rows older than `tracking.history_days` (0 keeps everything).
     fn cleanup_old(&self) -> Result<()> {
-        let cutoff = Utc::now() - chrono::Duration::days(HISTORY_DAYS);
+        let history_days = context::get().history_days;
+        if history_days == 0 {
+            return Ok(());
+        }
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(history_days));
         self.conn.execute(
             "DELETE FROM commands WHERE timestamp < ?1",
             params![cutoff.to_rfc3339()],
@@ -340,28 +596,37 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_summary(&self) -> Result<GainSummary> {
+        self.get_summary_top(10)
+    }
+
+    /// [`get_summary`](Self::get_summary) with the `top` commands by tokens
+    /// saved instead of 10 (`rtk gain --top`).
+    pub fn get_summary_top(&self, top: usize) -> Result<GainSummary> {
         let mut total_commands = 0usize;
         let mut total_input = 0usize;
         let mut total_output = 0usize;
         let mut total_saved = 0usize;
         let mut total_time_ms = 0u64;
+        let mut total_timed_out = 0usize;
 
-        let mut stmt = self.conn.prepare(
-            "SELECT input_tokens, output_tokens, saved_tokens, exec_time_ms FROM commands",
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT input_tokens, output_tokens, saved_tokens, exec_time_ms, COALESCE(timed_out, 0) FROM commands"),
         )?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
             Ok((
                 row.get::<_, i64>(0)? as usize,
                 row.get::<_, i64>(1)? as usize,
                 row.get::<_, i64>(2)? as usize,
                 row.get::<_, i64>(3)? as u64,
+                row.get::<_, bool>(4)?,
             ))
         })?;
 
         for row in rows {
-            let (input, output, saved, time_ms) = row?;
+            let (input, output, saved, time_ms, timed_out) = row?;
             total_commands += 1;
+            total_timed_out += usize::from(timed_out);
             total_input += input;
             total_output += output;
             total_saved += saved;
@@ -380,8 +645,9 @@ impl Tracker {
             0
         };
 
-        let by_command = self.get_by_command()?;
+        let by_command = self.get_by_command(top)?;
         let by_day = self.get_by_day()?;
+        let by_tokenizer = self.get_by_tokenizer()?;
 
         Ok(GainSummary {
             total_commands,
@@ -393,19 +659,39 @@ impl Tracker {
             avg_time_ms,
             by_command,
             by_day,
+            by_tokenizer,
+            timed_out: total_timed_out,
         })
     }
 
-    fn get_by_command(&self) -> Result<Vec<(String, usize, usize, f64, u64)>> {
-        let mut stmt = self.conn.prepare(
+    fn get_by_tokenizer(&self) -> Result<Vec<(String, usize)>> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT COALESCE(tokenizer, 'estimate'), COUNT(*)
+             FROM commands
+             GROUP BY 1
+             ORDER BY 2 DESC",
+        ))?;
+
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn get_by_command(&self, top: usize) -> Result<Vec<(String, usize, usize, f64, u64)>> {
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT rtk_cmd, COUNT(*), SUM(saved_tokens), AVG(savings_pct), AVG(exec_time_ms)
              FROM commands
              GROUP BY rtk_cmd
              ORDER BY SUM(saved_tokens) DESC
-             LIMIT 10",
-        )?;
+             LIMIT :top",
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let top = top as i64;
+        let mut params = self.scope.params();
+        params.push((":top", &top));
+        let rows = stmt.query_map(params.as_slice(), |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, i64>(1)? as usize,
@@ -419,15 +705,15 @@ impl Tracker {
     }
 
     fn get_by_day(&self) -> Result<Vec<(String, usize)>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT DATE(timestamp), SUM(saved_tokens)
              FROM commands
              GROUP BY DATE(timestamp)
              ORDER BY DATE(timestamp) DESC
              LIMIT 30",
-        )?;
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
         })?;
 
@@ -436,6 +722,230 @@ impl Tracker {
         Ok(result)
     }
 
+    /// Savings per project root, most tokens saved first.
+    ///
+    /// Rows recorded before project attribution are grouped as "(unknown)".
+    pub fn get_by_project(&self) -> Result<Vec<ProjectStats>> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT COALESCE(project_root, '(unknown)'), COUNT(*), SUM(saved_tokens),
+                    SUM(input_tokens), COUNT(DISTINCT session_id)
+             FROM commands
+             GROUP BY 1
+             ORDER BY 3 DESC",
+        ))?;
+
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
+            let saved = row.get::<_, i64>(2)? as usize;
+            let input = row.get::<_, i64>(3)? as usize;
+            Ok(ProjectStats {
+                project: row.get(0)?,
+                commands: row.get::<_, i64>(1)? as usize,
+                saved_tokens: saved,
+                savings_pct: if input > 0 {
+                    (saved as f64 / input as f64) * 100.0
+                } else {
+                    0.0
+                },
+                sessions: row.get::<_, i64>(4)? as usize,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Savings distribution and worst runs over the rows in scope, usually
+    /// narrowed to one command with [`Scope::command`].
+    ///
+    /// Returns `None` when no run matches.
+    pub fn get_command_detail(&self, worst: usize) -> Result<Option<CommandDetail>> {
+        let mut runs = self.get_runs()?;
+        if runs.is_empty() {
+            return Ok(None);
+        }
+
+        let commands: std::collections::HashSet<&str> =
+            runs.iter().map(|r| r.rtk_cmd.as_str()).collect();
+        let commands = commands.len();
+        let mut savings: Vec<f64> = runs.iter().map(|r| r.savings_pct).collect();
+        savings.sort_by(f64::total_cmp);
+        let mut times: Vec<u64> = runs.iter().map(|r| r.exec_time_ms).collect();
+        times.sort_unstable();
+
+        let detail = CommandDetail {
+            runs: runs.len(),
+            commands,
+            saved_tokens: runs.iter().map(|r| r.saved_tokens).sum(),
+            savings_p50: percentile(&savings, 50),
+            savings_p90: percentile(&savings, 90),
+            time_p50_ms: percentile(&times, 50),
+            time_p90_ms: percentile(&times, 90),
+            total_time_ms: times.iter().sum(),
+            worst: {
+                // Lowest savings first; among equals, the largest outputs
+                runs.sort_by(|a, b| {
+                    a.savings_pct
+                        .total_cmp(&b.savings_pct)
+                        .then(b.input_tokens.cmp(&a.input_tokens))
+                });
+                runs.truncate(worst);
+                runs
+            },
+        };
+        Ok(Some(detail))
+    }
+
+    /// Every run in scope, oldest first.
+    pub fn get_runs(&self) -> Result<Vec<RunRecord>> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens,
+                    savings_pct, COALESCE(exec_time_ms, 0)
+             FROM commands
+             ORDER BY timestamp",
+        ))?;
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
+            Ok(RunRecord {
+                timestamp: row.get(0)?,
+                original_cmd: row.get(1)?,
+                rtk_cmd: row.get(2)?,
+                input_tokens: row.get::<_, i64>(3)? as usize,
+                output_tokens: row.get::<_, i64>(4)? as usize,
+                saved_tokens: row.get::<_, i64>(5)? as usize,
+                savings_pct: row.get(6)?,
+                exec_time_ms: row.get::<_, i64>(7)? as u64,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Rows in scope as bundle rows, oldest first. Rows recorded locally are
+    /// labelled `host` and `user`; imported rows keep their own labels.
+    pub fn get_bundle_rows(&self, host: &str, user: &str) -> Result<Vec<BundleRow>> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT COALESCE(host, :host), COALESCE(user, :user), timestamp, original_cmd, rtk_cmd,
+                    input_tokens, output_tokens, saved_tokens, savings_pct,
+                    COALESCE(exec_time_ms, 0), COALESCE(tokenizer, 'estimate'),
+                    COALESCE(timed_out, 0), project_root, session_id, parse_tier, parse_warnings
+             FROM commands
+             ORDER BY timestamp",
+        ))?;
+
+        let mut params = self.scope.params();
+        params.push((":host", &host));
+        params.push((":user", &user));
+        let rows = stmt.query_map(params.as_slice(), |row| {
+            Ok(BundleRow {
+                host: row.get(0)?,
+                user: row.get(1)?,
+                timestamp: row.get(2)?,
+                original_cmd: row.get(3)?,
+                rtk_cmd: row.get(4)?,
+                input_tokens: row.get::<_, i64>(5)? as usize,
+                output_tokens: row.get::<_, i64>(6)? as usize,
+                saved_tokens: row.get::<_, i64>(7)? as usize,
+                savings_pct: row.get(8)?,
+                exec_time_ms: row.get::<_, i64>(9)? as u64,
+                tokenizer: row.get(10)?,
+                timed_out: row.get(11)?,
+                project_root: row.get(12)?,
+                session_id: row.get(13)?,
+                parse_tier: row.get(14)?,
+                parse_warnings: warnings_from_json(row.get(15)?),
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Insert bundle rows in one transaction, skipping rows already present
+    /// (same host, timestamp and rtk command). Returns the number inserted.
+    pub fn import_bundle(&mut self, rows: &[BundleRow]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO commands (host, user, timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, tokenizer, timed_out, project_root, session_id, parse_tier, parse_warnings)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            )?;
+            for row in rows {
+                let warnings = if row.parse_warnings.is_empty() {
+                    None
+                } else {
+                    Some(serde_json::to_string(&row.parse_warnings)?)
+                };
+                inserted += stmt.execute(params![
+                    row.host,
+                    row.user,
+                    row.timestamp,
+                    row.original_cmd,
+                    row.rtk_cmd,
+                    row.input_tokens as i64,
+                    row.output_tokens as i64,
+                    row.saved_tokens as i64,
+                    row.savings_pct,
+                    row.exec_time_ms as i64,
+                    row.tokenizer,
+                    row.timed_out,
+                    row.project_root,
+                    row.session_id,
+                    row.parse_tier,
+                    warnings
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Runs in scope that went through a parser, oldest first.
+    pub fn get_parse_runs(&self) -> Result<Vec<ParseRun>> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT timestamp, rtk_cmd, parse_tier, parse_warnings
+             FROM commands
+             ORDER BY timestamp",
+        ))?;
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
+            // Rows recorded without a parser (or before tiers were tracked)
+            let Some(tier) = row.get::<_, Option<u8>>(2)? else {
+                return Ok(None);
+            };
+            Ok(Some(ParseRun {
+                timestamp: row.get(0)?,
+                rtk_cmd: row.get(1)?,
+                tier,
+                warnings: warnings_from_json(row.get(3)?),
+            }))
+        })?;
+
+        let mut runs = Vec::new();
+        for row in rows {
+            runs.extend(row?);
+        }
+        Ok(runs)
+    }
+
+    /// Runs per savings-percentage decile: `[0]` counts 0-10%, `[9]` 90-100%.
+    pub fn get_savings_histogram(&self) -> Result<[usize; 10]> {
+        let mut stmt = self.conn.prepare(&scoped(
+            "SELECT MIN(MAX(CAST(savings_pct / 10 AS INTEGER), 0), 9), COUNT(*)
+             FROM commands
+             GROUP BY 1",
+        ))?;
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
+            Ok((
+                row.get::<_, i64>(0)? as usize,
+                row.get::<_, i64>(1)? as usize,
+            ))
+        })?;
+
+        let mut histogram = [0; 10];
+        for row in rows {
+            let (bucket, count) = row?;
+            histogram[bucket] = count;
+        }
+        Ok(histogram)
+    }
+
     /// Get daily statistics for all recorded days.
     ///
     /// Returns one [`DayStats`] per day with commands executed, tokens saved,
@@ -455,7 +965,7 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_all_days(&self) -> Result<Vec<DayStats>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT
                 DATE(timestamp) as date,
                 COUNT(*) as commands,
@@ -466,9 +976,9 @@ impl Tracker {
              FROM commands
              GROUP BY DATE(timestamp)
              ORDER BY DATE(timestamp) DESC",
-        )?;
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -520,7 +1030,7 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_by_week(&self) -> Result<Vec<WeekStats>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT
                 DATE(timestamp, 'weekday 0', '-6 days') as week_start,
                 DATE(timestamp, 'weekday 0') as week_end,
@@ -532,9 +1042,9 @@ impl Tracker {
              FROM commands
              GROUP BY week_start
              ORDER BY week_start DESC",
-        )?;
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
             let input = row.get::<_, i64>(3)? as usize;
             let saved = row.get::<_, i64>(5)? as usize;
             let commands = row.get::<_, i64>(2)? as usize;
@@ -587,7 +1097,7 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_by_month(&self) -> Result<Vec<MonthStats>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT
                 strftime('%Y-%m', timestamp) as month,
                 COUNT(*) as commands,
@@ -598,9 +1108,9 @@ impl Tracker {
              FROM commands
              GROUP BY month
              ORDER BY month DESC",
-        )?;
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(self.scope.params().as_slice(), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -655,14 +1165,17 @@ impl Tracker {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn get_recent(&self, limit: usize) -> Result<Vec<CommandRecord>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare(&scoped(
             "SELECT timestamp, rtk_cmd, saved_tokens, savings_pct
              FROM commands
              ORDER BY timestamp DESC
-             LIMIT ?1",
-        )?;
+             LIMIT :limit",
+        ))?;
 
-        let rows = stmt.query_map(params![limit as i64], |row| {
+        let limit = limit as i64;
+        let mut params = self.scope.params();
+        params.push((":limit", &limit));
+        let rows = stmt.query_map(params.as_slice(), |row| {
             Ok(CommandRecord {
                 timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
                     .map(|dt| dt.with_timezone(&Utc))
@@ -677,7 +1190,110 @@ impl Tracker {
     }
 }
 
-fn get_db_path() -> Result<PathBuf> {
+/// Nearest-rank percentile of sorted, non-empty `values`
+fn percentile<T: Copy>(values: &[T], pct: usize) -> T {
+    let rank = (values.len() * pct).div_ceil(100).max(1);
+    values[rank - 1]
+}
+
+/// Decode the `parse_warnings` column (a JSON array, or NULL).
+fn warnings_from_json(json: Option<String>) -> Vec<String> {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Add the [`Scope`] filter to a query on `commands` (bound by `Scope::params`).
+fn scoped(sql: &str) -> String {
+    sql.replacen(
+        "FROM commands",
+        "FROM commands
+             WHERE (:project IS NULL OR project_root = :project)
+               AND (:session IS NULL OR session_id = :session)
+               AND (:since IS NULL OR timestamp >= :since)
+               AND (:until IS NULL OR timestamp < :until)
+               AND (:command IS NULL OR rtk_cmd GLOB :command)",
+        1,
+    )
+}
+
+/// Bring the schema up to [`SCHEMA_VERSION`], refusing databases written by a
+/// newer rtk.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER NOT NULL,
+            applied_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    if schema_version(conn)? == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    // IMMEDIATE takes the write lock up front, so two rtk processes starting
+    // together cannot both apply the same migration
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut version = schema_version(&tx)?;
+    if version == 0 {
+        version = legacy_version(&tx)?;
+        if version > 0 {
+            tx.execute(
+                "INSERT INTO schema_version (version, applied_at) VALUES (?1, ?2)",
+                params![version, Utc::now().to_rfc3339()],
+            )?;
+        }
+    }
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "schema version {} was written by a newer rtk (this one supports up to {}); \
+             upgrade rtk, or set RTK_DB_PATH to use another database",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let target = index as u32 + 1;
+        tx.execute_batch(migration)
+            .with_context(|| format!("Migration to schema version {} failed", target))?;
+        tx.execute(
+            "INSERT INTO schema_version (version, applied_at) VALUES (?1, ?2)",
+            params![target, Utc::now().to_rfc3339()],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Version of a database created before `schema_version` existed, inferred
+/// from the columns the old ad hoc migrations added (0 = empty database).
+fn legacy_version(conn: &Connection) -> Result<u32> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('commands')")?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let version = if columns.is_empty() {
+        0
+    } else if columns.iter().any(|c| c == "timed_out") {
+        4
+    } else if columns.iter().any(|c| c == "tokenizer") {
+        3
+    } else if columns.iter().any(|c| c == "exec_time_ms") {
+        2
+    } else {
+        1
+    };
+    Ok(version)
+}
+
+pub fn get_db_path() -> Result<PathBuf> {
     // Priority 1: Environment variable RTK_DB_PATH
     if let Ok(custom_path) = std::env::var("RTK_DB_PATH") {
         return Ok(PathBuf::from(custom_path));
@@ -697,8 +1313,8 @@ fn get_db_path() -> Result<PathBuf> {
 
 /// Estimate token count from text using ~4 chars = 1 token heuristic.
 ///
-/// This is a fast approximation suitable for tracking purposes.
-/// For precise counts, integrate with your LLM's tokenizer API.
+/// This is a fast approximation and the default for tracking. Set
+/// `tracking.tokenizer` to a bundled BPE vocabulary for exact counts.
 ///
 /// # Formula
 ///
@@ -719,11 +1335,41 @@ pub fn estimate_tokens(text: &str) -> usize {
     (text.len() as f64 / 4.0).ceil() as usize
 }
 
+impl Tokenizer {
+    /// Name stored in the `tokenizer` column
+    pub fn name(self) -> &'static str {
+        match self {
+            Tokenizer::Estimate => "estimate",
+            Tokenizer::Cl100k => "cl100k",
+            Tokenizer::O200k => "o200k",
+        }
+    }
+
+    /// Count tokens in `text`. BPE vocabularies are embedded in the binary and
+    /// loaded on first use.
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            Tokenizer::Estimate => estimate_tokens(text),
+            Tokenizer::Cl100k => tiktoken_rs::cl100k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Tokenizer::O200k => tiktoken_rs::o200k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+        }
+    }
+}
+
+/// Whether a stored tokenizer name is an exact count
+pub fn is_exact_tokenizer(name: &str) -> bool {
+    name != Tokenizer::Estimate.name()
+}
+
 /// Helper struct for timing command execution
 /// Helper for timing command execution and tracking results.
 ///
 /// Preferred API for tracking commands. Automatically measures execution time
-/// and records token savings. Use instead of the deprecated [`track`] function.
+/// and records token savings.
 ///
 /// # Examples
 ///
@@ -788,16 +1434,16 @@ impl TimedExecution {
     /// ```
     pub fn track(&self, original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
-        let input_tokens = estimate_tokens(input);
-        let output_tokens = estimate_tokens(output);
 
-        if let Ok(tracker) = Tracker::new() {
+        if let Some(tracker) = recorder() {
+            let tokenizer = context::get().tokenizer;
             let _ = tracker.record(
                 original_cmd,
                 rtk_cmd,
-                input_tokens,
-                output_tokens,
+                tokenizer.count(input),
+                tokenizer.count(output),
                 elapsed_ms,
+                tokenizer,
             );
         }
     }
@@ -825,12 +1471,62 @@ impl TimedExecution {
     pub fn track_passthrough(&self, original_cmd: &str, rtk_cmd: &str) {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
         // input_tokens=0, output_tokens=0 won't dilute savings statistics
-        if let Ok(tracker) = Tracker::new() {
-            let _ = tracker.record(original_cmd, rtk_cmd, 0, 0, elapsed_ms);
+        if let Some(tracker) = recorder() {
+            let _ = tracker.record(
+                original_cmd,
+                rtk_cmd,
+                0,
+                0,
+                elapsed_ms,
+                context::get().tokenizer,
+            );
+        }
+    }
+
+    /// Track a streamed command whose raw output was never held in memory.
+    ///
+    /// Same as [`track`](Self::track), but the input side is given as the
+    /// number of raw bytes seen while streaming.
+    ///
+    /// # Arguments
+    ///
+    /// - `original_cmd`: Standard command (e.g., "cargo build")
+    /// - `rtk_cmd`: RTK command used (e.g., "rtk cargo build")
+    /// - `input_bytes`: Total bytes of raw stdout/stderr
+    /// - `output`: RTK command output (for token estimation)
+    pub fn track_streamed(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input_bytes: usize,
+        output: &str,
+    ) {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        let input_tokens = (input_bytes as f64 / 4.0).ceil() as usize;
+        // The raw side can only be estimated, so both sides are
+        let output_tokens = estimate_tokens(output);
+
+        if let Some(tracker) = recorder() {
+            let _ = tracker.record(
+                original_cmd,
+                rtk_cmd,
+                input_tokens,
+                output_tokens,
+                elapsed_ms,
+                Tokenizer::Estimate,
+            );
         }
     }
 }
 
+/// Database handle for recording, or `None` when `tracking.enabled = false`.
+fn recorder() -> Option<Tracker> {
+    if !context::get().tracking_enabled {
+        return None;
+    }
+    Tracker::new().ok()
+}
+
 /// Format OsString args for tracking display.
 ///
 /// Joins arguments with spaces, converting each to UTF-8 (lossy).
@@ -852,41 +1548,6 @@ pub fn args_display(args: &[OsString]) -> String {
         .join(" ")
 }
 
-/// Track a command execution (legacy function, use [`TimedExecution`] for new code).
-///
-/// # Deprecation Notice
-///
-/// This function is deprecated. Use [`TimedExecution`] instead for automatic
-/// timing and cleaner API.
-///
-/// # Arguments
-///
-/// - `original_cmd`: Standard command (e.g., "ls -la")
-/// - `rtk_cmd`: RTK command used (e.g., "rtk ls")
-/// - `input`: Standard command output (for token estimation)
-/// - `output`: RTK command output (for token estimation)
-///
-/// # Migration
-///
-/// ```no_run
-/// # use rtk::tracking::{track, TimedExecution};
-/// // Old (deprecated)
-/// track("ls -la", "rtk ls", "input", "output");
-///
-/// // New (preferred)
-/// let timer = TimedExecution::start();
-/// timer.track("ls -la", "rtk ls", "input", "output");
-/// ```
-#[deprecated(note = "Use TimedExecution instead")]
-pub fn track(original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
-    let input_tokens = estimate_tokens(input);
-    let output_tokens = estimate_tokens(output);
-
-    if let Ok(tracker) = Tracker::new() {
-        let _ = tracker.record(original_cmd, rtk_cmd, input_tokens, output_tokens, 0);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -921,7 +1582,7 @@ mod tests {
         let test_cmd = format!("rtk git status test_{}", std::process::id());
 
         tracker
-            .record("git status", &test_cmd, 100, 20, 50)
+            .record("git status", &test_cmd, 100, 20, 50, Tokenizer::Estimate)
             .expect("Failed to record");
 
         let recent = tracker.get_recent(10).expect("Failed to get recent");
@@ -948,12 +1609,12 @@ mod tests {
 
         // Record one real command with 80% savings
         tracker
-            .record("cmd1", &cmd1, 1000, 200, 10)
+            .record("cmd1", &cmd1, 1000, 200, 10, Tokenizer::Estimate)
             .expect("Failed to record cmd1");
 
         // Record passthrough (0, 0)
         tracker
-            .record("cmd2", &cmd2, 0, 0, 5)
+            .record("cmd2", &cmd2, 0, 0, 5, Tokenizer::Estimate)
             .expect("Failed to record passthrough");
 
         // Verify both records exist in recent history
@@ -1037,4 +1698,201 @@ mod tests {
         let db_path = get_db_path().expect("Failed to get db path");
         assert!(db_path.ends_with("rtk/history.db"));
     }
+
+    // 9. Exact tokenizers differ from the estimate and are recorded per row
+    #[test]
+    fn test_tokenizer_counts_and_recorded_name() {
+        assert_eq!(Tokenizer::Estimate.count("hello world"), 3);
+        assert_eq!(Tokenizer::Cl100k.count("hello world"), 2);
+        assert_eq!(Tokenizer::O200k.count("hello world"), 2);
+        assert_eq!(Tokenizer::Cl100k.count(""), 0);
+        assert!(is_exact_tokenizer("cl100k"));
+        assert!(!is_exact_tokenizer("estimate"));
+
+        let tracker = Tracker::new().expect("Failed to create tracker");
+        let cmd = format!("rtk tokenizer_test_{}", std::process::id());
+        tracker
+            .record("cmd", &cmd, 10, 2, 1, Tokenizer::Cl100k)
+            .expect("Failed to record");
+        let summary = tracker.get_summary().expect("Failed to get summary");
+        assert!(summary
+            .by_tokenizer
+            .iter()
+            .any(|(name, count)| name == "cl100k" && *count >= 1));
+    }
+
+    // 10. Fresh and pre-versioning databases end at the latest schema
+    #[test]
+    fn test_migrations_fresh_and_legacy() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let fresh = Tracker::open(&dir.path().join("fresh.db")).unwrap();
+        let info = fresh.db_info().unwrap();
+        assert_eq!(info.schema_version, SCHEMA_VERSION);
+        assert_eq!(info.rows, 0);
+        fresh
+            .record("ls", "rtk ls", 10, 2, 1, Tokenizer::Estimate)
+            .unwrap();
+        assert_eq!(fresh.db_info().unwrap().rows, 1);
+
+        // Table as created before exec_time_ms existed, with one row
+        let legacy = dir.path().join("legacy.db");
+        Connection::open(&legacy)
+            .unwrap()
+            .execute_batch(&format!(
+                "{} INSERT INTO commands VALUES (1, '2025-01-01T00:00:00Z', 'ls', 'rtk ls', 10, 2, 8, 80.0);",
+                MIGRATIONS[0]
+            ))
+            .unwrap();
+        let tracker = Tracker::open(&legacy).unwrap();
+        let info = tracker.db_info().unwrap();
+        assert_eq!(info.schema_version, SCHEMA_VERSION);
+        assert_eq!(info.rows, 1);
+        assert_eq!(tracker.get_recent(1).unwrap()[0].saved_tokens, 8);
+        // Reopening is a no-op
+        drop(tracker);
+        assert!(Tracker::open(&legacy).is_ok());
+    }
+
+    // 11. Scope filters every query; by-project groups unattributed rows
+    #[test]
+    fn test_scope_and_by_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scope.db");
+        let tracker = Tracker::open(&path).unwrap();
+        for (project, session, saved) in [
+            (Some("/repo/a"), Some("s1"), 10),
+            (Some("/repo/a"), Some("s2"), 20),
+            (Some("/repo/b"), Some("s1"), 40),
+            (None, None, 5),
+        ] {
+            tracker
+                .conn
+                .execute(
+                    "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, project_root, session_id)
+                     VALUES (?1, 'ls', 'rtk ls', ?2, 0, ?2, 100.0, ?3, ?4)",
+                    params![Utc::now().to_rfc3339(), saved, project, session],
+                )
+                .unwrap();
+        }
+
+        let projects = tracker.get_by_project().unwrap();
+        let names: Vec<&str> = projects.iter().map(|p| p.project.as_str()).collect();
+        assert_eq!(names, ["/repo/b", "/repo/a", "(unknown)"]);
+        assert_eq!(projects[1].sessions, 2);
+
+        let project_a = Tracker::open(&path).unwrap().with_scope(Scope {
+            project: Some("/repo/a".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(project_a.get_summary().unwrap().total_saved, 30);
+        assert_eq!(project_a.get_recent(10).unwrap().len(), 2);
+
+        let session_1 = Tracker::open(&path).unwrap().with_scope(Scope {
+            session: Some("s1".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(session_1.get_summary().unwrap().total_saved, 50);
+        assert_eq!(session_1.get_all_days().unwrap()[0].commands, 2);
+
+        let future = Tracker::open(&path).unwrap().with_scope(Scope {
+            since: Some((Utc::now() + chrono::Duration::hours(1)).to_rfc3339()),
+            ..Default::default()
+        });
+        assert_eq!(future.get_summary().unwrap().total_commands, 0);
+    }
+
+    // 12. Drill-down percentiles and worst runs for one command
+    #[test]
+    fn test_command_detail() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::open(&dir.path().join("detail.db")).unwrap();
+        for (cmd, input, output) in [
+            ("rtk git log", 100, 10),
+            ("rtk git log", 100, 20),
+            ("rtk git log", 100, 50),
+            ("rtk git log", 100, 90),
+            ("rtk ls", 100, 0),
+        ] {
+            tracker
+                .record("git log", cmd, input, output, 5, Tokenizer::Estimate)
+                .unwrap();
+        }
+
+        let tracker = tracker.with_scope(Scope {
+            command: Some("rtk git*".to_string()),
+            ..Default::default()
+        });
+        let detail = tracker.get_command_detail(2).unwrap().unwrap();
+        assert_eq!(detail.runs, 4);
+        assert_eq!(detail.commands, 1);
+        assert_eq!(detail.savings_p50, 50.0);
+        assert_eq!(detail.savings_p90, 90.0);
+        assert_eq!(detail.total_time_ms, 20);
+        let worst: Vec<f64> = detail.worst.iter().map(|r| r.savings_pct).collect();
+        assert_eq!(worst, [10.0, 50.0]);
+        assert_eq!(tracker.get_summary_top(1).unwrap().by_command.len(), 1);
+
+        let none = tracker.with_scope(Scope {
+            command: Some("rtk cargo*".to_string()),
+            ..Default::default()
+        });
+        assert!(none.get_command_detail(5).unwrap().is_none());
+    }
+
+    // 13. A database from a newer rtk is refused rather than modified
+    #[test]
+    fn test_newer_schema_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("newer.db");
+        drop(Tracker::open(&path).unwrap());
+        Connection::open(&path)
+            .unwrap()
+            .execute(
+                "INSERT INTO schema_version (version, applied_at) VALUES (?1, '')",
+                params![SCHEMA_VERSION + 1],
+            )
+            .unwrap();
+
+        let err = Tracker::open(&path).err().unwrap();
+        assert!(format!("{:#}", err).contains("newer rtk"));
+    }
+
+    // 14. Bundle rows round-trip into a team database; re-imports are skipped
+    #[test]
+    fn test_bundle_import_dedup() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = Tracker::open(&dir.path().join("local.db")).unwrap();
+        for ts in ["2026-02-01T10:00:00+00:00", "2026-02-02T10:00:00+00:00"] {
+            local
+                .conn
+                .execute(
+                    "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, project_root)
+                     VALUES (?1, 'ls', 'rtk ls', 100, 20, 80, 80.0, '/repo/a')",
+                    params![ts],
+                )
+                .unwrap();
+        }
+        let rows = local.get_bundle_rows("laptop", "sam").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].host, "laptop");
+        assert_eq!(rows[0].project_root.as_deref(), Some("/repo/a"));
+
+        let line = serde_json::to_string(&rows[0]).unwrap();
+        let parsed: BundleRow = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.timestamp, rows[0].timestamp);
+
+        let mut team = Tracker::open(&dir.path().join("team.db")).unwrap();
+        assert_eq!(team.import_bundle(&rows).unwrap(), 2);
+        assert_eq!(team.import_bundle(&rows).unwrap(), 0);
+
+        // Same runs from another machine are distinct
+        let other = local.get_bundle_rows("desktop", "sam").unwrap();
+        assert_eq!(team.import_bundle(&other).unwrap(), 2);
+        assert_eq!(team.get_summary().unwrap().total_commands, 4);
+
+        // Re-exporting the team database keeps the imported labels
+        let again = team.get_bundle_rows("ci", "bot").unwrap();
+        assert!(again.iter().all(|r| r.host != "ci"));
+    }
 }
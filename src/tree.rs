@@ -3,48 +3,21 @@
 //! This module proxies to the native `tree` command and filters the output
 //! to reduce token usage while preserving structure visibility.
 //!
-//! Token optimization: automatically excludes noise directories via -I pattern
+//! Token optimization: automatically excludes `filters.ignore_dirs` and
+//! `filters.ignore_files` from config via -I pattern
 //! unless -a flag is present (respecting user intent).
 
+use crate::context;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
 
-/// Noise directories commonly excluded from LLM context
-const NOISE_DIRS: &[&str] = &[
-    "node_modules",
-    ".git",
-    "target",
-    "__pycache__",
-    ".next",
-    "dist",
-    "build",
-    ".cache",
-    ".turbo",
-    ".vercel",
-    ".pytest_cache",
-    ".mypy_cache",
-    ".tox",
-    ".venv",
-    "venv",
-    "env",
-    ".env",
-    "coverage",
-    ".nyc_output",
-    ".DS_Store",
-    "Thumbs.db",
-    ".idea",
-    ".vscode",
-    ".vs",
-    "*.egg-info",
-    ".eggs",
-];
-
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Check if tree is installed
-    let tree_check = Command::new("which").arg("tree").output();
+    let tree_check = Command::new("which").arg("tree").output_timed();
     if tree_check.is_err() || !tree_check.unwrap().status.success() {
         anyhow::bail!(
             "tree command not found. Install it first:\n\
@@ -63,7 +36,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     // Auto-inject -I pattern unless user wants all or already specified -I
     if !show_all && !has_ignore {
-        let ignore_pattern = NOISE_DIRS.join("|");
+        let ctx = context::get();
+        let ignore_pattern = ignore_pattern(&ctx.ignore_dirs, &ctx.ignore_files);
         cmd.arg("-I").arg(&ignore_pattern);
     }
 
@@ -72,19 +46,19 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run tree")?;
+    let output = cmd.output_timed().context("Failed to run tree")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        render_eprint!("{}", stderr);
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let filtered = filter_tree_output(&raw);
 
     if verbose > 0 {
-        eprintln!(
+        render_eprintln!(
             "Lines: {} → {} ({}% reduction)",
             raw.lines().count(),
             filtered.lines().count(),
@@ -96,12 +70,21 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         );
     }
 
-    print!("{}", filtered);
+    render_print!("{}", filtered);
     timer.track("tree", "rtk tree", &raw, &filtered);
 
     Ok(())
 }
 
+/// `-I` pattern for tree from the configured ignore lists
+fn ignore_pattern(dirs: &[String], files: &[String]) -> String {
+    dirs.iter()
+        .chain(files)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
 fn filter_tree_output(raw: &str) -> String {
     let lines: Vec<&str> = raw.lines().collect();
 
@@ -196,14 +179,22 @@ mod tests {
     }
 
     #[test]
-    fn test_noise_dirs_constant() {
-        // Verify NOISE_DIRS contains expected patterns
-        assert!(NOISE_DIRS.contains(&"node_modules"));
-        assert!(NOISE_DIRS.contains(&".git"));
-        assert!(NOISE_DIRS.contains(&"target"));
-        assert!(NOISE_DIRS.contains(&"__pycache__"));
-        assert!(NOISE_DIRS.contains(&".next"));
-        assert!(NOISE_DIRS.contains(&"dist"));
-        assert!(NOISE_DIRS.contains(&"build"));
+    fn test_ignore_pattern_defaults() {
+        // Default ignore lists cover the usual noise directories
+        let filters = crate::config::FilterConfig::default();
+        let pattern = ignore_pattern(&filters.ignore_dirs, &filters.ignore_files);
+        let parts: Vec<&str> = pattern.split('|').collect();
+        for dir in [
+            "node_modules",
+            ".git",
+            "target",
+            "__pycache__",
+            ".next",
+            "dist",
+            "build",
+        ] {
+            assert!(parts.contains(&dir), "missing {}", dir);
+        }
+        assert!(parts.contains(&"*.lock"));
     }
 }
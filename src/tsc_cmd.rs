@@ -1,3 +1,8 @@
+use crate::context;
+use crate::delta;
+use crate::parser::{self, truncate_output, LintIssue, LintResult, LintSeverity, ParseResult};
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -11,7 +16,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     // Try tsc directly first, fallback to npx if not found
     let tsc_exists = Command::new("which")
         .arg("tsc")
-        .output()
+        .output_timed()
         .map(|o| o.status.success())
         .unwrap_or(false);
 
@@ -29,19 +34,35 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     if verbose > 0 {
         let tool = if tsc_exists { "tsc" } else { "npx tsc" };
-        eprintln!("Running: {} {}", tool, args.join(" "));
+        render_eprintln!("Running: {} {}", tool, args.join(" "));
     }
 
     let output = cmd
-        .output()
+        .output_timed()
         .context("Failed to run tsc (try: npm install -g typescript)")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
+    let exit_code = output.status.code().unwrap_or(1);
+
+    if parser::json_requested() {
+        let json = parser::print_json("tsc", parse_tsc_output(&raw, exit_code), exit_code);
+        timer.track(
+            &format!("tsc {}", args.join(" ")),
+            &format!("rtk tsc {}", args.join(" ")),
+            &raw,
+            &json,
+        );
+        crate::utils::exit(exit_code);
+    }
 
-    let filtered = filter_tsc_output(&raw);
+    let filtered = delta::report(&format!("tsc {}", args.join(" ")), || {
+        parse_tsc_output(&raw, exit_code)
+    })
+    .unwrap_or_else(|| filter_tsc_output(&raw));
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint(&format!("tsc {}", args.join(" ")), &raw, &filtered);
 
     timer.track(
         &format!("tsc {}", args.join(" ")),
@@ -51,26 +72,28 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve tsc exit code for CI/CD compatibility
-    std::process::exit(output.status.code().unwrap_or(1));
+    crate::utils::exit_on_failure(exit_code, &raw, &filtered);
+    Ok(())
 }
 
-/// Filter TypeScript compiler output - group errors by file, show every error
-fn filter_tsc_output(output: &str) -> String {
-    lazy_static::lazy_static! {
-        // Pattern: src/file.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
-        static ref TSC_ERROR: Regex = Regex::new(
-            r"^(.+?)\((\d+),(\d+)\):\s+(error|warning)\s+(TS\d+):\s+(.+)$"
-        ).unwrap();
-    }
+lazy_static::lazy_static! {
+    // Pattern: src/file.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
+    static ref TSC_ERROR: Regex = Regex::new(
+        r"^(.+?)\((\d+),(\d+)\):\s+(error|warning)\s+(TS\d+):\s+(.+)$"
+    ).unwrap();
+}
 
-    struct TsError {
-        file: String,
-        line: usize,
-        code: String,
-        message: String,
-        context_lines: Vec<String>,
-    }
+struct TsError {
+    file: String,
+    line: usize,
+    column: usize,
+    severity: LintSeverity,
+    code: String,
+    message: String,
+    context_lines: Vec<String>,
+}
 
+fn parse_tsc_errors(output: &str) -> Vec<TsError> {
     let mut errors: Vec<TsError> = Vec::new();
     let lines: Vec<&str> = output.lines().collect();
     let mut i = 0;
@@ -81,6 +104,12 @@ fn filter_tsc_output(output: &str) -> String {
             let mut err = TsError {
                 file: caps[1].to_string(),
                 line: caps[2].parse().unwrap_or(0),
+                column: caps[3].parse().unwrap_or(0),
+                severity: if &caps[4] == "error" {
+                    LintSeverity::Error
+                } else {
+                    LintSeverity::Warning
+                },
                 code: caps[5].to_string(),
                 message: caps[6].to_string(),
                 context_lines: Vec::new(),
@@ -107,6 +136,38 @@ fn filter_tsc_output(output: &str) -> String {
         }
     }
 
+    errors
+}
+
+/// Canonical result for `--format json`
+fn parse_tsc_output(output: &str, exit_code: i32) -> ParseResult<LintResult> {
+    let issues: Vec<LintIssue> = parse_tsc_errors(output)
+        .into_iter()
+        .map(|e| LintIssue {
+            file_path: e.file,
+            line: e.line,
+            column: e.column,
+            severity: e.severity,
+            rule_id: e.code,
+            message: std::iter::once(e.message)
+                .chain(e.context_lines)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        })
+        .collect();
+    let result = LintResult::from_issues(issues);
+
+    if result.issues.is_empty() && exit_code != 0 {
+        // tsc failed without any diagnostic we recognize (bad flag, missing tsconfig)
+        return ParseResult::Passthrough(truncate_output(output.trim(), 2000));
+    }
+    ParseResult::Full(result)
+}
+
+/// Filter TypeScript compiler output - group errors by file, show every error
+fn filter_tsc_output(output: &str) -> String {
+    let errors = parse_tsc_errors(output);
+
     if errors.is_empty() {
         if output.contains("Found 0 errors") {
             return "✓ TypeScript: No errors found".to_string();
@@ -160,10 +221,10 @@ fn filter_tsc_output(output: &str) -> String {
                 "  L{}: {} {}\n",
                 err.line,
                 err.code,
-                truncate(&err.message, 120)
+                truncate(&err.message, context::width(120))
             ));
             for ctx in &err.context_lines {
-                result.push_str(&format!("    {}\n", truncate(ctx, 120)));
+                result.push_str(&format!("    {}\n", truncate(ctx, context::width(120))));
             }
         }
         result.push('\n');
@@ -245,6 +306,32 @@ src/app.tsx(20,5): error TS2345: Argument of type 'number' is not assignable to
         }
     }
 
+    #[test]
+    fn test_parse_tsc_output_canonical() {
+        let output = "\
+src/app.tsx(10,3): error TS2322: Type '{ children: Element; }' is not assignable to type 'Props'.
+  Property 'children' does not exist on type 'Props'.
+src/api.ts(20,5): error TS2345: Argument of type 'number' is not assignable to parameter of type 'string'.
+";
+        let result = parse_tsc_output(output, 2);
+        assert_eq!(result.tier(), 1);
+        let lint = result.unwrap();
+        assert_eq!(lint.total_issues, 2);
+        assert_eq!(lint.errors, 2);
+        assert_eq!(lint.files_with_issues, 2);
+        assert_eq!(lint.issues[0].file_path, "src/app.tsx");
+        assert_eq!(lint.issues[0].line, 10);
+        assert_eq!(lint.issues[0].column, 3);
+        assert_eq!(lint.issues[0].rule_id, "TS2322");
+        assert!(lint.issues[0].message.contains("Property 'children'"));
+    }
+
+    #[test]
+    fn test_parse_tsc_output_unrecognized_failure() {
+        let result = parse_tsc_output("error: tsconfig.json not found", 1);
+        assert_eq!(result.tier(), 3);
+    }
+
     #[test]
     fn test_filter_no_errors() {
         let output = "Found 0 errors. Watching for file changes.";
@@ -7,6 +7,7 @@
 
 use anyhow::{Context, Result};
 use regex::Regex;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Tronque une chaîne à `max_len` caractères avec "..." si nécessaire.
@@ -33,6 +34,84 @@ pub fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Raw lines shown when a failing command's filtered output has no error line
+pub const RAW_TAIL_LINES: usize = 20;
+
+/// Exit with `code` after printing the `--max-tokens` footer, if output was cut,
+/// and an `[exit N]` marker when `code` is nonzero. After a `--timeout` kill the
+/// run is labeled as timed out and the code is [`crate::timeout::EXIT_CODE`].
+///
+/// Use instead of `std::process::exit` so none of these is ever lost.
+pub fn exit(code: i32) -> ! {
+    let timed_out = crate::timeout::expired();
+    let code = if timed_out {
+        crate::timeout::EXIT_CODE
+    } else {
+        code
+    };
+    crate::budget::finish();
+    if !crate::parser::json_requested() {
+        if timed_out {
+            crate::budget::print_footer(&crate::timeout::label());
+        }
+        if code != 0 {
+            crate::budget::print_footer(&format!("[exit {}]", code));
+        }
+    }
+    std::process::exit(code)
+}
+
+/// Return if `code` is zero, otherwise exit with it.
+///
+/// Safety net for filters: when `filtered` has no error line, the last
+/// [`RAW_TAIL_LINES`] lines of `raw` are printed first, so a failure is never
+/// shown as a clean result.
+pub fn exit_on_failure(code: i32, raw: &str, filtered: &str) {
+    if code == 0 && !crate::timeout::expired() {
+        return;
+    }
+    if !crate::parser::json_requested() {
+        if let Some(tail) = failure_tail(raw, filtered) {
+            crate::budget::print_footer(&tail);
+        }
+    }
+    exit(code)
+}
+
+/// Raw tail to show for a failed command, unless `filtered` already explains
+/// the failure or ends with the same output (passthrough)
+fn failure_tail(raw: &str, filtered: &str) -> Option<String> {
+    if has_error_lines(filtered) {
+        return None;
+    }
+    let lines: Vec<&str> = raw.lines().filter(|l| !l.trim().is_empty()).collect();
+    let last = lines.last()?;
+    if filtered.contains(last.trim()) {
+        return None;
+    }
+    let tail = &lines[lines.len().saturating_sub(RAW_TAIL_LINES)..];
+    Some(format!(
+        "[rtk: command failed; last {} raw lines]\n{}",
+        tail.len(),
+        tail.join("\n")
+    ))
+}
+
+/// True if any line reports an error or failure (and not "0 errors"). Warnings
+/// and issue counts do not count: they rarely explain why a command failed.
+pub fn has_error_lines(text: &str) -> bool {
+    lazy_static::lazy_static! {
+        static ref ERROR_LINE: Regex = Regex::new(
+            r"(?i)\b(errors?|fail|failed|failures?|failing|fatal|panic|panicked|exception|traceback)\b|❌|✗|✕"
+        )
+        .unwrap();
+        static ref NONE_FOUND: Regex =
+            Regex::new(r"(?i)\b(0|no) (errors?|failures?|failed|warnings?|issues?)\b").unwrap();
+    }
+    text.lines()
+        .any(|line| ERROR_LINE.is_match(line) && !NONE_FOUND.is_match(line))
+}
+
 /// Supprime les codes ANSI d'une chaîne (couleurs, styles).
 ///
 /// # Arguments
@@ -225,10 +304,149 @@ pub fn package_manager_exec(tool: &str) -> Command {
     }
 }
 
+/// Match a filename against a glob pattern (supports `*` and `?`).
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    glob_match_inner(pattern.as_bytes(), name.as_bytes())
+}
+
+fn glob_match_inner(pat: &[u8], name: &[u8]) -> bool {
+    match (pat.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            // '*' matches zero or more characters
+            glob_match_inner(&pat[1..], name)
+                || (!name.is_empty() && glob_match_inner(pat, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match_inner(&pat[1..], &name[1..]),
+        (Some(&p), Some(&n)) if p == n => glob_match_inner(&pat[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// Shells an agent may start for each command it runs
+#[cfg(unix)]
+const SHELLS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "fish", "ksh", "mksh", "tcsh", "csh", "busybox",
+];
+
+/// Agent session for per-session state: `RTK_SESSION_ID`, else the nearest
+/// ancestor that is not a shell, identified by pid and start time so a reused
+/// pid is a new session. Agents that start a fresh shell per command keep the
+/// same session this way. `None` when neither is known.
+pub fn session_id() -> Option<String> {
+    if let Some(id) = env_session_id() {
+        return Some(id);
+    }
+    #[cfg(unix)]
+    {
+        let pid = session_process(std::os::unix::process::parent_id());
+        process_info(pid).map(|(_, _, start)| format!("pid-{}-{}", pid, start))
+    }
+    #[cfg(not(unix))]
+    None
+}
+
+/// Walk up from `pid` past shells (`sh -c` wrappers, login shells)
+#[cfg(unix)]
+fn session_process(mut pid: u32) -> u32 {
+    // Bounded: nested shells deeper than this are not worth following
+    for _ in 0..8 {
+        match process_info(pid) {
+            Some((name, ppid, _)) if is_shell(&name) && ppid > 1 => pid = ppid,
+            _ => break,
+        }
+    }
+    pid
+}
+
+/// `bash`, `/bin/zsh` or `-zsh` (login shell)
+#[cfg(unix)]
+fn is_shell(name: &str) -> bool {
+    let name = name.rsplit('/').next().unwrap_or(name);
+    SHELLS.contains(&name.trim_start_matches('-'))
+}
+
+/// Name, parent pid and start time of process `pid`: from /proc (start in
+/// clock ticks since boot), else `ps` (start date)
+#[cfg(unix)]
+fn process_info(pid: u32) -> Option<(String, u32, String)> {
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        // The command name in field 2 may contain spaces and parentheses
+        let open = stat.find('(')?;
+        let close = stat.rfind(')')?;
+        let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+        // Fields 4 (ppid) and 22 (start time)
+        return Some((
+            stat[open + 1..close].to_string(),
+            fields.get(1)?.parse().ok()?,
+            fields.get(19)?.to_string(),
+        ));
+    }
+    let output = Command::new("ps")
+        .args(["-o", "ppid=,lstart=,comm=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    // lstart is five words: "Mon Oct 17 10:00:00 2026"
+    let start = words.get(1..6)?.join("_");
+    Some((words[6..].join(" "), words[0].parse().ok()?, start))
+}
+
+/// 64-bit FNV-1a over `parts`, each followed by a NUL. Unlike `DefaultHasher`
+/// it is the same across Rust releases, so it can name files kept on disk.
+pub fn stable_hash(parts: &[&str]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = OFFSET;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Session id set explicitly through `RTK_SESSION_ID`
+pub fn env_session_id() -> Option<String> {
+    std::env::var("RTK_SESSION_ID")
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Project a command runs in: the enclosing git toplevel, else the working directory
+pub fn project_root() -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    project_root_of(&cwd)
+}
+
+/// Nearest ancestor of `dir` holding `.git` (a directory, or a file for
+/// worktrees and submodules), else `dir` itself
+pub fn project_root_of(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|d| d.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_process_info_stable() {
+        let pid = std::process::id();
+        let info = process_info(pid).unwrap();
+        assert_eq!(info.1, std::os::unix::process::parent_id());
+        assert_eq!(process_info(pid), Some(info));
+    }
+
     #[test]
     fn test_truncate_short_string() {
         assert_eq!(truncate("hello", 10), "hello");
@@ -396,4 +614,73 @@ mod tests {
         assert!(result.ends_with("..."));
     }
 
+    #[test]
+    fn test_has_error_lines() {
+        assert!(has_error_lines("error[E0425]: cannot find value `x`"));
+        assert!(has_error_lines("FAILED tests/test_a.py::test_x"));
+        assert!(has_error_lines(
+            "cargo build: 1 errors, 2 warnings (3 crates)"
+        ));
+        assert!(!has_error_lines("ruff: 3 issues in 2 files"));
+        assert!(!has_error_lines("cargo clippy: 0 errors, 4 warnings"));
+        assert!(!has_error_lines("✓ cargo build (0 errors, 0 warnings)"));
+        assert!(!has_error_lines("ok ✓ 2 files changed"));
+    }
+
+    #[test]
+    fn test_failure_tail() {
+        let raw: String = (1..=30).map(|i| format!("line {}\n", i)).collect();
+        let tail = failure_tail(&raw, "ok ✓").unwrap();
+        assert!(tail.starts_with("[rtk: command failed; last 20 raw lines]"));
+        assert!(tail.contains("line 11\n"));
+        assert!(tail.ends_with("line 30"));
+        assert!(!tail.contains("line 10\n"));
+
+        assert!(failure_tail(&raw, "error: build failed").is_none());
+        // A warning summary does not explain the failure: the raw tail is shown
+        let raw = "warning: unused import\nld: cannot find -lssl\n";
+        let tail = failure_tail(raw, "cargo build: 0 errors, 1 warnings").unwrap();
+        assert!(tail.contains("ld: cannot find -lssl"));
+        assert!(failure_tail("\n\n", "ok ✓").is_none());
+        assert!(failure_tail("building\nexit status 2\n", "building\nexit status 2").is_none());
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        // Fixed values: keys written by one build must match the next
+        assert_eq!(stable_hash(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(&["a"]), 0x089b_e207_b544_f1e4);
+        assert_ne!(stable_hash(&["ab", "c"]), stable_hash(&["a", "bc"]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_process_skips_shells() {
+        assert!(is_shell("bash"));
+        assert!(is_shell("-zsh"));
+        assert!(is_shell("/bin/sh"));
+        assert!(!is_shell("claude"));
+        assert!(!is_shell("node"));
+
+        // `; true` keeps sh from exec'ing sleep in its place
+        let mut shell = Command::new("sh")
+            .args(["-c", "sleep 30; true"])
+            .spawn()
+            .unwrap();
+        let found = session_process(shell.id());
+        shell.kill().unwrap();
+        shell.wait().unwrap();
+        assert_eq!(found, std::process::id());
+    }
+
+    #[test]
+    fn test_project_root_of() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("repo").join("src").join("bin");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(project_root_of(&nested), nested);
+
+        std::fs::create_dir(dir.path().join("repo").join(".git")).unwrap();
+        assert_eq!(project_root_of(&nested), dir.path().join("repo"));
+    }
 }
@@ -2,10 +2,13 @@ use anyhow::{Context, Result};
 use regex::Regex;
 use serde::Deserialize;
 
+use crate::delta;
 use crate::parser::{
-    emit_degradation_warning, emit_passthrough_warning, extract_json_object, truncate_output,
+    self, emit_degradation_warning, emit_passthrough_warning, extract_json_object, truncate_output,
     FormatMode, OutputParser, ParseResult, TestFailure, TestResult, TokenFormatter,
 };
+use crate::tee;
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use crate::utils::{package_manager_exec, strip_ansi};
 
@@ -232,40 +235,53 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
         cmd.arg(arg);
     }
 
-    let output = cmd.output().context("Failed to run vitest")?;
+    let output = cmd.output_timed().context("Failed to run vitest")?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
 
     // Parse output using VitestParser
     let parse_result = VitestParser::parse(&stdout);
+    parse_result.note();
+
+    if parser::json_requested() {
+        let exit_code = output.status.code().unwrap_or(1);
+        let json = parser::print_json("vitest", parse_result, exit_code);
+        timer.track("vitest run", "rtk vitest run", &combined, &json);
+        crate::utils::exit(exit_code);
+    }
+
     let mode = FormatMode::from_verbosity(verbose);
 
-    let filtered = match parse_result {
-        ParseResult::Full(data) => {
-            if verbose > 0 {
-                eprintln!("vitest run (Tier 1: Full JSON parse)");
+    let label = format!("vitest run {}", args.join(" "));
+    let filtered =
+        delta::report_parsed(&label, &parse_result).unwrap_or_else(|| match parse_result {
+            ParseResult::Full(data) => {
+                if verbose > 0 {
+                    render_eprintln!("vitest run (Tier 1: Full JSON parse)");
+                }
+                data.format(mode)
             }
-            data.format(mode)
-        }
-        ParseResult::Degraded(data, warnings) => {
-            if verbose > 0 {
-                emit_degradation_warning("vitest", &warnings.join(", "));
+            ParseResult::Degraded(data, warnings) => {
+                if verbose > 0 {
+                    emit_degradation_warning("vitest", &warnings.join(", "));
+                }
+                data.format(mode)
             }
-            data.format(mode)
-        }
-        ParseResult::Passthrough(raw) => {
-            emit_passthrough_warning("vitest", "All parsing tiers failed");
-            raw
-        }
-    };
+            ParseResult::Passthrough(raw) => {
+                emit_passthrough_warning("vitest", "All parsing tiers failed");
+                raw
+            }
+        });
 
-    println!("{}", filtered);
+    render_println!("{}", filtered);
+    tee::print_hint("vitest run", &combined, &filtered);
 
     timer.track("vitest run", "rtk vitest run", &combined, &filtered);
 
     // Propagate original exit code
-    std::process::exit(output.status.code().unwrap_or(1))
+    crate::utils::exit_on_failure(output.status.code().unwrap_or(1), &combined, &filtered);
+    Ok(())
 }
 
 #[cfg(test)]
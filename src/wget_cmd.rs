@@ -1,3 +1,4 @@
+use crate::timeout::TimedCommand;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -7,7 +8,7 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("wget: {}", url);
+        render_eprintln!("wget: {}", url);
     }
 
     // Run wget normally but capture output to parse it
@@ -21,7 +22,7 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
 
     let output = Command::new("wget")
         .args(&cmd_args)
-        .output()
+        .output_timed()
         .context("Failed to run wget")?;
 
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -38,13 +39,14 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
             filename,
             format_size(size)
         );
-        println!("{}", msg);
+        render_println!("{}", msg);
         timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
     } else {
         let error = parse_error(&stderr, &stdout);
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
-        println!("{}", msg);
+        render_println!("{}", msg);
         timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())
@@ -55,7 +57,7 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("wget: {} -> stdout", url);
+        render_eprintln!("wget: {} -> stdout", url);
     }
 
     let mut cmd_args = vec!["-q", "-O", "-"];
@@ -66,7 +68,7 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
 
     let output = Command::new("wget")
         .args(&cmd_args)
-        .output()
+        .output_timed()
         .context("Failed to run wget")?;
 
     if output.status.success() {
@@ -94,7 +96,7 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
                 rtk_output.push_str(&format!("{}\n", line));
             }
         }
-        print!("{}", rtk_output);
+        render_print!("{}", rtk_output);
         timer.track(
             &format!("wget -O - {}", url),
             "rtk wget -o",
@@ -105,8 +107,9 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let error = parse_error(&stderr, "");
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
-        println!("{}", msg);
+        render_println!("{}", msg);
         timer.track(&format!("wget -O - {}", url), "rtk wget -o", &stderr, &msg);
+        crate::utils::exit(output.status.code().unwrap_or(1));
     }
 
     Ok(())